@@ -0,0 +1,273 @@
+//! Tamper-evident hash chain over `access_log`.
+//!
+//! Each row stores the hash of the previous row (`prev_hash`) and a keyed
+//! hash of its own fields chained onto it (`hash`). The key is the vault
+//! secret, which lives outside the database, so rows edited or removed with a
+//! SQLite editor cannot be re-hashed. The anchor (start of the chain) and the
+//! head (last row) are kept in settings, each with its own MAC, so that
+//! truncation at either end is detected too. Hashes cover the decrypted
+//! fields, so encrypted payloads swapped between rows break the chain as well.
+//!
+//! Compaction removes a prefix of the chain and re-anchors it on the hash of
+//! the last removed row, so the remaining rows still verify.
+
+use crate::crypto;
+use crate::db::AccessLogEntry;
+use serde::{Deserialize, Serialize};
+
+pub const ANCHOR_SETTING_KEY: &str = "access_log_anchor";
+pub const HEAD_SETTING_KEY: &str = "access_log_head";
+
+/// Keyed hash of `entry` chained onto `prev_hash`.
+pub fn entry_hash(key: &str, prev_hash: &str, entry: &AccessLogEntry) -> String {
+    crypto::hmac_sign(&entry_hash_data(prev_hash, entry), key)
+}
+
+/// Start of the chain: the first row's `prev_hash` must equal `genesis`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainAnchor {
+    pub genesis: String,
+    pub created_at: i64,
+    pub mac: String,
+}
+
+impl ChainAnchor {
+    pub fn new(key: &str, created_at: i64) -> Self {
+        Self::at(key, &crypto::generate_key(), created_at)
+    }
+
+    /// Anchor on a known hash, used when older rows are compacted away.
+    pub fn at(key: &str, genesis: &str, created_at: i64) -> Self {
+        let mac = crypto::hmac_sign(&Self::mac_data(genesis, created_at), key);
+        ChainAnchor { genesis: genesis.to_string(), created_at, mac }
+    }
+
+    pub fn is_authentic(&self, key: &str) -> bool {
+        crypto::hmac_verify(&Self::mac_data(&self.genesis, self.created_at), key, &self.mac)
+    }
+
+    fn mac_data(genesis: &str, created_at: i64) -> String {
+        format!("anchor|{}|{}", genesis, created_at)
+    }
+}
+
+/// Last row of the chain, updated with every logged event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainHead {
+    pub last_id: i64,
+    pub hash: String,
+    pub mac: String,
+}
+
+impl ChainHead {
+    pub fn new(key: &str, last_id: i64, hash: &str) -> Self {
+        let mac = crypto::hmac_sign(&Self::mac_data(last_id, hash), key);
+        ChainHead { last_id, hash: hash.to_string(), mac }
+    }
+
+    pub fn is_authentic(&self, key: &str) -> bool {
+        crypto::hmac_verify(&Self::mac_data(self.last_id, &self.hash), key, &self.mac)
+    }
+
+    fn mac_data(last_id: i64, hash: &str) -> String {
+        format!("head|{}|{}", last_id, hash)
+    }
+}
+
+/// Result of `verify_access_log`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainReport {
+    pub valid: bool,
+    pub entries_checked: i64,
+    /// The anchor is present and carries a valid MAC.
+    pub anchor_valid: bool,
+    /// First row whose hash does not match its fields or whose link to the
+    /// previous row is broken (row edited, inserted or deleted before it).
+    pub first_broken_id: Option<i64>,
+    /// Rows are missing before the first remaining row.
+    pub truncated_start: bool,
+    /// Rows are missing after the last remaining row.
+    pub truncated_end: bool,
+}
+
+/// Walks the chain row by row, in ascending id order.
+pub struct ChainVerifier<'a> {
+    key: &'a str,
+    anchor: Option<ChainAnchor>,
+    head: Option<ChainHead>,
+    report: ChainReport,
+    last: Option<(i64, String)>,
+}
+
+impl<'a> ChainVerifier<'a> {
+    pub fn new(key: &'a str, anchor: Option<ChainAnchor>, head: Option<ChainHead>) -> Self {
+        let anchor = anchor.filter(|a| a.is_authentic(key));
+        let head = head.filter(|h| h.is_authentic(key));
+        ChainVerifier {
+            key,
+            report: ChainReport {
+                valid: false,
+                entries_checked: 0,
+                anchor_valid: anchor.is_some(),
+                first_broken_id: None,
+                truncated_start: false,
+                truncated_end: false,
+            },
+            anchor,
+            head,
+            last: None,
+        }
+    }
+
+    /// Checks one row. `prev_hash` and `hash` are the stored values, `None` if missing.
+    pub fn push(&mut self, entry: &AccessLogEntry, prev_hash: Option<&str>, hash: Option<&str>) {
+        let prev_hash = prev_hash.unwrap_or("");
+        let hash = hash.unwrap_or("");
+
+        match &self.last {
+            None => {
+                if self.anchor.as_ref().is_some_and(|a| a.genesis != prev_hash) {
+                    self.report.truncated_start = true;
+                }
+            }
+            Some((_, last_hash)) => {
+                if last_hash != prev_hash {
+                    self.mark_broken(entry.id);
+                }
+            }
+        }
+        if !crypto::hmac_verify(&entry_hash_data(prev_hash, entry), self.key, hash) {
+            self.mark_broken(entry.id);
+        }
+
+        self.report.entries_checked += 1;
+        self.last = Some((entry.id, hash.to_string()));
+    }
+
+    /// Checks a row whose fields could not be read, e.g. a payload that no
+    /// longer decrypts. It cannot match its hash, so it breaks the chain.
+    pub fn push_unreadable(&mut self, id: i64, hash: Option<&str>) {
+        self.mark_broken(id);
+        self.report.entries_checked += 1;
+        self.last = Some((id, hash.unwrap_or("").to_string()));
+    }
+
+    pub fn finish(mut self) -> ChainReport {
+        self.report.truncated_end = match (&self.head, &self.last) {
+            (Some(head), Some((id, hash))) => head.last_id != *id || head.hash != *hash,
+            // Every row was compacted away: the head is where the anchor now starts.
+            (Some(head), None) => self.anchor.as_ref().is_none_or(|a| a.genesis != head.hash),
+            // A chain with rows but no authentic head cannot prove its end.
+            (None, Some(_)) => true,
+            (None, None) => false,
+        };
+        self.report.valid = self.report.anchor_valid
+            && self.report.first_broken_id.is_none()
+            && !self.report.truncated_start
+            && !self.report.truncated_end;
+        self.report
+    }
+
+    fn mark_broken(&mut self, id: i64) {
+        self.report.first_broken_id.get_or_insert(id);
+    }
+}
+
+fn entry_hash_data(prev_hash: &str, entry: &AccessLogEntry) -> String {
+    let mut data = format!(
+        "{}|{}|{}|{}|{}",
+        prev_hash, entry.id, entry.lockbox_id, entry.event_type, entry.timestamp
+    );
+    // Appended only when present so rows chained before details existed keep their hashes.
+    if let Some(details) = &entry.details {
+        data.push('|');
+        data.push_str(&details.to_string());
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "test-vault-key";
+
+    fn chain(anchor: &ChainAnchor, count: i64) -> Vec<(AccessLogEntry, String, String)> {
+        let mut prev = anchor.genesis.clone();
+        (1..=count)
+            .map(|id| {
+                let entry = AccessLogEntry {
+                    id,
+                    lockbox_id: 1,
+                    event_type: "unlock_requested".to_string(),
+                    timestamp: id * 1000,
+                    details: Some(serde_json::json!({ "v": 1, "unlock_delay_seconds": 60 })),
+                };
+                let hash = entry_hash(KEY, &prev, &entry);
+                let row = (entry, prev.clone(), hash.clone());
+                prev = hash;
+                row
+            })
+            .collect()
+    }
+
+    fn verify(anchor: &ChainAnchor, rows: &[(AccessLogEntry, String, String)], head: ChainHead) -> ChainReport {
+        let mut verifier = ChainVerifier::new(KEY, Some(anchor.clone()), Some(head));
+        for (entry, prev, hash) in rows {
+            verifier.push(entry, Some(prev), Some(hash));
+        }
+        verifier.finish()
+    }
+
+    #[test]
+    fn test_intact_chain_is_valid() {
+        let anchor = ChainAnchor::new(KEY, 0);
+        let rows = chain(&anchor, 3);
+        let head = ChainHead::new(KEY, 3, &rows[2].2);
+
+        let report = verify(&anchor, &rows, head);
+        assert!(report.valid);
+        assert_eq!(report.entries_checked, 3);
+    }
+
+    #[test]
+    fn test_detects_edited_and_deleted_rows() {
+        let anchor = ChainAnchor::new(KEY, 0);
+        let mut rows = chain(&anchor, 4);
+        let head = ChainHead::new(KEY, 4, &rows[3].2);
+
+        rows[1].0.event_type = "unlock_completed".to_string();
+        assert_eq!(verify(&anchor, &rows, head.clone()).first_broken_id, Some(2));
+
+        let mut rows = chain(&anchor, 4);
+        rows.remove(2);
+        assert_eq!(verify(&anchor, &rows, head).first_broken_id, Some(4));
+    }
+
+    #[test]
+    fn test_detects_truncation_at_both_ends() {
+        let anchor = ChainAnchor::new(KEY, 0);
+        let rows = chain(&anchor, 4);
+        let head = ChainHead::new(KEY, 4, &rows[3].2);
+
+        let report = verify(&anchor, &rows[1..], head.clone());
+        assert!(report.truncated_start && !report.truncated_end);
+        assert_eq!(report.first_broken_id, None);
+
+        let report = verify(&anchor, &rows[..3], head);
+        assert!(report.truncated_end && !report.valid);
+    }
+
+    #[test]
+    fn test_reanchored_chain_stays_valid_after_compaction() {
+        let anchor = ChainAnchor::new(KEY, 0);
+        let rows = chain(&anchor, 4);
+        let head = ChainHead::new(KEY, 4, &rows[3].2);
+
+        let compacted = ChainAnchor::at(KEY, &rows[1].2, 10);
+        assert!(verify(&compacted, &rows[2..], head.clone()).valid);
+
+        let emptied = ChainAnchor::at(KEY, &rows[3].2, 10);
+        assert!(verify(&emptied, &[], head).valid);
+    }
+}
@@ -0,0 +1,333 @@
+//! Full-fidelity vault backups.
+//!
+//! Unlike the sharing export (`transfer`), a backup carries the whole vault:
+//! lock state and countdowns, schedules, panic codes, view counters,
+//! timestamps, categories, folders, settings and the access history with its
+//! aggregates. The file is versioned and signed as a whole with HMAC-SHA256
+//! under the master password hash, so a restore either reproduces the vault
+//! faithfully or is refused.
+
+use crate::crypto;
+use crate::db::{AccessLogEntry, Lockbox, LockboxSummary};
+use crate::error::{LockboxError, Result};
+use crate::i18n;
+use crate::identity;
+use crate::retention::{self, MonthlyAggregate};
+use crate::transfer::{self, RollbackGuard};
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Value of `format`, telling backups apart from sharing exports.
+pub const BACKUP_FORMAT: &str = "lockbox-backup";
+
+/// Version of the backup layout written by this build.
+pub const BACKUP_VERSION: i64 = 1;
+
+/// Settings that belong to the vault. Machine-bound state (clock baselines,
+/// access log anchor and head, device key, master password hash) stays behind.
+pub const BACKED_UP_SETTINGS: [&str; 6] = [
+    i18n::LOCALE_SETTING_KEY,
+    retention::RETENTION_SETTING_KEY,
+    validation::LIMITS_SETTING_KEY,
+    transfer::VAULT_ID_SETTING_KEY,
+    transfer::EXPORT_COUNTER_SETTING_KEY,
+    identity::TRUSTED_DEVICES_SETTING_KEY,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub format: String,
+    pub version: i64,
+    pub created_at: i64,
+    pub vault: BackupVault,
+    /// HMAC-SHA256 of every other field, see `signed_data`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// Every row of the vault, ids included, so that access history keeps
+/// pointing at the right lockboxes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupVault {
+    pub lockboxes: Vec<BackupLockbox>,
+    pub tags: Vec<BackupTag>,
+    pub categories: Vec<BackupCategory>,
+    pub folders: Vec<BackupFolder>,
+    pub settings: BTreeMap<String, String>,
+    pub access_log: Vec<AccessLogEntry>,
+    pub aggregates: Vec<MonthlyAggregate>,
+    pub tombstones: Vec<BackupTombstone>,
+    /// Export counter at the last delay raise of each lockbox name.
+    #[serde(default)]
+    pub delay_raises: BTreeMap<String, i64>,
+}
+
+/// A lockbox row as stored, content still encrypted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupLockbox {
+    pub id: i64,
+    pub name: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub is_locked: bool,
+    pub unlock_delay_seconds: i64,
+    pub relock_delay_seconds: i64,
+    pub unlock_timestamp: Option<i64>,
+    pub relock_timestamp: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub reflection_enabled: bool,
+    pub reflection_message: Option<String>,
+    pub reflection_checklist: Option<String>,
+    pub penalty_enabled: bool,
+    pub penalty_seconds: i64,
+    pub panic_code_hash: Option<String>,
+    pub panic_code_used: bool,
+    pub scheduled_unlock_at: Option<i64>,
+    pub tags: Option<String>,
+    pub max_views_per_unlock: Option<i64>,
+    pub views_this_unlock: i64,
+    pub folder_id: Option<i64>,
+}
+
+impl From<Lockbox> for BackupLockbox {
+    fn from(lb: Lockbox) -> Self {
+        BackupLockbox {
+            id: lb.id,
+            name: lb.name,
+            content: lb.content,
+            category: lb.category,
+            is_locked: lb.is_locked,
+            unlock_delay_seconds: lb.unlock_delay_seconds,
+            relock_delay_seconds: lb.relock_delay_seconds,
+            unlock_timestamp: lb.unlock_timestamp,
+            relock_timestamp: lb.relock_timestamp,
+            created_at: lb.created_at,
+            updated_at: lb.updated_at,
+            reflection_enabled: lb.reflection_enabled,
+            reflection_message: lb.reflection_message,
+            reflection_checklist: lb.reflection_checklist,
+            penalty_enabled: lb.penalty_enabled,
+            penalty_seconds: lb.penalty_seconds,
+            panic_code_hash: lb.panic_code_hash,
+            panic_code_used: lb.panic_code_used,
+            scheduled_unlock_at: lb.scheduled_unlock_at,
+            tags: lb.tags,
+            max_views_per_unlock: lb.max_views_per_unlock,
+            views_this_unlock: lb.views_this_unlock,
+            folder_id: lb.folder_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTag {
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupCategory {
+    pub id: i64,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub position: i64,
+    pub default_unlock_delay_seconds: Option<i64>,
+    pub default_relock_delay_seconds: Option<i64>,
+    pub default_penalty_seconds: Option<i64>,
+    pub default_reflection_checklist: Option<String>,
+    pub min_unlock_delay_seconds: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFolder {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Name of a deleted lockbox still referenced by the access history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTombstone {
+    pub id: i64,
+    pub name: Option<String>,
+    pub deleted_at: i64,
+}
+
+/// Outcome of restoring a backup.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub lockboxes: Vec<LockboxSummary>,
+    /// Lockboxes left out because their delays were raised in this vault
+    /// after the backup was taken; restoring them would shorten them again.
+    pub outdated: Vec<String>,
+}
+
+/// Names of the backed-up lockboxes that predate a delay raise of this
+/// vault, given its id and raises. A backup of another vault has no place
+/// in this vault's history and counts as older than every raise.
+pub fn outdated_lockboxes(vault: &BackupVault, vault_id: Option<&str>, raised: HashMap<String, i64>) -> HashSet<String> {
+    let setting = |key: &str| vault.settings.get(key).map(String::as_str);
+    let counter = match (setting(transfer::VAULT_ID_SETTING_KEY), vault_id) {
+        (Some(backed_up), Some(live)) if backed_up == live => {
+            setting(transfer::EXPORT_COUNTER_SETTING_KEY).and_then(|value| value.parse().ok()).unwrap_or_default()
+        }
+        _ => 0,
+    };
+    let guard = RollbackGuard { counter, raised };
+    vault.lockboxes.iter().filter(|lb| guard.predates_raise(&lb.name)).map(|lb| lb.name.clone()).collect()
+}
+
+/// Keys for reading a backup: `source` is the hash of the master password
+/// the backup was made under, when it differs from the current one.
+#[derive(Debug, Clone, Copy)]
+pub struct RestoreKeys<'a> {
+    pub source: Option<&'a str>,
+    pub current: &'a str,
+}
+
+impl RestoreKeys<'_> {
+    fn file_key(&self) -> &str {
+        self.source.unwrap_or(self.current)
+    }
+}
+
+/// Signs `vault` as a new backup taken at `created_at`.
+pub fn create(vault: BackupVault, created_at: i64, key: &str) -> Result<BackupFile> {
+    let mut file = BackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at,
+        vault,
+        signature: None,
+    };
+    let signature = crypto::hmac_sign(&signed_data(serde_json::to_value(&file)?), key);
+    file.signature = Some(signature);
+    Ok(file)
+}
+
+/// Parses and verifies a backup, then prepares it for a restore at `now_ms`:
+/// contents moved to the current password and countdowns carried over.
+pub fn open(data: &str, keys: RestoreKeys, now_ms: i64) -> Result<BackupFile> {
+    let invalid = |detail: String| LockboxError::InvalidImportFormat { detail };
+    let value: Value = serde_json::from_str(data).map_err(|e| invalid(e.to_string()))?;
+    if value.get("format").and_then(Value::as_str) != Some(BACKUP_FORMAT) {
+        return Err(invalid("not a backup file".to_string()));
+    }
+    let version = value.get("version").and_then(Value::as_i64).unwrap_or_default();
+    if version != BACKUP_VERSION {
+        return Err(LockboxError::UnsupportedBackupVersion { version });
+    }
+    let signature = value.get("signature").and_then(Value::as_str).unwrap_or_default().to_string();
+    if !crypto::hmac_verify(&signed_data(value.clone()), keys.file_key(), &signature) {
+        return Err(LockboxError::BackupSignatureInvalid);
+    }
+
+    let mut file: BackupFile = serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+    for lb in &mut file.vault.lockboxes {
+        if let Some(source) = keys.source.filter(|source| *source != keys.current) {
+            let plaintext = crypto::decrypt(&lb.content, source)
+                .map_err(|_| LockboxError::SourceDecryptionFailed { name: lb.name.clone() })?;
+            lb.content = crypto::encrypt(&plaintext, keys.current)?;
+        }
+        carry_over_timers(lb, file.created_at, now_ms);
+    }
+    Ok(file)
+}
+
+/// Moves the timers of a lockbox backed up at `created_at` to a restore at
+/// `now_ms`. A running countdown keeps at least the time it had left, so
+/// neither the time spent in the backup nor a clock ahead on the restoring
+/// machine shortens it. An unlocked lockbox keeps its relock time and is
+/// relocked if that has passed. Scheduled dates are kept as they are.
+pub fn carry_over_timers(lb: &mut BackupLockbox, created_at: i64, now_ms: i64) {
+    if lb.is_locked {
+        lb.unlock_timestamp = lb
+            .unlock_timestamp
+            .map(|at| at.max(now_ms + (at - created_at).max(0)));
+    } else if lb.relock_timestamp.is_some_and(|at| at <= now_ms) {
+        lb.is_locked = true;
+        lb.relock_timestamp = None;
+    }
+}
+
+/// Canonical text of a backup without its signature.
+fn signed_data(mut value: Value) -> String {
+    if let Value::Object(map) = &mut value {
+        map.remove("signature");
+    }
+    crypto::canonical_json(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lockbox(is_locked: bool, unlock_timestamp: Option<i64>, relock_timestamp: Option<i64>) -> BackupLockbox {
+        BackupLockbox::from(Lockbox {
+            id: 1,
+            name: "bank".to_string(),
+            is_locked,
+            unlock_timestamp,
+            relock_timestamp,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_open_rejects_any_edit() {
+        let vault = BackupVault { lockboxes: vec![lockbox(true, None, None)], ..Default::default() };
+        let file = create(vault, 1_000, "key").unwrap();
+        let data = serde_json::to_string(&file).unwrap();
+        let keys = RestoreKeys { source: None, current: "key" };
+        assert_eq!(open(&data, keys, 2_000).unwrap().vault.lockboxes.len(), 1);
+
+        let tampered = data.replace("\"views_this_unlock\":0", "\"views_this_unlock\":1");
+        assert!(matches!(open(&tampered, keys, 2_000), Err(LockboxError::BackupSignatureInvalid)));
+        let other_key = RestoreKeys { source: None, current: "other" };
+        assert!(matches!(open(&data, other_key, 2_000), Err(LockboxError::BackupSignatureInvalid)));
+    }
+
+    #[test]
+    fn test_outdated_lockboxes_predate_a_live_raise() {
+        let mut vault = BackupVault { lockboxes: vec![lockbox(true, None, None)], ..Default::default() };
+        vault.settings.insert(transfer::VAULT_ID_SETTING_KEY.to_string(), "vault".to_string());
+        vault.settings.insert(transfer::EXPORT_COUNTER_SETTING_KEY.to_string(), "4".to_string());
+        let raised = |counter| HashMap::from([("bank".to_string(), counter)]);
+
+        assert!(outdated_lockboxes(&vault, Some("vault"), raised(3)).is_empty());
+        assert!(outdated_lockboxes(&vault, Some("vault"), raised(4)).contains("bank"));
+        // Counters of another vault say nothing about this one's raises
+        assert!(outdated_lockboxes(&vault, Some("other"), raised(3)).contains("bank"));
+        assert!(outdated_lockboxes(&vault, None, HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_carry_over_timers_never_shortens_a_countdown() {
+        // Backed up at 1000 with 500ms left, restored at 5000
+        let mut counting = lockbox(true, Some(1_500), None);
+        carry_over_timers(&mut counting, 1_000, 5_000);
+        assert_eq!(counting.unlock_timestamp, Some(5_500));
+
+        // Restored on a machine whose clock is behind: the original target stands
+        let mut early = lockbox(true, Some(1_500), None);
+        carry_over_timers(&mut early, 1_000, 900);
+        assert_eq!(early.unlock_timestamp, Some(1_500));
+
+        let mut expired = lockbox(false, None, Some(3_000));
+        carry_over_timers(&mut expired, 1_000, 5_000);
+        assert!(expired.is_locked && expired.relock_timestamp.is_none());
+
+        let mut open_window = lockbox(false, None, Some(9_000));
+        carry_over_timers(&mut open_window, 1_000, 5_000);
+        assert!(!open_window.is_locked);
+        assert_eq!(open_window.relock_timestamp, Some(9_000));
+    }
+}
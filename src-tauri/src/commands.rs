@@ -1,123 +1,158 @@
+use crate::audit::ChainReport;
+use crate::backup::{self, RestoreKeys, RestoreReport};
 use crate::crypto;
-use crate::db::{AccessLogEntry, CreateLockboxRequest, Lockbox, UpdateLockboxRequest};
+use crate::db::{
+    AccessLogPage, AccessLogQuery, Category, CategoryPolicies, CategoryRequest, CreateLockboxRequest, Database,
+    Folder, ImportWrite, LockboxDetail, LockboxFilter, LockboxSummary, TagUsage, UpdateLockboxRequest,
+};
+use crate::error::LockboxError;
+use crate::foreign::{self, ClearFormat, ForeignImport};
+use crate::i18n::{self, Locale};
+use crate::identity::{DeviceIdentity, TrustedDevice};
+use crate::portable::{self, KdfParams};
+use crate::retention::{self, CompactionReport, RetentionPolicy};
+use crate::search;
+use crate::snapshot::{self, SnapshotInfo, SnapshotPolicy};
+use crate::stats::{self, StatsReport};
+use crate::transfer::{
+    self, ExportData, ExportLockbox, ImportAction, ImportKeys, ImportReport, ImportStrategies, ImportTarget,
+    ManifestKeys, RollbackGuard,
+};
+use crate::validation::{self, LockboxInput, ValidationLimits};
 use crate::AppState;
-use serde::{Deserialize, Serialize};
-use tauri::State;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExportData {
-    pub version: String,
-    pub exported_at: i64,
-    pub lockboxes: Vec<ExportLockbox>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExportLockbox {
-    pub name: String,
-    pub content: String,
-    pub category: Option<String>,
-    pub unlock_delay_seconds: i64,
-    pub relock_delay_seconds: i64,
-    // Advanced fields (added in v2)
-    #[serde(default)]
-    pub reflection_enabled: bool,
-    #[serde(default)]
-    pub reflection_message: Option<String>,
-    #[serde(default)]
-    pub reflection_checklist: Option<String>,
-    #[serde(default)]
-    pub penalty_enabled: bool,
-    #[serde(default)]
-    pub penalty_seconds: i64,
-    #[serde(default)]
-    pub tags: Option<String>,
-    /// HMAC-SHA256 signature of security-critical fields.
-    /// Prevents tampering with delays / content after export.
-    #[serde(default)]
-    pub signature: Option<String>,
-}
-
-/// Builds the canonical string that is HMAC-signed for a lockbox.
-/// Only security-critical fields are included.
-fn lockbox_sign_data(
-    name: &str,
-    content: &str,
-    unlock_delay_seconds: i64,
-    relock_delay_seconds: i64,
-    penalty_enabled: bool,
-    penalty_seconds: i64,
-) -> String {
-    format!(
-        "{name}|{content}|{unlock}|{relock}|{penalty}|{penalty_sec}",
-        name = name,
-        content = content,
-        unlock = unlock_delay_seconds,
-        relock = relock_delay_seconds,
-        penalty = if penalty_enabled { "1" } else { "0" },
-        penalty_sec = penalty_seconds,
-    )
-}
-
-#[tauri::command]
-pub fn get_all_lockboxes(state: State<AppState>) -> Result<Vec<Lockbox>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_all_lockboxes().map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub fn get_lockbox(id: i64, state: State<AppState>) -> Result<Option<Lockbox>, String> {
-    let mut lockbox = {
-        let db = state.db.lock().map_err(|e| e.to_string())?;
-        db.get_lockbox(id).map_err(|e| e.to_string())?
-    };
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+#[derive(Debug, Serialize)]
+pub struct NotificationText {
+    pub title: String,
+    pub body: String,
+}
+
+/// File of an export in clear or portable, with the number of lockboxes
+/// written and of those left out because they were locked.
+#[derive(Debug, Serialize)]
+pub struct ClearExport {
+    pub data: String,
+    pub exported: usize,
+    pub skipped_locked: usize,
+}
 
+/// All lockboxes, or only those matching `filter` (e.g. any or all of some tags).
+#[tauri::command]
+pub fn get_all_lockboxes(
+    filter: Option<LockboxFilter>,
+    state: State<AppState>,
+) -> Result<Vec<LockboxSummary>, LockboxError> {
+    let db = state.db.lock()?;
+    let lockboxes = db.find_lockboxes(&filter.unwrap_or_default())?;
+    Ok(lockboxes.into_iter().map(LockboxSummary::from).collect())
+}
+
+/// Full-text search over names, categories, tags and reflection text, best
+/// match first. Content is never searched.
+#[tauri::command]
+pub fn search_lockboxes(
+    query: String,
+    limit: Option<u32>,
+    state: State<AppState>,
+) -> Result<Vec<LockboxSummary>, LockboxError> {
+    let db = state.db.lock()?;
+    let lockboxes = db.search_lockboxes(&query, limit.unwrap_or(search::DEFAULT_SEARCH_LIMIT))?;
+    Ok(lockboxes.into_iter().map(LockboxSummary::from).collect())
+}
+
+#[tauri::command]
+pub fn get_lockbox(id: i64, state: State<AppState>) -> Result<Option<LockboxDetail>, LockboxError> {
     let hash_copy = {
-        let password_hash = state.master_password_hash.lock().map_err(|e| e.to_string())?;
+        let password_hash = state.master_password_hash.lock()?;
         password_hash.clone()
     };
 
-    if let (Some(ref mut lb), Some(ref hash)) = (&mut lockbox, &hash_copy) {
-        if !lb.is_locked {
-            if let Ok(decrypted) = crypto::decrypt(&lb.content, hash) {
-                lb.content = decrypted;
-            }
-        }
-    }
+    let db = state.db.lock()?;
+    let Some(lockbox) = db.get_lockbox(id)? else {
+        return Ok(None);
+    };
+
+    // Content only leaves the backend decrypted, for an unlocked box, in an authenticated session.
+    let content = match (&hash_copy, lockbox.is_locked) {
+        (Some(hash), false) => crypto::decrypt(&lockbox.content, hash).ok(),
+        _ => None,
+    };
+
+    // Every delivered view is logged and counts towards the box's view limit.
+    let lockbox = if content.is_some() { db.record_content_view(id)? } else { lockbox };
+
+    Ok(Some(LockboxDetail { summary: LockboxSummary::from(lockbox), content }))
+}
 
-    Ok(lockbox)
+#[tauri::command]
+pub fn record_content_copy(id: i64, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    let lockbox = db.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
+    if lockbox.is_locked {
+        return Err(LockboxError::Locked { id });
+    }
+    db.record_content_copy(id)
 }
 
+/// Creates a lockbox. Delays, penalty and reflection left out come from the
+/// category's default policies.
 #[tauri::command]
 pub fn create_lockbox(
     name: String,
     content: String,
     category: Option<String>,
-    unlock_delay_seconds: i64,
-    relock_delay_seconds: i64,
-    reflection_enabled: bool,
+    unlock_delay_seconds: Option<i64>,
+    relock_delay_seconds: Option<i64>,
+    reflection_enabled: Option<bool>,
     reflection_message: Option<String>,
     reflection_checklist: Option<String>,
-    penalty_enabled: bool,
-    penalty_seconds: i64,
+    penalty_enabled: Option<bool>,
+    penalty_seconds: Option<i64>,
     panic_code: Option<String>,
     scheduled_unlock_at: Option<i64>,
     tags: Option<String>,
+    max_views_per_unlock: Option<i64>,
     state: State<AppState>,
-) -> Result<Lockbox, String> {
+) -> Result<LockboxSummary, LockboxError> {
+    {
+        let db = state.db.lock()?;
+        validation::validate(
+            &LockboxInput {
+                name: Some(&name),
+                content_len: Some(content.len()),
+                category: category.as_deref(),
+                unlock_delay_seconds,
+                relock_delay_seconds,
+                penalty_seconds,
+                reflection_message: reflection_message.as_deref(),
+                reflection_checklist: reflection_checklist.as_deref(),
+                tags: tags.as_deref(),
+                scheduled_unlock_at,
+                max_views_per_unlock,
+            },
+            &ValidationLimits::load(&db)?,
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+    }
+
     let hash_copy = {
-        let password_hash = state.master_password_hash.lock().map_err(|e| e.to_string())?;
+        let password_hash = state.master_password_hash.lock()?;
         password_hash.clone()
     };
 
     let encrypted_content = if let Some(ref hash) = hash_copy {
-        crypto::encrypt(&content, hash).map_err(|e| e.to_string())?
+        crypto::encrypt(&content, hash)?
     } else {
         content
     };
 
     let panic_code_hash = panic_code.map(|c| crypto::hash_password(&c));
 
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let db = state.db.lock()?;
     db.create_lockbox(CreateLockboxRequest {
         name,
         content: encrypted_content,
@@ -132,8 +167,9 @@ pub fn create_lockbox(
         panic_code_hash,
         scheduled_unlock_at,
         tags,
+        max_views_per_unlock,
     })
-    .map_err(|e| e.to_string())
+    .map(LockboxSummary::from)
 }
 
 #[tauri::command]
@@ -156,15 +192,39 @@ pub fn update_lockbox(
     scheduled_unlock_at: Option<i64>,
     tags: Option<String>,
     clear_tags: Option<bool>,
+    max_views_per_unlock: Option<i64>,
+    clear_max_views_per_unlock: Option<bool>,
     state: State<AppState>,
-) -> Result<Lockbox, String> {
+) -> Result<LockboxSummary, LockboxError> {
+    {
+        let db = state.db.lock()?;
+        // Scheduled dates are checked below against the current lock state.
+        validation::validate(
+            &LockboxInput {
+                name: name.as_deref(),
+                content_len: content.as_ref().map(|c| c.len()),
+                category: category.as_deref(),
+                unlock_delay_seconds,
+                relock_delay_seconds,
+                penalty_seconds,
+                reflection_message: reflection_message.as_deref(),
+                reflection_checklist: reflection_checklist.as_deref(),
+                tags: tags.as_deref(),
+                scheduled_unlock_at: None,
+                max_views_per_unlock,
+            },
+            &ValidationLimits::load(&db)?,
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+    }
+
     let encrypted_content = if let Some(c) = content {
         let hash_copy = {
-            let password_hash = state.master_password_hash.lock().map_err(|e| e.to_string())?;
+            let password_hash = state.master_password_hash.lock()?;
             password_hash.clone()
         };
         if let Some(ref hash) = hash_copy {
-            Some(crypto::encrypt(&c, hash).map_err(|e| e.to_string())?)
+            Some(crypto::encrypt(&c, hash)?)
         } else {
             Some(c)
         }
@@ -174,45 +234,63 @@ pub fn update_lockbox(
 
     let panic_code_hash = panic_code.map(|c| crypto::hash_password(&c));
 
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+    let db = state.db.lock()?;
 
     // Validate state-based edit restrictions
-    let current = db.get_lockbox(id).map_err(|e| e.to_string())?
-        .ok_or_else(|| "Lockbox not found".to_string())?;
+    let current = db.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
 
     let now_ms = chrono::Utc::now().timestamp_millis();
     let is_effectively_locked = current.is_locked || current.unlock_timestamp.is_some();
 
     if is_effectively_locked {
         if encrypted_content.is_some() {
-            return Err("Cannot edit content while locked".to_string());
+            return Err(LockboxError::ContentLocked);
         }
         if let Some(new_delay) = unlock_delay_seconds {
             if new_delay < current.unlock_delay_seconds {
-                return Err("Cannot reduce unlock delay while locked".to_string());
+                return Err(LockboxError::UnlockDelayReduction {
+                    current: current.unlock_delay_seconds,
+                    requested: new_delay,
+                });
             }
         }
         if let Some(new_delay) = relock_delay_seconds {
             if new_delay < current.relock_delay_seconds {
-                return Err("Cannot reduce relock delay while locked".to_string());
+                return Err(LockboxError::RelockDelayReduction {
+                    current: current.relock_delay_seconds,
+                    requested: new_delay,
+                });
+            }
+        }
+        if let Some(current_max) = current.max_views_per_unlock {
+            let clearing = clear_max_views_per_unlock.unwrap_or(false);
+            let raising = matches!(max_views_per_unlock, Some(new_max) if new_max > current_max);
+            if clearing || raising {
+                return Err(LockboxError::ViewLimitIncrease {
+                    current: current_max,
+                    requested: if clearing { None } else { max_views_per_unlock },
+                });
             }
         }
         if panic_code_hash.is_some() {
-            return Err("Cannot change emergency code while locked".to_string());
+            return Err(LockboxError::PanicCodeLocked);
         }
         if scheduled_unlock_at.is_some() {
-            return Err("Cannot set or modify scheduled unlock while locked".to_string());
+            return Err(LockboxError::ScheduleLocked);
         }
     } else if let Some(scheduled_ts) = scheduled_unlock_at {
         // Box is in scheduled state (no countdown): only allow pushing date further
         if let Some(current_scheduled) = current.scheduled_unlock_at {
             if scheduled_ts < current_scheduled {
-                return Err("Cannot move scheduled unlock to an earlier date".to_string());
+                return Err(LockboxError::ScheduleEarlier {
+                    current: current_scheduled,
+                    requested: scheduled_ts,
+                });
             }
         }
         // Prevent setting a date in the past
         if scheduled_ts <= now_ms {
-            return Err("Scheduled unlock date must be in the future".to_string());
+            return Err(LockboxError::ScheduleInPast { requested: scheduled_ts });
         }
     }
 
@@ -235,26 +313,28 @@ pub fn update_lockbox(
         scheduled_unlock_at,
         tags,
         clear_tags: clear_tags.unwrap_or(false),
+        max_views_per_unlock,
+        clear_max_views_per_unlock: clear_max_views_per_unlock.unwrap_or(false),
     })
-    .map_err(|e| e.to_string())
+    .map(LockboxSummary::from)
 }
 
 #[tauri::command]
-pub fn delete_lockbox(id: i64, state: State<AppState>) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.delete_lockbox(id).map_err(|e| e.to_string())
+pub fn delete_lockbox(id: i64, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    db.delete_lockbox(id)
 }
 
 #[tauri::command]
-pub fn unlock_lockbox(id: i64, state: State<AppState>) -> Result<Lockbox, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.unlock_lockbox(id).map_err(|e| e.to_string())
+pub fn unlock_lockbox(id: i64, state: State<AppState>) -> Result<LockboxSummary, LockboxError> {
+    let db = state.db.lock()?;
+    db.unlock_lockbox(id).map(LockboxSummary::from)
 }
 
 #[tauri::command]
-pub fn cancel_unlock(id: i64, state: State<AppState>) -> Result<Lockbox, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.cancel_unlock(id).map_err(|e| e.to_string())
+pub fn cancel_unlock(id: i64, state: State<AppState>) -> Result<LockboxSummary, LockboxError> {
+    let db = state.db.lock()?;
+    db.cancel_unlock(id).map(LockboxSummary::from)
 }
 
 #[tauri::command]
@@ -262,20 +342,19 @@ pub fn extend_unlock_delay(
     id: i64,
     additional_seconds: i64,
     state: State<AppState>,
-) -> Result<Lockbox, String> {
+) -> Result<LockboxSummary, LockboxError> {
     if additional_seconds <= 0 {
-        return Err("Additional delay must be positive".to_string());
+        return Err(LockboxError::NonPositiveExtension { additional_seconds });
     }
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.extend_unlock_delay(id, additional_seconds)
-        .map_err(|e| e.to_string())
+    let db = state.db.lock()?;
+    db.extend_unlock_delay(id, additional_seconds).map(LockboxSummary::from)
 }
 
 #[tauri::command]
-pub fn use_panic_code(id: i64, code: String, state: State<AppState>) -> Result<Option<Lockbox>, String> {
+pub fn use_panic_code(id: i64, code: String, state: State<AppState>) -> Result<Option<LockboxSummary>, LockboxError> {
     let code_hash = crypto::hash_password(&code);
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.use_panic_code(id, &code_hash).map_err(|e| e.to_string())
+    let db = state.db.lock()?;
+    Ok(db.use_panic_code(id, &code_hash)?.map(LockboxSummary::from))
 }
 
 #[tauri::command]
@@ -283,63 +362,71 @@ pub fn reset_panic_code(
     id: i64,
     new_code: Option<String>,
     state: State<AppState>,
-) -> Result<Lockbox, String> {
+) -> Result<LockboxSummary, LockboxError> {
     let new_code_hash = new_code.map(|c| crypto::hash_password(&c));
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.reset_panic_code(id, new_code_hash.as_deref())
-        .map_err(|e| e.to_string())
+    let db = state.db.lock()?;
+    db.reset_panic_code(id, new_code_hash.as_deref()).map(LockboxSummary::from)
 }
 
 #[tauri::command]
-pub fn get_access_log(
-    lockbox_id: i64,
+pub fn query_access_log(
+    query: AccessLogQuery,
     state: State<AppState>,
-) -> Result<Vec<AccessLogEntry>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_access_log(lockbox_id).map_err(|e| e.to_string())
+) -> Result<AccessLogPage, LockboxError> {
+    let db = state.db.lock()?;
+    db.query_access_log(&query)
 }
 
+/// Self-control statistics, with days and hours in `timezone` (IANA name).
 #[tauri::command]
-pub fn get_global_access_log(state: State<AppState>) -> Result<Vec<AccessLogEntry>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_global_access_log().map_err(|e| e.to_string())
+pub fn get_statistics(timezone: String, state: State<AppState>) -> Result<StatsReport, LockboxError> {
+    let tz = stats::parse_timezone(&timezone)?;
+    let db = state.db.lock()?;
+    stats::compute(&db, tz, chrono::Utc::now().timestamp_millis())
 }
 
 #[tauri::command]
-pub fn relock_lockbox(id: i64, state: State<AppState>) -> Result<Lockbox, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.relock_lockbox(id).map_err(|e| e.to_string())
+pub fn verify_access_log(state: State<AppState>) -> Result<ChainReport, LockboxError> {
+    let db = state.db.lock()?;
+    db.verify_access_log()
 }
 
 #[tauri::command]
-pub fn check_and_update_lockboxes(state: State<AppState>) -> Result<Vec<Lockbox>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.check_and_update_states().map_err(|e| e.to_string())
+pub fn relock_lockbox(id: i64, state: State<AppState>) -> Result<LockboxSummary, LockboxError> {
+    let db = state.db.lock()?;
+    db.relock_lockbox(id).map(LockboxSummary::from)
 }
 
 #[tauri::command]
-pub fn set_master_password(password: String, state: State<AppState>) -> Result<(), String> {
+pub fn check_and_update_lockboxes(state: State<AppState>) -> Result<Vec<LockboxSummary>, LockboxError> {
+    let db = state.db.lock()?;
+    Ok(db.check_and_update_states()?.into_iter().map(LockboxSummary::from).collect())
+}
+
+#[tauri::command]
+pub fn set_master_password(password: String, app: AppHandle, state: State<AppState>) -> Result<(), LockboxError> {
     let hash = crypto::hash_password(&password);
 
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.set_setting("master_password_hash", &hash)
-        .map_err(|e| e.to_string())?;
+    let mut db = state.db.lock()?;
+    open_vault(&app, &mut db, &password)?;
+    db.set_setting("master_password_hash", &hash)?;
 
-    let mut password_hash = state.master_password_hash.lock().map_err(|e| e.to_string())?;
+    let mut password_hash = state.master_password_hash.lock()?;
     *password_hash = Some(hash);
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn verify_master_password(password: String, state: State<AppState>) -> Result<bool, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
+pub fn verify_master_password(password: String, app: AppHandle, state: State<AppState>) -> Result<bool, LockboxError> {
+    let mut db = state.db.lock()?;
 
-    if let Some(stored_hash) = db.get_setting("master_password_hash").map_err(|e| e.to_string())? {
+    if let Some(stored_hash) = db.get_setting("master_password_hash")? {
         let is_valid = crypto::verify_password(&password, &stored_hash);
 
         if is_valid {
-            let mut password_hash = state.master_password_hash.lock().map_err(|e| e.to_string())?;
+            open_vault(&app, &mut db, &password)?;
+            let mut password_hash = state.master_password_hash.lock()?;
             *password_hash = Some(stored_hash);
         }
 
@@ -349,136 +436,628 @@ pub fn verify_master_password(password: String, state: State<AppState>) -> Resul
     }
 }
 
+/// Opens the vault at the first login, then runs what needs its keys: the
+/// check of the start of the access log chain, and the retention policy.
+fn open_vault(app: &AppHandle, db: &mut Database, password: &str) -> Result<(), LockboxError> {
+    if db.is_vault_open() {
+        return Ok(());
+    }
+    db.open_vault(password)?;
+
+    // Warn (without blocking) if the start of the access log chain was altered
+    if let Ok(false) = db.verify_access_log_anchor() {
+        let locale = i18n::current();
+        app.dialog()
+            .message(i18n::tr(locale, "dialog.log_tampered.body", &[]))
+            .title(i18n::tr(locale, "dialog.log_tampered.title", &[]))
+            .kind(MessageDialogKind::Warning)
+            .show(|_| {});
+    }
+
+    // A failure only delays compaction
+    let _ = retention::run(db, chrono::Utc::now().timestamp_millis());
+    Ok(())
+}
+
 #[tauri::command]
-pub fn is_master_password_set(state: State<AppState>) -> Result<bool, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let setting = db.get_setting("master_password_hash").map_err(|e| e.to_string())?;
+pub fn is_master_password_set(state: State<AppState>) -> Result<bool, LockboxError> {
+    let db = state.db.lock()?;
+    let setting = db.get_setting("master_password_hash")?;
     Ok(setting.is_some())
 }
 
 #[tauri::command]
-pub fn export_lockboxes(state: State<AppState>) -> Result<String, String> {
-    let master_hash = {
-        let pw = state.master_password_hash.lock().map_err(|e| e.to_string())?;
-        pw.clone()
-    };
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let lockboxes = db.get_all_lockboxes().map_err(|e| e.to_string())?;
+pub fn get_validation_limits(state: State<AppState>) -> Result<ValidationLimits, LockboxError> {
+    let db = state.db.lock()?;
+    ValidationLimits::load(&db)
+}
 
-    let export_data = ExportData {
-        version: "2.0.0".to_string(),
-        exported_at: chrono::Utc::now().timestamp_millis(),
-        lockboxes: lockboxes
+#[tauri::command]
+pub fn set_validation_limits(
+    limits: ValidationLimits,
+    state: State<AppState>,
+) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    limits.save(&db)
+}
+
+#[tauri::command]
+pub fn list_tags(state: State<AppState>) -> Result<Vec<TagUsage>, LockboxError> {
+    let db = state.db.lock()?;
+    db.list_tags()
+}
+
+#[tauri::command]
+pub fn rename_tag(name: String, new_name: String, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    let new_name = new_name.trim();
+    validation::validate_tag_name("new_name", new_name, &ValidationLimits::load(&db)?)?;
+    db.rename_tag(&name, new_name)
+}
+
+#[tauri::command]
+pub fn merge_tags(sources: Vec<String>, target: String, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    let target = target.trim();
+    validation::validate_tag_name("target", target, &ValidationLimits::load(&db)?)?;
+    db.merge_tags(&sources, target)
+}
+
+#[tauri::command]
+pub fn delete_tag(name: String, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    db.delete_tag(&name)
+}
+
+#[tauri::command]
+pub fn list_categories(state: State<AppState>) -> Result<Vec<Category>, LockboxError> {
+    let db = state.db.lock()?;
+    db.list_categories()
+}
+
+#[tauri::command]
+pub fn create_category(mut category: CategoryRequest, state: State<AppState>) -> Result<Category, LockboxError> {
+    let db = state.db.lock()?;
+    category.name = category.name.trim().to_string();
+    validation::validate_category(&category, &ValidationLimits::load(&db)?)?;
+    db.create_category(category)
+}
+
+/// Replaces a category. Renaming moves its lockboxes along; raising the
+/// minimum delay raises the lockboxes below it.
+#[tauri::command]
+pub fn update_category(
+    id: i64,
+    mut category: CategoryRequest,
+    state: State<AppState>,
+) -> Result<Category, LockboxError> {
+    let db = state.db.lock()?;
+    category.name = category.name.trim().to_string();
+    validation::validate_category(&category, &ValidationLimits::load(&db)?)?;
+    db.update_category(id, category)
+}
+
+#[tauri::command]
+pub fn reorder_categories(ids: Vec<i64>, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    db.reorder_categories(&ids)
+}
+
+#[tauri::command]
+pub fn delete_category(id: i64, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    db.delete_category(id)
+}
+
+#[tauri::command]
+pub fn list_folders(state: State<AppState>) -> Result<Vec<Folder>, LockboxError> {
+    let db = state.db.lock()?;
+    db.list_folders()
+}
+
+#[tauri::command]
+pub fn create_folder(name: String, parent_id: Option<i64>, state: State<AppState>) -> Result<Folder, LockboxError> {
+    let db = state.db.lock()?;
+    let name = name.trim();
+    validation::validate_folder_name("name", name, &ValidationLimits::load(&db)?)?;
+    db.create_folder(name, parent_id)
+}
+
+#[tauri::command]
+pub fn rename_folder(id: i64, name: String, state: State<AppState>) -> Result<Folder, LockboxError> {
+    let db = state.db.lock()?;
+    let name = name.trim();
+    validation::validate_folder_name("name", name, &ValidationLimits::load(&db)?)?;
+    db.rename_folder(id, name)
+}
+
+#[tauri::command]
+pub fn move_folder(id: i64, parent_id: Option<i64>, state: State<AppState>) -> Result<Folder, LockboxError> {
+    let db = state.db.lock()?;
+    db.move_folder(id, parent_id)
+}
+
+/// Deletes a folder; what it held moves up to its parent.
+#[tauri::command]
+pub fn delete_folder(id: i64, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    db.delete_folder(id)
+}
+
+#[tauri::command]
+pub fn move_lockboxes(ids: Vec<i64>, folder_id: Option<i64>, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    db.move_lockboxes(&ids, folder_id)
+}
+
+/// Relocks every unlocked lockbox in the folder and its subfolders.
+#[tauri::command]
+pub fn relock_folder(id: i64, state: State<AppState>) -> Result<Vec<LockboxSummary>, LockboxError> {
+    let db = state.db.lock()?;
+    Ok(db.relock_folder(id)?.into_iter().map(LockboxSummary::from).collect())
+}
+
+/// Extends the unlock delay of every lockbox in the folder and its
+/// subfolders, under the same rules as `extend_unlock_delay`.
+#[tauri::command]
+pub fn extend_folder_delays(
+    id: i64,
+    additional_seconds: i64,
+    state: State<AppState>,
+) -> Result<Vec<LockboxSummary>, LockboxError> {
+    if additional_seconds <= 0 {
+        return Err(LockboxError::NonPositiveExtension { additional_seconds });
+    }
+    let db = state.db.lock()?;
+    Ok(db.extend_folder_delays(id, additional_seconds)?.into_iter().map(LockboxSummary::from).collect())
+}
+
+#[tauri::command]
+pub fn get_retention_policy(state: State<AppState>) -> Result<RetentionPolicy, LockboxError> {
+    let db = state.db.lock()?;
+    RetentionPolicy::load(&db)
+}
+
+#[tauri::command]
+pub fn set_retention_policy(
+    policy: RetentionPolicy,
+    state: State<AppState>,
+) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    policy.save(&db)
+}
+
+/// Applies the retention policy now instead of waiting for the next start.
+#[tauri::command]
+pub fn compact_access_log(state: State<AppState>) -> Result<CompactionReport, LockboxError> {
+    let db = state.db.lock()?;
+    retention::run(&db, chrono::Utc::now().timestamp_millis())
+}
+
+#[tauri::command]
+pub fn get_locale() -> Result<String, LockboxError> {
+    Ok(i18n::current().code().to_string())
+}
+
+#[tauri::command]
+pub fn set_locale(locale: String, state: State<AppState>) -> Result<(), LockboxError> {
+    let parsed = Locale::from_tag(&locale).ok_or_else(|| LockboxError::UnsupportedLocale {
+        locale: locale.clone(),
+    })?;
+    let db = state.db.lock()?;
+    db.set_setting(i18n::LOCALE_SETTING_KEY, parsed.code())?;
+    i18n::set_current(parsed);
+    Ok(())
+}
+
+/// Localized title/body for a desktop notification.
+/// `kind` is one of "unlocked", "scheduled", "relocked" or "tamper".
+#[tauri::command]
+pub fn localize_notification(
+    kind: String,
+    name: Option<String>,
+) -> Result<NotificationText, LockboxError> {
+    let locale = i18n::current();
+    let args = [("name", name.unwrap_or_default())];
+    Ok(NotificationText {
+        title: i18n::tr(locale, &format!("notification.{}.title", kind), &args),
+        body: i18n::tr(locale, &format!("notification.{}.body", kind), &args),
+    })
+}
+
+/// Exports the lockboxes matching `selection`, every lockbox without one;
+/// the file is signed by this device, see `transfer::seal`.
+#[tauri::command]
+pub fn export_lockboxes(selection: Option<LockboxFilter>, state: State<AppState>) -> Result<String, LockboxError> {
+    let selection = selection.unwrap_or_default();
+    let db = state.db.lock()?;
+    let lockboxes = db.find_lockboxes(&selection)?.into_iter().map(ExportLockbox::from).collect();
+    Ok(serde_json::to_string_pretty(&sealed_export(&db, &selection, lockboxes)?)?)
+}
+
+/// Exports the lockboxes matching `selection` with their content in clear,
+/// the whole file encrypted under `passphrase` instead of the master password.
+/// As for `export_clear`, only unlocked boxes are written, so the passphrase
+/// cannot skip a delay; each one counts as a view.
+#[tauri::command]
+pub fn export_portable(
+    passphrase: String,
+    selection: Option<LockboxFilter>,
+    state: State<AppState>,
+) -> Result<ClearExport, LockboxError> {
+    // Checked first, so that a refused passphrase uses up no export counter
+    portable::check_passphrase(&passphrase)?;
+    let selection = selection.unwrap_or_default();
+    let master_hash = state.master_password_hash.lock()?.clone().ok_or(LockboxError::MasterPasswordRequired)?;
+    let (ids, export, skipped_locked) = {
+        let db = state.db.lock()?;
+        db.check_and_update_states()?;
+        let (unlocked, locked): (Vec<_>, Vec<_>) =
+            db.find_lockboxes(&selection)?.into_iter().partition(|lb| !lb.is_locked);
+        let ids: Vec<i64> = unlocked.iter().map(|lb| lb.id).collect();
+        let lockboxes = unlocked
             .into_iter()
             .map(|lb| {
-                let sign_data = lockbox_sign_data(
-                    &lb.name,
-                    &lb.content,
-                    lb.unlock_delay_seconds,
-                    lb.relock_delay_seconds,
-                    lb.penalty_enabled,
-                    lb.penalty_seconds,
-                );
-                let signature = master_hash.as_deref().map(|key| crypto::hmac_sign(&sign_data, key));
-                ExportLockbox {
-                    name: lb.name,
-                    content: lb.content,
-                    category: lb.category,
-                    unlock_delay_seconds: lb.unlock_delay_seconds,
-                    relock_delay_seconds: lb.relock_delay_seconds,
-                    reflection_enabled: lb.reflection_enabled,
-                    reflection_message: lb.reflection_message,
-                    reflection_checklist: lb.reflection_checklist,
-                    penalty_enabled: lb.penalty_enabled,
-                    penalty_seconds: lb.penalty_seconds,
-                    tags: lb.tags,
-                    signature,
-                }
+                let content = crypto::decrypt(&lb.content, &master_hash)?;
+                Ok(ExportLockbox { content, ..ExportLockbox::from(lb) })
             })
-            .collect(),
+            .collect::<Result<Vec<_>, LockboxError>>()?;
+        if lockboxes.is_empty() {
+            return Ok(ClearExport { data: String::new(), exported: 0, skipped_locked: locked.len() });
+        }
+        (ids, serde_json::to_string(&sealed_export(&db, &selection, lockboxes)?)?, locked.len())
     };
 
-    serde_json::to_string_pretty(&export_data).map_err(|e| e.to_string())
+    // The key derivation takes a while: the database stays free meanwhile
+    let data = serde_json::to_string_pretty(&portable::seal(&export, &passphrase, KdfParams::new())?)?;
+    // Counted once the file is sealed, so that a failure costs no view
+    state.db.lock()?.record_content_exports(&ids, "portable")?;
+    Ok(ClearExport {
+        data,
+        exported: ids.len(),
+        skipped_locked,
+    })
 }
 
+/// Exports the lockboxes matching `selection` in clear, as KeePass XML or
+/// CSV, for another password manager. Only unlocked boxes are written, so
+/// exporting cannot skip a delay; each one counts as a view.
 #[tauri::command]
-pub fn import_lockboxes(
-    data: String,
-    source_password: Option<String>,
+pub fn export_clear(
+    format: ClearFormat,
+    selection: Option<LockboxFilter>,
     state: State<AppState>,
-) -> Result<Vec<String>, String> {
-    let export_data: ExportData =
-        serde_json::from_str(&data).map_err(|e| format!("Invalid file format: {}", e))?;
+) -> Result<ClearExport, LockboxError> {
+    let master_hash = state.master_password_hash.lock()?.clone().ok_or(LockboxError::MasterPasswordRequired)?;
+    let db = state.db.lock()?;
+    // Boxes past their relock time are relocked first
+    db.check_and_update_states()?;
+    let (unlocked, locked): (Vec<_>, Vec<_>) =
+        db.find_lockboxes(&selection.unwrap_or_default())?.into_iter().partition(|lb| !lb.is_locked);
+    let ids: Vec<i64> = unlocked.iter().map(|lb| lb.id).collect();
+    let lockboxes = unlocked
+        .into_iter()
+        .map(|lb| {
+            let content = crypto::decrypt(&lb.content, &master_hash)?;
+            Ok(ExportLockbox { content, ..ExportLockbox::from(lb) })
+        })
+        .collect::<Result<Vec<_>, LockboxError>>()?;
+    // Counted once every content decrypted, so that a failure costs no view
+    db.record_content_exports(&ids, format.as_str())?;
+    Ok(ClearExport {
+        data: foreign::write(format, &lockboxes),
+        exported: lockboxes.len(),
+        skipped_locked: locked.len(),
+    })
+}
 
-    // Get current master password hash for re-encryption
-    let current_hash = {
-        let password_hash = state.master_password_hash.lock().map_err(|e| e.to_string())?;
-        password_hash.clone()
+/// Export file of `lockboxes`, chosen with `selection`, with its manifest
+/// signed by this device.
+fn sealed_export(
+    db: &Database,
+    selection: &LockboxFilter,
+    lockboxes: Vec<ExportLockbox>,
+) -> Result<ExportData, LockboxError> {
+    let mut export_data = ExportData {
+        notice: Some(i18n::tr(i18n::current(), "export.notice", &[])),
+        version: transfer::EXPORT_VERSION.to_string(),
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        lockboxes,
+        manifest: None,
+        device_verified: false,
+        source: None,
     };
+    transfer::seal(&mut export_data, selection, db.vault_id()?, db.next_export_counter()?, &db.device_key()?)?;
+    Ok(export_data)
+}
 
-    // If a source password is provided, compute its hash for decryption
+/// Full backup of the vault, signed under the master password hash.
+#[tauri::command]
+pub fn export_backup(state: State<AppState>) -> Result<String, LockboxError> {
+    let master_hash = state.master_password_hash.lock()?.clone().ok_or(LockboxError::MasterPasswordRequired)?;
+    let db = state.db.lock()?;
+    // A backup is placed in the export history, so that a restore can tell
+    // the delay raises made after it.
+    db.next_export_counter()?;
+    let file = backup::create(db.backup_vault()?, chrono::Utc::now().timestamp_millis(), &master_hash)?;
+    Ok(serde_json::to_string_pretty(&file)?)
+}
+
+/// Restores a full backup into an empty vault. `source_password` is the
+/// master password the backup was made under, when it differs.
+#[tauri::command]
+pub fn import_backup(
+    data: String,
+    source_password: Option<String>,
+    state: State<AppState>,
+) -> Result<RestoreReport, LockboxError> {
+    let current_hash = state.master_password_hash.lock()?.clone().ok_or(LockboxError::MasterPasswordRequired)?;
     let source_hash = source_password.map(|p| crypto::hash_password(&p));
+    let keys = RestoreKeys { source: source_hash.as_deref(), current: &current_hash };
+    let file = backup::open(&data, keys, chrono::Utc::now().timestamp_millis())?;
 
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let existing = db.get_all_lockboxes().map_err(|e| e.to_string())?;
-    let existing_names: std::collections::HashSet<_> = existing.iter().map(|lb| &lb.name).collect();
+    let db = state.db.lock()?;
+    let outdated = db.restore_vault(file.vault)?;
+    Ok(RestoreReport {
+        lockboxes: db.get_all_lockboxes()?.into_iter().map(LockboxSummary::from).collect(),
+        outdated,
+    })
+}
 
-    let mut imported = Vec::new();
+#[tauri::command]
+pub fn get_backup_policy(state: State<AppState>) -> Result<SnapshotPolicy, LockboxError> {
+    let db = state.db.lock()?;
+    SnapshotPolicy::load(&db)
+}
 
-    for lb in export_data.lockboxes {
-        if existing_names.contains(&lb.name) {
-            continue;
-        }
+#[tauri::command]
+pub fn set_backup_policy(policy: SnapshotPolicy, state: State<AppState>) -> Result<(), LockboxError> {
+    let db = state.db.lock()?;
+    policy.save(&db)
+}
 
-        // Verify HMAC signature before doing anything with this lockbox.
-        // Use source hash if provided (cross-machine), otherwise current hash.
-        let verify_key = source_hash.as_deref().or(current_hash.as_deref());
-        if let (Some(sig), Some(key)) = (&lb.signature, verify_key) {
-            let sign_data = lockbox_sign_data(
-                &lb.name,
-                &lb.content,
-                lb.unlock_delay_seconds,
-                lb.relock_delay_seconds,
-                lb.penalty_enabled,
-                lb.penalty_seconds,
-            );
-            if !crypto::hmac_verify(&sign_data, key, sig) {
-                return Err(format!(
-                    "Integrity check failed for '{}': the file may have been tampered with.",
-                    lb.name
-                ));
-            }
-        }
+/// Automatic backups in the policy directory, newest first.
+#[tauri::command]
+pub fn list_backups(state: State<AppState>) -> Result<Vec<SnapshotInfo>, LockboxError> {
+    let db = state.db.lock()?;
+    snapshot::list(&SnapshotPolicy::load(&db)?.directory())
+}
 
-        // If source hash differs from current hash, decrypt then re-encrypt
-        let final_content = match (&source_hash, &current_hash) {
-            (Some(src), Some(cur)) if src != cur => {
-                let decrypted = crypto::decrypt(&lb.content, src)
-                    .map_err(|_| format!("Failed to decrypt '{}': wrong source password?", lb.name))?;
-                crypto::encrypt(&decrypted, cur).map_err(|e| e.to_string())?
-            }
-            _ => lb.content, // same machine / same password: keep as-is
+/// Takes an automatic backup now instead of waiting for the next one.
+#[tauri::command]
+pub fn run_backup_now(state: State<AppState>) -> Result<SnapshotInfo, LockboxError> {
+    let db = state.db.lock()?;
+    snapshot::take(&db, &SnapshotPolicy::load(&db)?, chrono::Utc::now().timestamp_millis())
+}
+
+/// Restores the automatic backup `file_name` over the live database, see
+/// `Database::restore_snapshot`. The current state is backed up first, so
+/// the restore itself can be undone.
+#[tauri::command]
+pub fn restore_backup(file_name: String, state: State<AppState>) -> Result<Vec<LockboxSummary>, LockboxError> {
+    state.master_password_hash.lock()?.as_ref().ok_or(LockboxError::MasterPasswordRequired)?;
+    let mut db = state.db.lock()?;
+    let policy = SnapshotPolicy::load(&db)?;
+    let data = snapshot::read(&policy.directory(), &file_name)?;
+    snapshot::take(&db, &policy, chrono::Utc::now().timestamp_millis())?;
+    db.restore_snapshot(&data, &file_name)?;
+    Ok(db.get_all_lockboxes()?.into_iter().map(LockboxSummary::from).collect())
+}
+
+/// Public key of this device, to be trusted on the machines importing its
+/// exports.
+#[tauri::command]
+pub fn get_device_identity(state: State<AppState>) -> Result<DeviceIdentity, LockboxError> {
+    let db = state.db.lock()?;
+    match db.device_key() {
+        Ok(device) => Ok(DeviceIdentity::of(device.public_key(), true)),
+        Err(LockboxError::DeviceKeyUnreadable) => match db.device_public_key()? {
+            Some(public_key) => Ok(DeviceIdentity::of(public_key, false)),
+            None => Err(LockboxError::DeviceKeyUnreadable),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Replaces this device's signing key when it can no longer be read.
+/// Machines that trusted the old key have to trust the new one.
+#[tauri::command]
+pub fn reset_device_key(state: State<AppState>) -> Result<DeviceIdentity, LockboxError> {
+    let db = state.db.lock()?;
+    Ok(DeviceIdentity::of(db.reset_device_key()?.public_key(), true))
+}
+
+#[tauri::command]
+pub fn list_trusted_devices(state: State<AppState>) -> Result<Vec<TrustedDevice>, LockboxError> {
+    let db = state.db.lock()?;
+    db.trusted_devices()
+}
+
+/// Accepts exports signed by `public_key` from now on.
+#[tauri::command]
+pub fn trust_device(
+    public_key: String,
+    name: String,
+    state: State<AppState>,
+) -> Result<Vec<TrustedDevice>, LockboxError> {
+    let db = state.db.lock()?;
+    db.trust_device(&public_key, &name)
+}
+
+#[tauri::command]
+pub fn remove_trusted_device(
+    public_key: String,
+    state: State<AppState>,
+) -> Result<Vec<TrustedDevice>, LockboxError> {
+    let db = state.db.lock()?;
+    db.remove_trusted_device(&public_key)
+}
+
+/// Parses and checks an import file without writing anything. With
+/// `foreign`, the file comes from another password manager.
+#[tauri::command]
+pub fn preview_import(
+    data: String,
+    source_password: Option<String>,
+    passphrase: Option<String>,
+    strategies: Option<ImportStrategies>,
+    foreign: Option<ForeignImport>,
+    state: State<AppState>,
+) -> Result<ImportReport, LockboxError> {
+    let current_hash = state.master_password_hash.lock()?.clone();
+    let opened = open_import_file(&data, passphrase, foreign, current_hash.as_deref())?;
+    let db = state.db.lock()?;
+    let strategies = strategies.unwrap_or_default();
+    Ok(check_import_file(&db, opened, source_password, &strategies, current_hash.as_deref())?.0)
+}
+
+/// Carries out the action `preview_import` reports for each lockbox of the
+/// file: create, create renamed, replace or merge; the others are left out
+/// and keep their status in the returned report.
+#[tauri::command]
+pub fn import_lockboxes(
+    data: String,
+    source_password: Option<String>,
+    passphrase: Option<String>,
+    strategies: Option<ImportStrategies>,
+    foreign: Option<ForeignImport>,
+    state: State<AppState>,
+) -> Result<ImportReport, LockboxError> {
+    let current_hash = state.master_password_hash.lock()?.clone();
+    let opened = open_import_file(&data, passphrase, foreign, current_hash.as_deref())?;
+    let db = state.db.lock()?;
+    let strategies = strategies.unwrap_or_default();
+    let (mut report, export_data, contents) =
+        check_import_file(&db, opened, source_password, &strategies, current_hash.as_deref())?;
+
+    let mut writes = Vec::new();
+    let mut imported = Vec::new();
+    let files = export_data.lockboxes.into_iter().zip(contents);
+    for ((index, item), (lb, content)) in report.items.iter().enumerate().zip(files) {
+        let current = match item.existing_id {
+            Some(id) => db.get_lockbox(id)?,
+            None => None,
         };
+        writes.push(match (item.action, content, current) {
+            (ImportAction::Replace, Some(content), Some(current)) => {
+                ImportWrite::Update(transfer::replacement(lb, &current, content))
+            }
+            (ImportAction::Merge, _, Some(current)) => ImportWrite::Update(transfer::merge(&lb, &current)),
+            (ImportAction::Create | ImportAction::Rename, Some(content), _) => {
+                ImportWrite::Create(imported_lockbox(lb, item.renamed_to.clone(), content))
+            }
+            _ => continue,
+        });
+        imported.push(index);
+    }
 
-        db.create_lockbox(CreateLockboxRequest {
-            name: lb.name.clone(),
-            content: final_content,
-            category: lb.category,
-            unlock_delay_seconds: lb.unlock_delay_seconds,
-            relock_delay_seconds: lb.relock_delay_seconds,
-            reflection_enabled: lb.reflection_enabled,
-            reflection_message: lb.reflection_message,
-            reflection_checklist: lb.reflection_checklist,
-            penalty_enabled: lb.penalty_enabled,
-            penalty_seconds: lb.penalty_seconds,
-            panic_code_hash: None, // panic code hashes cannot be transferred
-            scheduled_unlock_at: None, // scheduled dates are not restored on import
-            tags: lb.tags,
-        })
-        .map_err(|e| e.to_string())?;
+    // All or nothing, so that a failure never leaves half a file imported.
+    db.import_lockboxes(writes)?;
+    for index in imported {
+        report.items[index].imported = true;
+    }
+    Ok(report)
+}
+
+/// Request creating an imported lockbox, under `name` when given.
+fn imported_lockbox(lb: ExportLockbox, name: Option<String>, content: String) -> CreateLockboxRequest {
+    CreateLockboxRequest {
+        name: name.unwrap_or(lb.name),
+        content,
+        category: lb.category,
+        unlock_delay_seconds: Some(lb.unlock_delay_seconds),
+        relock_delay_seconds: Some(lb.relock_delay_seconds),
+        reflection_enabled: Some(lb.reflection_enabled),
+        reflection_message: lb.reflection_message,
+        reflection_checklist: lb.reflection_checklist,
+        penalty_enabled: Some(lb.penalty_enabled),
+        penalty_seconds: Some(lb.penalty_seconds),
+        panic_code_hash: None, // panic code hashes cannot be transferred
+        scheduled_unlock_at: None, // scheduled dates are not restored on import
+        tags: lb.tags,
+        max_views_per_unlock: lb.max_views_per_unlock,
+    }
+}
+
+/// An import file once its passphrase has been applied.
+enum OpenedImport {
+    /// Entries of another password manager.
+    Foreign(Box<ExportData>),
+    /// A Lockbox export, `portable` when it was opened with a passphrase.
+    Export { text: String, portable: bool },
+}
 
-        imported.push(lb.name);
+/// Decrypts a portable or foreign file. Its key derivation may take seconds,
+/// so this runs before the database is locked.
+fn open_import_file(
+    data: &str,
+    passphrase: Option<String>,
+    foreign: Option<ForeignImport>,
+    current_hash: Option<&str>,
+) -> Result<OpenedImport, LockboxError> {
+    // Portable and foreign contents are in clear, to be encrypted under the
+    // current master password.
+    let portable = foreign.is_some() || portable::is_portable(data);
+    if portable && current_hash.is_none() {
+        return Err(LockboxError::MasterPasswordRequired);
+    }
+    if let Some(foreign) = foreign {
+        return Ok(OpenedImport::Foreign(Box::new(foreign::parse(data, &foreign, passphrase.as_deref())?)));
     }
+    let text = match passphrase {
+        Some(passphrase) if portable => portable::open(data, &passphrase)?,
+        None if portable => return Err(LockboxError::PassphraseRequired),
+        _ => data.to_string(),
+    };
+    Ok(OpenedImport::Export { text, portable })
+}
+
+/// Shared by preview and import: the report, the parsed file and the
+/// content to store for each lockbox created or replaced.
+fn check_import_file(
+    db: &Database,
+    opened: OpenedImport,
+    source_password: Option<String>,
+    strategies: &ImportStrategies,
+    current_hash: Option<&str>,
+) -> Result<(ImportReport, ExportData, Vec<Option<String>>), LockboxError> {
+    let existing = db.get_all_lockboxes()?;
+    let categories: HashMap<String, CategoryPolicies> =
+        db.list_categories()?.into_iter().map(|category| (category.name, category.policies)).collect();
+    let limits = ValidationLimits::load(db)?;
+    let target = ImportTarget { lockboxes: &existing, categories: &categories, limits: &limits };
+    let now_ms = chrono::Utc::now().timestamp_millis();
+
+    // Entries of another password manager have nothing to verify; they may
+    // not bring back a lockbox at delays lower than the ones it was raised to.
+    let (text, portable) = match opened {
+        OpenedImport::Foreign(export_data) => {
+            let keys = ImportKeys { source: None, current: current_hash, portable: true };
+            let rollback = RollbackGuard::for_file(&export_data, &db.vault_id()?, db.delay_raises()?);
+            let (report, contents) =
+                transfer::check_import(&export_data, keys, target, strategies, rollback.as_ref(), now_ms)?;
+            return Ok((report, *export_data, contents));
+        }
+        OpenedImport::Export { text, portable } => (text, portable),
+    };
+
+    // Hash of the password the file was exported under, when given (cross-machine)
+    let source_hash = source_password.map(|p| crypto::hash_password(&p));
+    let keys = ImportKeys { source: source_hash.as_deref(), current: current_hash, portable };
+    let mut trusted: Vec<String> = db.trusted_devices()?.into_iter().map(|device| device.public_key).collect();
+    trusted.extend(db.device_public_key()?);
+    let manifest_keys = ManifestKeys {
+        trusted: &trusted,
+        hmac: source_hash.as_deref().or(current_hash),
+        allow_untrusted: portable,
+    };
+    let export_data = transfer::parse_export(&text, manifest_keys)?;
+
+    // No file may roll back a delay raised since it was exported, unless a
+    // trusted device of another vault signed it.
+    let rollback = RollbackGuard::for_file(&export_data, &db.vault_id()?, db.delay_raises()?);
 
-    Ok(imported)
+    let (report, contents) =
+        transfer::check_import(&export_data, keys, target, strategies, rollback.as_ref(), now_ms)?;
+    Ok((report, export_data, contents))
 }
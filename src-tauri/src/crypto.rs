@@ -1,5 +1,5 @@
 use aes_gcm::{
-    aead::{Aead, KeyInit, OsRng},
+    aead::{Aead, KeyInit, OsRng, Payload},
     Aes256Gcm, Nonce,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
@@ -23,7 +23,7 @@ pub enum CryptoError {
 
 const SALT_LENGTH: usize = 16;
 const NONCE_LENGTH: usize = 12;
-const KEY_LENGTH: usize = 32;
+pub const KEY_LENGTH: usize = 32;
 const PBKDF2_ITERATIONS: u32 = 100_000;
 
 /// Derives a 256-bit key from a password using PBKDF2
@@ -89,6 +89,61 @@ pub fn decrypt(encrypted: &str, password: &str) -> Result<String, CryptoError> {
     String::from_utf8(plaintext).map_err(|_| CryptoError::DecryptionFailed)
 }
 
+/// Derives a 256-bit key for one `purpose` from a high-entropy secret, so the
+/// same secret can key several uses independently. Not for passwords.
+pub fn derive_subkey(secret: &str, purpose: &str) -> [u8; KEY_LENGTH] {
+    let mut mac = <HmacSha256 as digest::KeyInit>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any size");
+    mac.update(purpose.as_bytes());
+    mac.finalize().into_bytes().into()
+}
+
+/// Encrypts content using AES-256-GCM under a raw key, for many small records
+/// where a password derivation per record would be too slow.
+/// Returns base64 encoded: nonce || ciphertext
+pub fn encrypt_with_key(content: &str, key: &[u8; KEY_LENGTH]) -> Result<String, CryptoError> {
+    Ok(BASE64.encode(encrypt_bytes_with_key(content.as_bytes(), &[], key)?))
+}
+
+/// Decrypts content encrypted with encrypt_with_key()
+pub fn decrypt_with_key(encrypted: &str, key: &[u8; KEY_LENGTH]) -> Result<String, CryptoError> {
+    let combined = BASE64
+        .decode(encrypted)
+        .map_err(|_| CryptoError::InvalidFormat)?;
+    let plaintext = decrypt_bytes_with_key(&combined, &[], key)?;
+    String::from_utf8(plaintext).map_err(|_| CryptoError::DecryptionFailed)
+}
+
+/// Encrypts binary data using AES-256-GCM under a raw key, authenticating
+/// `aad` along with it. Returns nonce || ciphertext
+pub fn encrypt_bytes_with_key(data: &[u8], aad: &[u8], key: &[u8; KEY_LENGTH]) -> Result<Vec<u8>, CryptoError> {
+    let mut nonce_bytes = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce_bytes);
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::EncryptionFailed)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: data, aad })
+        .map_err(|_| CryptoError::EncryptionFailed)?;
+
+    let mut combined = Vec::with_capacity(NONCE_LENGTH + ciphertext.len());
+    combined.extend_from_slice(&nonce_bytes);
+    combined.extend_from_slice(&ciphertext);
+    Ok(combined)
+}
+
+/// Decrypts data encrypted with encrypt_bytes_with_key() and the same `aad`
+pub fn decrypt_bytes_with_key(combined: &[u8], aad: &[u8], key: &[u8; KEY_LENGTH]) -> Result<Vec<u8>, CryptoError> {
+    if combined.len() < NONCE_LENGTH {
+        return Err(CryptoError::InvalidFormat);
+    }
+
+    let (nonce_bytes, ciphertext) = combined.split_at(NONCE_LENGTH);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::DecryptionFailed)?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), Payload { msg: ciphertext, aad })
+        .map_err(|_| CryptoError::DecryptionFailed)
+}
+
 /// Hashes the master password for storage verification
 pub fn hash_password(password: &str) -> String {
     let mut hasher = Sha256::new();
@@ -101,6 +156,13 @@ pub fn verify_password(password: &str, hash: &str) -> bool {
     hash_password(password) == hash
 }
 
+/// Generates a random 256-bit secret, hex-encoded.
+pub fn generate_key() -> String {
+    let mut key = [0u8; KEY_LENGTH];
+    OsRng.fill_bytes(&mut key);
+    hex::encode(key)
+}
+
 /// Signs data with HMAC-SHA256 using the given key. Returns a hex-encoded signature.
 pub fn hmac_sign(data: &str, key: &str) -> String {
     let mut mac = <HmacSha256 as digest::KeyInit>::new_from_slice(key.as_bytes())
@@ -109,11 +171,58 @@ pub fn hmac_sign(data: &str, key: &str) -> String {
     hex::encode(mac.finalize().into_bytes())
 }
 
+/// Keyed hash of `value` under a raw key, hex-encoded, for looking encrypted
+/// records up by an exact value without storing it in clear.
+pub fn blind_index(value: &str, key: &[u8; KEY_LENGTH]) -> String {
+    let mut mac = <HmacSha256 as digest::KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(value.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
 /// Verifies a HMAC-SHA256 signature.
 pub fn hmac_verify(data: &str, key: &str, signature: &str) -> bool {
     hmac_sign(data, key) == signature
 }
 
+/// Compact JSON with object keys sorted at every level, so that a signature
+/// over it does not depend on the field order of the file it was read from.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +250,25 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_encrypt_with_key_roundtrip_and_key_separation() {
+        let key = derive_subkey("vault-secret", "access_log");
+        let other = derive_subkey("vault-secret", "something_else");
+        assert_ne!(key, other);
+
+        let encrypted = encrypt_with_key("payload", &key).unwrap();
+        assert_eq!(decrypt_with_key(&encrypted, &key).unwrap(), "payload");
+        assert!(decrypt_with_key(&encrypted, &other).is_err());
+    }
+
+    #[test]
+    fn test_blind_index_depends_on_value_and_key() {
+        let key = [1u8; KEY_LENGTH];
+        assert_eq!(blind_index("lockbox:1", &key), blind_index("lockbox:1", &key));
+        assert_ne!(blind_index("lockbox:1", &key), blind_index("lockbox:2", &key));
+        assert_ne!(blind_index("lockbox:1", &key), blind_index("lockbox:1", &[2u8; KEY_LENGTH]));
+    }
+
     #[test]
     fn test_password_hash() {
         let password = "mypassword";
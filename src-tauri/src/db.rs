@@ -1,6 +1,20 @@
-use rusqlite::{Connection, Result, params};
+use crate::audit::{self, ChainAnchor, ChainHead, ChainReport, ChainVerifier};
+use crate::backup::{self, BackupCategory, BackupFolder, BackupLockbox, BackupTag, BackupTombstone, BackupVault};
+use crate::crypto;
+use crate::error::{LockboxError, Result};
+use crate::events::AccessEvent;
+use crate::identity::{self, DeviceKey, TrustedDevice};
+use crate::retention::{self, AccessLogArchive, ArchivedEntry, CompactionReport, MonthlyAggregate};
+use crate::search;
+use crate::snapshot;
+use crate::transfer::{self, ExportLockbox};
+use crate::validation;
+use rusqlite::types::Value;
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName, OptionalExtension, params, params_from_iter};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
@@ -9,7 +23,35 @@ const LAST_WALL_CLOCK_MS_KEY: &str = "clock_last_wall_ms";
 const LAST_MONOTONIC_MS_KEY: &str = "clock_last_monotonic_ms";
 const LAST_BOOT_UPTIME_MS_KEY: &str = "clock_last_boot_uptime_ms";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Setting holding the vault secret, encrypted under the master password.
+const VAULT_KEY_SETTING_KEY: &str = "vault_key_sealed";
+
+/// Schema version set by the last step of `migrate`.
+const SCHEMA_VERSION: i32 = 13;
+
+/// Settings a snapshot restore keeps from the live database: machine-bound
+/// state, and the settings that decide what a restore may do.
+const KEPT_ON_RESTORE: [&str; 14] = [
+    LAST_WALL_CLOCK_MS_KEY,
+    LAST_MONOTONIC_MS_KEY,
+    LAST_BOOT_UPTIME_MS_KEY,
+    VAULT_KEY_SETTING_KEY,
+    identity::DEVICE_KEY_SETTING_KEY,
+    identity::DEVICE_PUBLIC_KEY_SETTING_KEY,
+    identity::TRUSTED_DEVICES_SETTING_KEY,
+    validation::LIMITS_SETTING_KEY,
+    retention::RETENTION_SETTING_KEY,
+    snapshot::SNAPSHOT_POLICY_SETTING_KEY,
+    audit::ANCHOR_SETTING_KEY,
+    audit::HEAD_SETTING_KEY,
+    "master_password_hash",
+    "min_app_version",
+];
+
+/// Full lockbox row, including the encrypted content and panic code hash.
+/// Deliberately not `Serialize`: commands return `LockboxSummary` or
+/// `LockboxDetail` so secret material never crosses the IPC boundary.
+#[derive(Debug, Clone, Default)]
 pub struct Lockbox {
     pub id: i64,
     pub name: String,
@@ -34,28 +76,100 @@ pub struct Lockbox {
     pub panic_code_used: bool,
     // Scheduled unlock
     pub scheduled_unlock_at: Option<i64>,
-    // Free tags
+    // Free tags, read from `lockbox_tags` in the box's order
     pub tags: Option<String>, // JSON array e.g. '["urgent","work"]'
+    // View limit: relock after this many content views in one unlock window
+    pub max_views_per_unlock: Option<i64>,
+    pub views_this_unlock: i64,
+    // Folder holding the box; `None` at the root
+    pub folder_id: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CreateLockboxRequest {
+/// Lockbox metadata safe to send to the webview: no content, no hashes.
+#[derive(Debug, Clone, Serialize)]
+pub struct LockboxSummary {
+    pub id: i64,
     pub name: String,
-    pub content: String,
     pub category: Option<String>,
+    pub is_locked: bool,
     pub unlock_delay_seconds: i64,
     pub relock_delay_seconds: i64,
+    pub unlock_timestamp: Option<i64>,
+    pub relock_timestamp: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
     pub reflection_enabled: bool,
     pub reflection_message: Option<String>,
     pub reflection_checklist: Option<String>,
     pub penalty_enabled: bool,
     pub penalty_seconds: i64,
-    pub panic_code_hash: Option<String>,
+    pub has_panic_code: bool,
+    pub panic_code_used: bool,
     pub scheduled_unlock_at: Option<i64>,
     pub tags: Option<String>,
+    pub max_views_per_unlock: Option<i64>,
+    pub views_this_unlock: i64,
+    pub folder_id: Option<i64>,
+}
+
+impl From<Lockbox> for LockboxSummary {
+    fn from(lb: Lockbox) -> Self {
+        LockboxSummary {
+            id: lb.id,
+            name: lb.name,
+            category: lb.category,
+            is_locked: lb.is_locked,
+            unlock_delay_seconds: lb.unlock_delay_seconds,
+            relock_delay_seconds: lb.relock_delay_seconds,
+            unlock_timestamp: lb.unlock_timestamp,
+            relock_timestamp: lb.relock_timestamp,
+            created_at: lb.created_at,
+            updated_at: lb.updated_at,
+            reflection_enabled: lb.reflection_enabled,
+            reflection_message: lb.reflection_message,
+            reflection_checklist: lb.reflection_checklist,
+            penalty_enabled: lb.penalty_enabled,
+            penalty_seconds: lb.penalty_seconds,
+            has_panic_code: lb.panic_code_hash.is_some(),
+            panic_code_used: lb.panic_code_used,
+            scheduled_unlock_at: lb.scheduled_unlock_at,
+            tags: lb.tags,
+            max_views_per_unlock: lb.max_views_per_unlock,
+            views_this_unlock: lb.views_this_unlock,
+            folder_id: lb.folder_id,
+        }
+    }
+}
+
+/// A single lockbox with its decrypted content, returned by `get_lockbox`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LockboxDetail {
+    #[serde(flatten)]
+    pub summary: LockboxSummary,
+    /// Plaintext content; `None` unless the box is unlocked and the session is authenticated.
+    pub content: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLockboxRequest {
+    pub name: String,
+    pub content: String,
+    pub category: Option<String>,
+    // Policies left out are taken from the category's defaults.
+    pub unlock_delay_seconds: Option<i64>,
+    pub relock_delay_seconds: Option<i64>,
+    pub reflection_enabled: Option<bool>,
+    pub reflection_message: Option<String>,
+    pub reflection_checklist: Option<String>,
+    pub penalty_enabled: Option<bool>,
+    pub penalty_seconds: Option<i64>,
+    pub panic_code_hash: Option<String>,
+    pub scheduled_unlock_at: Option<i64>,
+    pub tags: Option<String>,
+    pub max_views_per_unlock: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateLockboxRequest {
     pub id: i64,
     pub name: Option<String>,
@@ -75,6 +189,156 @@ pub struct UpdateLockboxRequest {
     pub scheduled_unlock_at: Option<i64>,
     pub tags: Option<String>,
     pub clear_tags: bool,
+    pub max_views_per_unlock: Option<i64>,
+    pub clear_max_views_per_unlock: bool,
+}
+
+/// One write of an import, see `Database::import_lockboxes`.
+#[derive(Debug, Clone)]
+pub enum ImportWrite {
+    Create(CreateLockboxRequest),
+    Update(UpdateLockboxRequest),
+}
+
+/// A tag with the number of lockboxes carrying it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagUsage {
+    pub id: i64,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    /// Lockboxes carrying at least one of the tags.
+    #[default]
+    Any,
+    /// Lockboxes carrying every one of the tags.
+    All,
+}
+
+/// Filters for `find_lockboxes`, all of which must hold. An empty filter
+/// matches every lockbox.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockboxFilter {
+    /// Only these lockboxes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// Only lockboxes changed at or after this time, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_since: Option<i64>,
+}
+
+impl LockboxFilter {
+    /// True when the filter matches every lockbox.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_none() && self.category.is_none() && self.tags.is_empty() && self.updated_since.is_none()
+    }
+}
+
+/// Policies a category applies to the lockboxes created in it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CategoryPolicies {
+    pub unlock_delay_seconds: Option<i64>,
+    pub relock_delay_seconds: Option<i64>,
+    /// Penalty on cancellation; `None` leaves penalty mode off.
+    pub penalty_seconds: Option<i64>,
+    /// JSON array of strings; turns the reflection modal on when set.
+    pub reflection_checklist: Option<String>,
+    /// Floor for the unlock delay of every lockbox in the category.
+    pub min_unlock_delay_seconds: Option<i64>,
+}
+
+impl CategoryPolicies {
+    /// Rejects an unlock delay below the category's floor.
+    fn check_floor(&self, category: &str, unlock_delay_seconds: i64) -> Result<()> {
+        match self.min_unlock_delay_seconds {
+            Some(minimum) if unlock_delay_seconds < minimum => Err(LockboxError::BelowCategoryMinimum {
+                category: category.to_string(),
+                minimum,
+                requested: unlock_delay_seconds,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A user-defined category. Lockboxes refer to it by name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    /// Icon identifier understood by the frontend, e.g. "key".
+    pub icon: Option<String>,
+    /// CSS color, e.g. "#6366f1".
+    pub color: Option<String>,
+    pub position: i64,
+    #[serde(flatten)]
+    pub policies: CategoryPolicies,
+    pub lockbox_count: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CategoryRequest {
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(flatten, default)]
+    pub policies: CategoryPolicies,
+}
+
+/// A node of the folder tree; `parent_id` is `None` at the root.
+#[derive(Debug, Clone, Serialize)]
+pub struct Folder {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    /// Lockboxes directly in this folder, not in its subfolders.
+    pub lockbox_count: i64,
+}
+
+/// Categories offered before they became user-defined, as (name, icon, color).
+const BUILT_IN_CATEGORIES: [(&str, &str, &str); 7] = [
+    ("Passwords", "key", "#6366f1"),
+    ("Financial", "wallet", "#10b981"),
+    ("Personal", "user", "#f59e0b"),
+    ("Work", "briefcase", "#3b82f6"),
+    ("Social", "users", "#ec4899"),
+    ("Gaming", "gamepad", "#8b5cf6"),
+    ("Other", "folder", "#6b7280"),
+];
+
+/// Delays of a lockbox created without them, in or out of a category.
+const DEFAULT_UNLOCK_DELAY_SECONDS: i64 = 60;
+const DEFAULT_RELOCK_DELAY_SECONDS: i64 = 3600;
+
+/// Tag names from a JSON array as stored in `Lockbox.tags`, cleaned by
+/// `clean_tag_names`. Unparseable input yields no tags.
+fn parse_tag_names(json: Option<&str>) -> Vec<String> {
+    let names: Vec<String> = json.and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default();
+    clean_tag_names(&names)
+}
+
+/// Trimmed tag names, without blanks or duplicates, in their original order.
+fn clean_tag_names(names: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.trim();
+        if !name.is_empty() && !tags.iter().any(|t| t == name) {
+            tags.push(name.to_string());
+        }
+    }
+    tags
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +347,153 @@ pub struct AccessLogEntry {
     pub lockbox_id: i64,
     pub event_type: String,
     pub timestamp: i64,
+    /// Versioned payload from `AccessEvent`, `None` for rows logged before details existed.
+    pub details: Option<serde_json::Value>,
+}
+
+const SELECT_ACCESS_LOG: &str = "SELECT id, timestamp, payload, prev_hash, hash FROM access_log";
+
+/// Purpose the access log key is derived for from the vault secret.
+const ACCESS_LOG_KEY_PURPOSE: &str = "access_log";
+
+/// Encrypted part of an access log row. Only the id and timestamp stay in
+/// clear, so the database alone does not tell which box was opened when.
+#[derive(Serialize, Deserialize)]
+struct AccessLogPayload {
+    lockbox_id: i64,
+    event_type: String,
+    details: Option<serde_json::Value>,
+}
+
+/// An access log row as stored, payload still encrypted.
+struct AccessLogRow {
+    id: i64,
+    timestamp: i64,
+    payload: String,
+    prev_hash: Option<String>,
+    hash: Option<String>,
+}
+
+impl AccessLogRow {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(AccessLogRow {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            payload: row.get(2)?,
+            prev_hash: row.get(3)?,
+            hash: row.get(4)?,
+        })
+    }
+
+    fn decrypt(&self, key: &[u8; crypto::KEY_LENGTH]) -> Result<AccessLogEntry> {
+        let payload: AccessLogPayload = serde_json::from_str(&crypto::decrypt_with_key(&self.payload, key)?)?;
+        Ok(AccessLogEntry {
+            id: self.id,
+            lockbox_id: payload.lockbox_id,
+            event_type: payload.event_type,
+            timestamp: self.timestamp,
+            details: payload.details,
+        })
+    }
+}
+
+fn encrypt_access_log_payload(entry: &AccessLogEntry, key: &[u8; crypto::KEY_LENGTH]) -> Result<String> {
+    let payload = AccessLogPayload {
+        lockbox_id: entry.lockbox_id,
+        event_type: entry.event_type.clone(),
+        details: entry.details.clone(),
+    };
+    Ok(crypto::encrypt_with_key(&serde_json::to_string(&payload)?, key)?)
+}
+
+/// Blind index of the lockbox of an access log row, see `crypto::blind_index`.
+fn lockbox_tag(lockbox_id: i64, key: &[u8; crypto::KEY_LENGTH]) -> String {
+    crypto::blind_index(&format!("lockbox:{}", lockbox_id), key)
+}
+
+/// Blind index of the event type of an access log row.
+fn event_tag(event_type: &str, key: &[u8; crypto::KEY_LENGTH]) -> String {
+    crypto::blind_index(&format!("event:{}", event_type), key)
+}
+
+const ACCESS_LOG_DEFAULT_PAGE_SIZE: u32 = 100;
+const ACCESS_LOG_MAX_PAGE_SIZE: u32 = 500;
+
+/// Filters for `query_access_log`. Omitted or empty filters match everything.
+/// Lockbox and event filters match the blind indexes of the rows, so every
+/// filter runs in SQL.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AccessLogQuery {
+    pub lockbox_ids: Option<Vec<i64>>,
+    pub event_types: Option<Vec<String>>,
+    /// Inclusive lower bound, in milliseconds.
+    pub from: Option<i64>,
+    /// Exclusive upper bound, in milliseconds.
+    pub to: Option<i64>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+    /// Also count the matching entries, which costs a pass over all of them.
+    pub with_counts: bool,
+}
+
+/// One page of access log entries, newest first. `total` and `counts_by_event`
+/// cover every entry matching the filters, not just this page; they are only
+/// set when `with_counts` was asked.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessLogPage {
+    pub entries: Vec<AccessLogEntry>,
+    pub next_cursor: Option<String>,
+    pub total: Option<i64>,
+    pub counts_by_event: Option<BTreeMap<String, i64>>,
+}
+
+/// Position after the last entry of a page, encoded as "timestamp:id" so that
+/// pages stay stable while new events are being logged.
+fn encode_cursor(entry: &AccessLogEntry) -> String {
+    format!("{}:{}", entry.timestamp, entry.id)
+}
+
+fn decode_cursor(cursor: &str) -> Result<(i64, i64)> {
+    cursor
+        .split_once(':')
+        .and_then(|(ts, id)| Some((ts.parse().ok()?, id.parse().ok()?)))
+        .ok_or_else(|| LockboxError::InvalidCursor { cursor: cursor.to_string() })
+}
+
+/// SQL conditions and bound values for the filters of `query`.
+fn access_log_conditions(query: &AccessLogQuery, key: &[u8; crypto::KEY_LENGTH]) -> (Vec<String>, Vec<Value>) {
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(ids) = query.lockbox_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        conditions.push(format!("lockbox_tag IN ({})", vec!["?"; ids.len()].join(", ")));
+        values.extend(ids.iter().map(|id| Value::Text(lockbox_tag(*id, key))));
+    }
+    if let Some(types) = query.event_types.as_ref().filter(|types| !types.is_empty()) {
+        conditions.push(format!("event_tag IN ({})", vec!["?"; types.len()].join(", ")));
+        values.extend(types.iter().map(|event_type| Value::Text(event_tag(event_type, key))));
+    }
+
+    if let Some(from) = query.from {
+        conditions.push("timestamp >= ?".to_string());
+        values.push(Value::Integer(from));
+    }
+    if let Some(to) = query.to {
+        conditions.push("timestamp < ?".to_string());
+        values.push(Value::Integer(to));
+    }
+
+    (conditions, values)
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
 }
 
 static FIRST_TICK: AtomicBool = AtomicBool::new(true);
@@ -107,8 +518,21 @@ fn uptime_ms_since_boot() -> Option<u64> {
     None
 }
 
+/// Vault secret and the keys derived from it.
+#[derive(Clone)]
+struct VaultKeys {
+    /// Hex-encoded vault secret keying the access log chain. Stored sealed
+    /// under the master password, so the database alone can neither read
+    /// nor forge what it keys.
+    secret: String,
+    /// Key for access log payloads and aggregates, derived from the vault secret.
+    log: [u8; crypto::KEY_LENGTH],
+}
+
 pub struct Database {
     conn: Connection,
+    /// Set at login by `open_vault`. Until then only settings are readable.
+    keys: Option<VaultKeys>,
 }
 
 impl Database {
@@ -178,16 +602,26 @@ impl Database {
     }
 
     fn handle_clock_tampering(&self, now: i64) -> Result<()> {
-        let affected_ids: Vec<i64> = {
+        let affected: Vec<(i64, AccessEvent)> = {
             let mut stmt = self.conn.prepare(
-                "SELECT id FROM lockboxes
+                "SELECT id, is_locked, unlock_timestamp IS NOT NULL, scheduled_unlock_at IS NOT NULL
+                 FROM lockboxes
                  WHERE is_locked = 0 OR unlock_timestamp IS NOT NULL OR scheduled_unlock_at IS NOT NULL",
             )?;
-            let ids: Vec<i64> = stmt
-                .query_map([], |row| row.get(0))?
+            let rows: Vec<(i64, AccessEvent)> = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get(0)?,
+                        AccessEvent::ClockTamperDetected {
+                            was_unlocked: row.get::<_, i32>(1)? == 0,
+                            cancelled_unlock: row.get(2)?,
+                            cancelled_schedule: row.get(3)?,
+                        },
+                    ))
+                })?
                 .filter_map(|r| r.ok())
                 .collect();
-            ids
+            rows
         };
 
         self.conn.execute(
@@ -201,14 +635,14 @@ impl Database {
             params![now],
         )?;
 
-        for id in affected_ids {
-            let _ = self.log_access_event(id, "clock_tamper_detected");
+        for (id, event) in affected {
+            let _ = self.log_access_event(id, &event);
         }
 
         Ok(())
     }
 
-    pub fn new() -> std::result::Result<Self, String> {
+    pub fn new() -> Result<Self> {
         let db_path = Self::get_db_path();
         let old_db_path = Self::get_old_db_path();
 
@@ -225,7 +659,7 @@ impl Database {
                 if let Err(copy_e) = std::fs::copy(&old_db_path, &db_path) {
                     // CRITICAL: If both fail (e.g. file locked by old app on Windows), 
                     // we return an error to show a dialog to the user.
-                    return Err(format!("Impossible de migrer les données.\nL'ancienne version de l'application est probablement ouverte et verrouille les fichiers.\n\nDétail technique: {}", copy_e));
+                    return Err(LockboxError::MigrationBlocked { detail: copy_e.to_string() });
                 } else {
                     // If copy succeeded, we MUST delete the old file so the old app can't read it anymore
                     let _ = std::fs::remove_file(&old_db_path);
@@ -248,12 +682,66 @@ impl Database {
         }
         // ----------------------
 
-        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-        let db = Database { conn };
-        db.initialize().map_err(|e| e.to_string())?;
+        let conn = Connection::open(&db_path)?;
+        let db = Database { conn, keys: None };
+        db.initialize()?;
         Ok(db)
     }
 
+    /// Opens the vault with the master password: unseals the vault secret,
+    /// then brings the schema up to date, as some migrations need the keys.
+    /// The first login seals a new secret.
+    pub fn open_vault(&mut self, password: &str) -> Result<()> {
+        if self.keys.is_some() {
+            return Ok(());
+        }
+        let secret = match self.get_setting(VAULT_KEY_SETTING_KEY)? {
+            Some(sealed) => crypto::decrypt(&sealed, password)?,
+            // A new secret could not read what the lost one keyed.
+            None if self.has_keyed_data()? => {
+                return Err(LockboxError::VaultKeyUnavailable { detail: "the sealed vault key is missing".to_string() });
+            }
+            None => {
+                let secret = crypto::generate_key();
+                self.set_setting(VAULT_KEY_SETTING_KEY, &crypto::encrypt(&secret, password)?)?;
+                secret
+            }
+        };
+        let log = crypto::derive_subkey(&secret, ACCESS_LOG_KEY_PURPOSE);
+        self.keys = Some(VaultKeys { secret, log });
+
+        self.migrate()?;
+        // Depends on how SQLite was built unless set; per connection, outside a transaction.
+        self.conn.execute_batch("PRAGMA foreign_keys = ON")?;
+        Ok(())
+    }
+
+    pub fn is_vault_open(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// Whether rows keyed by an earlier vault secret are stored: the access
+    /// log chain exists from schema version 4.
+    fn has_keyed_data(&self) -> Result<bool> {
+        let version: i32 = self.conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version < 4 {
+            return Ok(false);
+        }
+        let any = |sql: &str| self.conn.query_row(sql, [], |r| r.get::<_, bool>(0)).unwrap_or(false);
+        Ok(any("SELECT EXISTS(SELECT 1 FROM access_log)")
+            || any("SELECT EXISTS(SELECT 1 FROM access_log_monthly)")
+            || self.get_setting(audit::ANCHOR_SETTING_KEY)?.is_some()
+            || self.get_setting(identity::DEVICE_KEY_SETTING_KEY)?.is_some())
+    }
+
+    fn vault_key(&self) -> Result<&str> {
+        self.keys.as_ref().map(|keys| keys.secret.as_str()).ok_or(LockboxError::MasterPasswordRequired)
+    }
+
+    fn log_key(&self) -> Result<&[u8; crypto::KEY_LENGTH]> {
+        self.keys.as_ref().map(|keys| &keys.log).ok_or(LockboxError::MasterPasswordRequired)
+    }
+
     fn get_old_db_path() -> PathBuf {
         if let Some(data_dir) = dirs::data_local_dir() {
             let app_dir = data_dir.join("com.lockbox.local");
@@ -315,12 +803,6 @@ impl Database {
             [],
         )?;
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_access_log_lockbox ON access_log(lockbox_id, timestamp)",
-            [],
-        )?;
-
-        self.migrate()?;
         Ok(())
     }
 
@@ -344,6 +826,390 @@ impl Database {
             self.conn.execute("PRAGMA user_version = 2", [])?;
         }
 
+        if version < 3 {
+            let _ = self.conn.execute("ALTER TABLE lockboxes ADD COLUMN max_views_per_unlock INTEGER", []);
+            let _ = self.conn.execute("ALTER TABLE lockboxes ADD COLUMN views_this_unlock INTEGER NOT NULL DEFAULT 0", []);
+            self.conn.execute("PRAGMA user_version = 3", [])?;
+        }
+
+        if version < 4 {
+            let _ = self.conn.execute("ALTER TABLE access_log ADD COLUMN prev_hash TEXT", []);
+            let _ = self.conn.execute("ALTER TABLE access_log ADD COLUMN hash TEXT", []);
+            self.rebuild_access_log_chain()?;
+            self.conn.execute("PRAGMA user_version = 4", [])?;
+        }
+
+        if version < 5 {
+            let _ = self.conn.execute("ALTER TABLE access_log ADD COLUMN details TEXT", []);
+            self.conn.execute("PRAGMA user_version = 5", [])?;
+        }
+
+        if version < 6 {
+            self.migrate_access_log_retention()?;
+            self.conn.execute("PRAGMA user_version = 6", [])?;
+        }
+
+        if version < 7 {
+            self.migrate_encrypt_access_log()?;
+            self.conn.execute("PRAGMA user_version = 7", [])?;
+        }
+
+        if version < 8 {
+            self.migrate_access_log_index()?;
+            self.conn.execute("PRAGMA user_version = 8", [])?;
+        }
+
+        if version < 9 {
+            self.migrate_tags()?;
+            self.conn.execute("PRAGMA user_version = 9", [])?;
+        }
+
+        if version < 10 {
+            self.migrate_categories()?;
+            self.conn.execute("PRAGMA user_version = 10", [])?;
+        }
+
+        if version < 11 {
+            self.migrate_search_index()?;
+            self.conn.execute("PRAGMA user_version = 11", [])?;
+        }
+
+        if version < 12 {
+            self.migrate_folders()?;
+            self.conn.execute("PRAGMA user_version = 12", [])?;
+        }
+
+        if version < 13 {
+            self.migrate_delay_raises()?;
+            self.conn.execute("PRAGMA user_version = 13", [])?;
+        }
+
+        Ok(())
+    }
+
+    /// Drops the cascade from `access_log` (deleting a lockbox must not cut
+    /// rows out of the hash chain), records tombstones for lockboxes already
+    /// deleted, and adds the monthly aggregates used by retention.
+    fn migrate_access_log_retention(&self) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+        // Keep the AUTOINCREMENT high-water mark so ids are never reused.
+        let seq: Option<i64> = tx
+            .query_row("SELECT seq FROM sqlite_sequence WHERE name = 'access_log'", [], |r| r.get(0))
+            .ok();
+        tx.execute_batch(
+            "CREATE TABLE access_log_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                lockbox_id INTEGER NOT NULL,
+                event_type TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                prev_hash TEXT,
+                hash TEXT,
+                details TEXT
+            );
+            INSERT INTO access_log_new (id, lockbox_id, event_type, timestamp, prev_hash, hash, details)
+                SELECT id, lockbox_id, event_type, timestamp, prev_hash, hash, details FROM access_log;
+            DROP TABLE access_log;
+            ALTER TABLE access_log_new RENAME TO access_log;
+            CREATE INDEX idx_access_log_lockbox ON access_log(lockbox_id, timestamp);
+            CREATE INDEX idx_access_log_timestamp ON access_log(timestamp, id);
+            CREATE INDEX idx_access_log_event ON access_log(event_type, timestamp);
+
+            CREATE TABLE IF NOT EXISTS lockbox_tombstones (
+                id INTEGER PRIMARY KEY,
+                name TEXT,
+                deleted_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS access_log_monthly (
+                lockbox_id INTEGER NOT NULL,
+                month TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                hour_buckets TEXT NOT NULL,
+                wait_seconds_total INTEGER NOT NULL DEFAULT 0,
+                wait_count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (lockbox_id, month, event_type)
+            );",
+        )?;
+        if let Some(seq) = seq {
+            tx.execute("UPDATE sqlite_sequence SET seq = MAX(seq, ?1) WHERE name = 'access_log'", params![seq])?;
+        }
+        // Names of lockboxes deleted before tombstones existed are lost.
+        tx.execute(
+            "INSERT OR IGNORE INTO lockbox_tombstones (id, name, deleted_at)
+             SELECT DISTINCT lockbox_id, NULL, ?1 FROM access_log
+             WHERE lockbox_id NOT IN (SELECT id FROM lockboxes)",
+            params![now],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Moves `lockbox_id`, `event_type` and `details` of every access log row,
+    /// and the monthly aggregates, into payloads encrypted under the log key.
+    /// Hashes cover the plaintext fields, so the chain carries over unchanged.
+    fn migrate_encrypt_access_log(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let seq: Option<i64> = tx
+            .query_row("SELECT seq FROM sqlite_sequence WHERE name = 'access_log'", [], |r| r.get(0))
+            .ok();
+
+        let rows: Vec<(AccessLogEntry, Option<String>, Option<String>)> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, lockbox_id, event_type, timestamp, details, prev_hash, hash
+                 FROM access_log ORDER BY id ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let details: Option<String> = row.get(4)?;
+                Ok((
+                    AccessLogEntry {
+                        id: row.get(0)?,
+                        lockbox_id: row.get(1)?,
+                        event_type: row.get(2)?,
+                        timestamp: row.get(3)?,
+                        details: details.and_then(|json| serde_json::from_str(&json).ok()),
+                    },
+                    row.get(5)?,
+                    row.get(6)?,
+                ))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut months: BTreeMap<String, Vec<MonthlyAggregate>> = BTreeMap::new();
+        {
+            let mut stmt = tx.prepare(
+                "SELECT lockbox_id, month, event_type, count, hour_buckets, wait_seconds_total, wait_count
+                 FROM access_log_monthly",
+            )?;
+            let mut aggregates = stmt.query([])?;
+            while let Some(row) = aggregates.next()? {
+                let aggregate = MonthlyAggregate {
+                    lockbox_id: row.get(0)?,
+                    month: row.get(1)?,
+                    event_type: row.get(2)?,
+                    count: row.get(3)?,
+                    hour_buckets: serde_json::from_str(&row.get::<_, String>(4)?)?,
+                    wait_seconds_total: row.get(5)?,
+                    wait_count: row.get(6)?,
+                };
+                months.entry(aggregate.month.clone()).or_default().push(aggregate);
+            }
+        }
+
+        tx.execute_batch(
+            "DROP TABLE access_log;
+            CREATE TABLE access_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                payload TEXT NOT NULL,
+                prev_hash TEXT,
+                hash TEXT
+            );
+            CREATE INDEX idx_access_log_timestamp ON access_log(timestamp, id);
+
+            DROP TABLE access_log_monthly;
+            CREATE TABLE access_log_monthly (
+                month TEXT PRIMARY KEY,
+                payload TEXT NOT NULL
+            );",
+        )?;
+        for (entry, prev_hash, hash) in &rows {
+            tx.execute(
+                "INSERT INTO access_log (id, timestamp, payload, prev_hash, hash) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![entry.id, entry.timestamp, encrypt_access_log_payload(entry, self.log_key()?)?, prev_hash, hash],
+            )?;
+        }
+        for (month, aggregates) in &months {
+            self.write_month_aggregates(month, aggregates)?;
+        }
+        if let Some(seq) = seq {
+            tx.execute("UPDATE sqlite_sequence SET seq = MAX(seq, ?1) WHERE name = 'access_log'", params![seq])?;
+        }
+        tx.commit()?;
+
+        // Don't leave the plaintext rows behind in free pages of the file.
+        self.conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    /// Adds the blind indexes of each access log row's lockbox and event
+    /// type, so that filters run in SQL instead of decrypting every row.
+    /// Rows that no longer decrypt are left without, and match no filter.
+    fn migrate_access_log_index(&self) -> Result<()> {
+        let key = self.log_key()?;
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "ALTER TABLE access_log ADD COLUMN lockbox_tag TEXT;
+            ALTER TABLE access_log ADD COLUMN event_tag TEXT;
+            CREATE INDEX IF NOT EXISTS idx_access_log_lockbox ON access_log(lockbox_tag, timestamp, id);
+            CREATE INDEX IF NOT EXISTS idx_access_log_event ON access_log(event_tag, timestamp, id);",
+        )?;
+        let rows: Vec<AccessLogRow> = {
+            let mut stmt = tx.prepare(SELECT_ACCESS_LOG)?;
+            let rows = stmt.query_map([], AccessLogRow::from_row)?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        for row in rows {
+            if let Ok(entry) = row.decrypt(key) {
+                tx.execute(
+                    "UPDATE access_log SET lockbox_tag = ?1, event_tag = ?2 WHERE id = ?3",
+                    params![lockbox_tag(entry.lockbox_id, key), event_tag(&entry.event_type, key), entry.id],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Moves tags from the JSON `lockboxes.tags` column into `tags` and
+    /// `lockbox_tags`, then drops the column.
+    fn migrate_tags(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tags (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS lockbox_tags (
+                lockbox_id INTEGER NOT NULL REFERENCES lockboxes(id) ON DELETE CASCADE,
+                tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (lockbox_id, tag_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_lockbox_tags_tag ON lockbox_tags(tag_id);",
+        )?;
+
+        let rows: Vec<(i64, Option<String>)> = {
+            let mut stmt = tx.prepare("SELECT id, tags FROM lockboxes")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        for (id, tags) in rows {
+            self.set_lockbox_tags(id, &parse_tag_names(tags.as_deref()))?;
+        }
+        tx.execute_batch("ALTER TABLE lockboxes DROP COLUMN tags")?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Creates `categories` with the built-in list, followed by any other
+    /// category name already used by a lockbox.
+    fn migrate_categories(&self) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS categories (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                icon TEXT,
+                color TEXT,
+                position INTEGER NOT NULL,
+                default_unlock_delay_seconds INTEGER,
+                default_relock_delay_seconds INTEGER,
+                default_penalty_seconds INTEGER,
+                default_reflection_checklist TEXT,
+                min_unlock_delay_seconds INTEGER,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );",
+        )?;
+        for (position, (name, icon, color)) in BUILT_IN_CATEGORIES.iter().enumerate() {
+            tx.execute(
+                "INSERT OR IGNORE INTO categories (name, icon, color, position, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                params![name, icon, color, position as i64, now],
+            )?;
+        }
+        let used: Vec<String> = {
+            let mut stmt = tx.prepare(
+                "SELECT DISTINCT category FROM lockboxes
+                 WHERE category IS NOT NULL AND category NOT IN (SELECT name FROM categories)
+                 ORDER BY category",
+            )?;
+            let names = stmt.query_map([], |row| row.get(0))?;
+            names.collect::<rusqlite::Result<_>>()?
+        };
+        for name in used {
+            self.ensure_category(&name)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Creates the full-text index over lockbox metadata and fills it.
+    fn migrate_search_index(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS lockbox_search USING fts5(
+                name, category, tags, reflection,
+                tokenize = 'unicode61 remove_diacritics 2',
+                prefix = '2 3'
+            );",
+        )?;
+        let ids: Vec<i64> = {
+            let mut stmt = tx.prepare("SELECT id FROM lockboxes")?;
+            let ids = stmt.query_map([], |row| row.get(0))?;
+            ids.collect::<rusqlite::Result<_>>()?
+        };
+        for id in ids {
+            self.index_lockbox(id)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Adds the folder tree; every existing lockbox starts at the root.
+    fn migrate_folders(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS folders (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                parent_id INTEGER REFERENCES folders(id),
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_folders_parent ON folders(parent_id);
+            ALTER TABLE lockboxes ADD COLUMN folder_id INTEGER REFERENCES folders(id);
+            CREATE INDEX IF NOT EXISTS idx_lockboxes_folder ON lockboxes(folder_id);",
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Adds the record of delay raises that imports check exports against.
+    /// Rows are keyed by name and outlive the lockbox, so deleting it and
+    /// importing an older export does not bring back shorter delays.
+    fn migrate_delay_raises(&self) -> Result<()> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS lockbox_delay_raises (
+                name TEXT PRIMARY KEY,
+                export_counter INTEGER NOT NULL
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Rewrites the search row of a lockbox from its metadata: name,
+    /// category, tags and reflection text. Content is never indexed. Callers
+    /// run it inside their transaction.
+    fn index_lockbox(&self, id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM lockbox_search WHERE rowid = ?", params![id])?;
+        self.conn.execute(
+            "INSERT INTO lockbox_search (rowid, name, category, tags, reflection)
+             SELECT l.id, l.name, COALESCE(l.category, ''),
+                 COALESCE((SELECT group_concat(t.name, ' ') FROM lockbox_tags lt
+                     JOIN tags t ON t.id = lt.tag_id WHERE lt.lockbox_id = l.id), ''),
+                 trim(COALESCE(l.reflection_message, '') || ' ' || COALESCE((
+                     SELECT group_concat(item.value, ' ') FROM json_each(
+                         CASE WHEN json_valid(l.reflection_checklist) THEN l.reflection_checklist ELSE '[]' END
+                     ) item), ''))
+             FROM lockboxes l WHERE l.id = ?1",
+            params![id],
+        )?;
         Ok(())
     }
 
@@ -369,6 +1235,9 @@ impl Database {
             panic_code_used: row.get::<_, i32>(17).unwrap_or(0) == 1,
             scheduled_unlock_at: row.get(18).unwrap_or(None),
             tags: row.get(19).unwrap_or(None),
+            max_views_per_unlock: row.get(20).unwrap_or(None),
+            views_this_unlock: row.get(21).unwrap_or(0),
+            folder_id: row.get(22).unwrap_or(None),
         })
     }
 
@@ -376,83 +1245,225 @@ impl Database {
         unlock_delay_seconds, relock_delay_seconds, unlock_timestamp, relock_timestamp, \
         created_at, updated_at, reflection_enabled, reflection_message, reflection_checklist, \
         penalty_enabled, penalty_seconds, panic_code_hash, panic_code_used, scheduled_unlock_at, \
-        tags \
+        NULLIF((SELECT json_group_array(name) FROM (SELECT t.name FROM lockbox_tags lt \
+            JOIN tags t ON t.id = lt.tag_id WHERE lt.lockbox_id = lockboxes.id ORDER BY lt.position)), '[]'), \
+        max_views_per_unlock, views_this_unlock, folder_id \
         FROM lockboxes";
 
     pub fn get_all_lockboxes(&self) -> Result<Vec<Lockbox>> {
-        let query = format!("{} ORDER BY name ASC", Self::SELECT_LOCKBOX);
-        let mut stmt = self.conn.prepare(&query)?;
-        let lockboxes = stmt.query_map([], Self::row_to_lockbox)?;
-        lockboxes.collect()
+        self.find_lockboxes(&LockboxFilter::default())
     }
 
-    pub fn get_lockbox(&self, id: i64) -> Result<Option<Lockbox>> {
-        let query = format!("{} WHERE id = ?", Self::SELECT_LOCKBOX);
-        let mut stmt = self.conn.prepare(&query)?;
-        let mut rows = stmt.query(params![id])?;
+    pub fn find_lockboxes(&self, filter: &LockboxFilter) -> Result<Vec<Lockbox>> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
 
-        if let Some(row) = rows.next()? {
+        if let Some(ids) = &filter.ids {
+            if ids.is_empty() {
+                return Ok(Vec::new());
+            }
+            conditions.push(format!("id IN ({})", vec!["?"; ids.len()].join(", ")));
+            values.extend(ids.iter().map(|id| Value::Integer(*id)));
+        }
+        if let Some(category) = &filter.category {
+            conditions.push("category = ?".to_string());
+            values.push(Value::Text(category.clone()));
+        }
+        if let Some(since) = filter.updated_since {
+            conditions.push("updated_at >= ?".to_string());
+            values.push(Value::Integer(since));
+        }
+
+        let tags = clean_tag_names(&filter.tags);
+        if !tags.is_empty() {
+            let placeholders = vec!["?"; tags.len()].join(", ");
+            let having = match filter.tag_match {
+                TagMatch::Any => String::new(),
+                TagMatch::All => format!(" GROUP BY lt.lockbox_id HAVING COUNT(*) = {}", tags.len()),
+            };
+            conditions.push(format!(
+                "id IN (SELECT lt.lockbox_id FROM lockbox_tags lt JOIN tags t ON t.id = lt.tag_id \
+                 WHERE t.name IN ({}){})",
+                placeholders, having
+            ));
+            values.extend(tags.into_iter().map(Value::Text));
+        }
+
+        let query = format!("{}{} ORDER BY name ASC", Self::SELECT_LOCKBOX, where_clause(&conditions));
+        let mut stmt = self.conn.prepare(&query)?;
+        let lockboxes = stmt.query_map(params_from_iter(values.iter()), Self::row_to_lockbox)?;
+        Ok(lockboxes.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Lockboxes whose metadata matches every word of `query` as a prefix,
+    /// best match first.
+    pub fn search_lockboxes(&self, query: &str, limit: u32) -> Result<Vec<Lockbox>> {
+        let Some(expression) = search::match_expression(query) else {
+            return Ok(Vec::new());
+        };
+        let [name, category, tags, reflection] = search::COLUMN_WEIGHTS;
+        let ids: Vec<i64> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT rowid FROM lockbox_search WHERE lockbox_search MATCH ?1
+                 ORDER BY bm25(lockbox_search, ?2, ?3, ?4, ?5), rowid LIMIT ?6",
+            )?;
+            let ids = stmt.query_map(
+                params![expression, name, category, tags, reflection, limit],
+                |row| row.get(0),
+            )?;
+            ids.collect::<rusqlite::Result<_>>()?
+        };
+        let mut lockboxes = Vec::with_capacity(ids.len());
+        for id in ids {
+            lockboxes.extend(self.get_lockbox(id)?);
+        }
+        Ok(lockboxes)
+    }
+
+    pub fn get_lockbox(&self, id: i64) -> Result<Option<Lockbox>> {
+        let query = format!("{} WHERE id = ?", Self::SELECT_LOCKBOX);
+        let mut stmt = self.conn.prepare(&query)?;
+        let mut rows = stmt.query(params![id])?;
+
+        if let Some(row) = rows.next()? {
             Ok(Some(Self::row_to_lockbox(row)?))
         } else {
             Ok(None)
         }
     }
 
+    /// Creates a lockbox, filling the policies left out from its category's
+    /// defaults and holding its unlock delay to the category's floor.
     pub fn create_lockbox(&self, req: CreateLockboxRequest) -> Result<Lockbox> {
+        let tx = self.conn.unchecked_transaction()?;
+        let id = self.write_new_lockbox(req)?;
+        tx.commit()?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
+    }
+
+    /// Inserts `req` inside the caller's transaction and returns its id.
+    fn write_new_lockbox(&self, req: CreateLockboxRequest) -> Result<i64> {
         let now = chrono::Utc::now().timestamp_millis();
 
+        let policies = match req.category.as_deref() {
+            Some(category) => self.ensure_category(category)?,
+            None => CategoryPolicies::default(),
+        };
+        let unlock_delay_seconds = req.unlock_delay_seconds.unwrap_or_else(|| {
+            let default = policies.unlock_delay_seconds.unwrap_or(DEFAULT_UNLOCK_DELAY_SECONDS);
+            default.max(policies.min_unlock_delay_seconds.unwrap_or(0))
+        });
+        if let Some(category) = req.category.as_deref() {
+            policies.check_floor(category, unlock_delay_seconds)?;
+        }
+        let reflection_checklist = req.reflection_checklist.or(policies.reflection_checklist);
+        let reflection_enabled = req.reflection_enabled.unwrap_or(reflection_checklist.is_some());
+        let penalty_enabled = req.penalty_enabled.unwrap_or(policies.penalty_seconds.is_some());
+        let penalty_seconds = req.penalty_seconds.or(policies.penalty_seconds).unwrap_or(0);
+
         self.conn.execute(
             "INSERT INTO lockboxes (name, content, category, is_locked, unlock_delay_seconds,
                 relock_delay_seconds, created_at, updated_at,
                 reflection_enabled, reflection_message, reflection_checklist,
-                penalty_enabled, penalty_seconds, panic_code_hash, scheduled_unlock_at, tags)
+                penalty_enabled, penalty_seconds, panic_code_hash, scheduled_unlock_at,
+                max_views_per_unlock)
              VALUES (?1, ?2, ?3, 1, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 req.name,
                 req.content,
                 req.category,
-                req.unlock_delay_seconds,
-                req.relock_delay_seconds,
+                unlock_delay_seconds,
+                req.relock_delay_seconds
+                    .or(policies.relock_delay_seconds)
+                    .unwrap_or(DEFAULT_RELOCK_DELAY_SECONDS),
                 now,
                 now,
-                req.reflection_enabled as i32,
+                reflection_enabled as i32,
                 req.reflection_message,
-                req.reflection_checklist,
-                req.penalty_enabled as i32,
-                req.penalty_seconds,
+                reflection_checklist,
+                penalty_enabled as i32,
+                penalty_seconds,
                 req.panic_code_hash,
                 req.scheduled_unlock_at,
-                req.tags,
+                req.max_views_per_unlock,
             ],
         )?;
 
         let id = self.conn.last_insert_rowid();
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.set_lockbox_tags(id, &parse_tag_names(req.tags.as_deref()))?;
+        self.index_lockbox(id)?;
+        Ok(id)
+    }
+
+    /// Carries out the writes of an import in one transaction, so that a
+    /// failure midway leaves the vault as it was.
+    pub fn import_lockboxes(&self, writes: Vec<ImportWrite>) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for write in writes {
+            match write {
+                ImportWrite::Create(req) => {
+                    self.write_new_lockbox(req)?;
+                }
+                ImportWrite::Update(req) => {
+                    let id = req.id;
+                    let fields = self.write_lockbox_update(req)?;
+                    self.append_access_event(id, &AccessEvent::FieldUpdated { fields })?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
     }
 
     pub fn update_lockbox(&self, req: UpdateLockboxRequest) -> Result<Lockbox> {
+        let id = req.id;
+        let tx = self.conn.unchecked_transaction()?;
+        let changed_fields = self.write_lockbox_update(req)?;
+        tx.commit()?;
+
+        self.log_access_event(id, &AccessEvent::FieldUpdated { fields: changed_fields })?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
+    }
+
+    /// Writes `req` inside the caller's transaction and returns the names of
+    /// the fields it changes.
+    fn write_lockbox_update(&self, req: UpdateLockboxRequest) -> Result<Vec<String>> {
         let now = chrono::Utc::now().timestamp_millis();
-        let current = self.get_lockbox(req.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_lockbox(req.id)?.ok_or(LockboxError::NotFound { id: req.id })?;
+        let changed_fields = Self::changed_fields(&req, &current);
 
         let category = if req.clear_category { None } else { req.category.or(current.category) };
         let reflection_message = if req.clear_reflection_message { None } else { req.reflection_message.or(current.reflection_message) };
         let reflection_checklist = if req.clear_reflection_checklist { None } else { req.reflection_checklist.or(current.reflection_checklist) };
-        let tags = if req.clear_tags { None } else { req.tags.or(current.tags) };
-
+        let tags = if req.clear_tags { Some(Vec::new()) } else { req.tags.as_deref().map(|json| parse_tag_names(Some(json))) };
+        let max_views_per_unlock = if req.clear_max_views_per_unlock {
+            None
+        } else {
+            req.max_views_per_unlock.or(current.max_views_per_unlock)
+        };
+        let unlock_delay_seconds = req.unlock_delay_seconds.unwrap_or(current.unlock_delay_seconds);
+        let relock_delay_seconds = req.relock_delay_seconds.unwrap_or(current.relock_delay_seconds);
+        let name = req.name.unwrap_or_else(|| current.name.clone());
+        let raised = unlock_delay_seconds > current.unlock_delay_seconds
+            || relock_delay_seconds > current.relock_delay_seconds;
+
+        if let Some(category) = category.as_deref() {
+            self.ensure_category(category)?.check_floor(category, unlock_delay_seconds)?;
+        }
         self.conn.execute(
             "UPDATE lockboxes SET
                 name = ?1, content = ?2, category = ?3,
                 unlock_delay_seconds = ?4, relock_delay_seconds = ?5,
                 reflection_enabled = ?6, reflection_message = ?7, reflection_checklist = ?8,
                 penalty_enabled = ?9, penalty_seconds = ?10, panic_code_hash = ?11,
-                scheduled_unlock_at = ?12, tags = ?13, updated_at = ?14
+                scheduled_unlock_at = ?12, max_views_per_unlock = ?13, updated_at = ?14
              WHERE id = ?15",
             params![
-                req.name.unwrap_or(current.name),
+                name,
                 req.content.unwrap_or(current.content),
                 category,
-                req.unlock_delay_seconds.unwrap_or(current.unlock_delay_seconds),
-                req.relock_delay_seconds.unwrap_or(current.relock_delay_seconds),
+                unlock_delay_seconds,
+                relock_delay_seconds,
                 req.reflection_enabled.unwrap_or(current.reflection_enabled) as i32,
                 reflection_message,
                 reflection_checklist,
@@ -460,24 +1471,613 @@ impl Database {
                 req.penalty_seconds.unwrap_or(current.penalty_seconds),
                 req.panic_code_hash.or(current.panic_code_hash),
                 req.scheduled_unlock_at.or(current.scheduled_unlock_at),
-                tags,
+                max_views_per_unlock,
                 now,
                 req.id,
             ],
         )?;
+        if let Some(tags) = tags {
+            self.set_lockbox_tags(req.id, &tags)?;
+        }
+        if name != current.name {
+            self.conn.execute(
+                "INSERT OR REPLACE INTO lockbox_delay_raises (name, export_counter)
+                 SELECT ?1, export_counter FROM lockbox_delay_raises WHERE name = ?2",
+                params![name, current.name],
+            )?;
+        }
+        if raised {
+            self.record_delay_raise(&name)?;
+        }
+        self.index_lockbox(req.id)?;
+        Ok(changed_fields)
+    }
+
+    /// Names of the fields `req` actually changes, for the `field_updated` event.
+    fn changed_fields(req: &UpdateLockboxRequest, current: &Lockbox) -> Vec<String> {
+        fn differs<T: PartialEq>(new: &Option<T>, old: &T) -> bool {
+            new.as_ref().is_some_and(|new| new != old)
+        }
+        fn option_differs<T: PartialEq>(new: &Option<T>, clear: bool, old: &Option<T>) -> bool {
+            if clear { old.is_some() } else { new.is_some() && new != old }
+        }
 
-        self.log_access_event(req.id, "field_updated")?;
-        self.get_lockbox(req.id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        [
+            ("name", differs(&req.name, &current.name)),
+            // Ciphertext changes on every save, so any submitted content counts.
+            ("content", req.content.is_some()),
+            ("category", option_differs(&req.category, req.clear_category, &current.category)),
+            ("unlock_delay_seconds", differs(&req.unlock_delay_seconds, &current.unlock_delay_seconds)),
+            ("relock_delay_seconds", differs(&req.relock_delay_seconds, &current.relock_delay_seconds)),
+            ("reflection_enabled", differs(&req.reflection_enabled, &current.reflection_enabled)),
+            (
+                "reflection_message",
+                option_differs(&req.reflection_message, req.clear_reflection_message, &current.reflection_message),
+            ),
+            (
+                "reflection_checklist",
+                option_differs(&req.reflection_checklist, req.clear_reflection_checklist, &current.reflection_checklist),
+            ),
+            ("penalty_enabled", differs(&req.penalty_enabled, &current.penalty_enabled)),
+            ("penalty_seconds", differs(&req.penalty_seconds, &current.penalty_seconds)),
+            ("panic_code", req.panic_code_hash.is_some()),
+            ("scheduled_unlock_at", option_differs(&req.scheduled_unlock_at, false, &current.scheduled_unlock_at)),
+            ("tags", option_differs(&req.tags, req.clear_tags, &current.tags)),
+            (
+                "max_views_per_unlock",
+                option_differs(&req.max_views_per_unlock, req.clear_max_views_per_unlock, &current.max_views_per_unlock),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| field.to_string())
+        .collect()
     }
 
+    /// Deletes the lockbox but keeps its access history, with a tombstone so
+    /// the rows still resolve to a name.
     pub fn delete_lockbox(&self, id: i64) -> Result<()> {
-        self.conn.execute("DELETE FROM lockboxes WHERE id = ?", params![id])?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO lockbox_tombstones (id, name, deleted_at)
+             SELECT id, name, ?2 FROM lockboxes WHERE id = ?1",
+            params![id, now],
+        )?;
+        tx.execute("DELETE FROM lockboxes WHERE id = ?", params![id])?;
+        tx.execute("DELETE FROM lockbox_search WHERE rowid = ?", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replaces the tags of a lockbox, creating missing tags. Callers run it
+    /// inside their transaction.
+    fn set_lockbox_tags(&self, lockbox_id: i64, tags: &[String]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn.execute("DELETE FROM lockbox_tags WHERE lockbox_id = ?", params![lockbox_id])?;
+        for (position, name) in tags.iter().enumerate() {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)",
+                params![name, now],
+            )?;
+            self.conn.execute(
+                "INSERT OR IGNORE INTO lockbox_tags (lockbox_id, tag_id, position)
+                 SELECT ?1, id, ?2 FROM tags WHERE name = ?3",
+                params![lockbox_id, position as i64, name],
+            )?;
+        }
         Ok(())
     }
 
+    /// Every tag with its usage count, most used first. Tags left on no
+    /// lockbox are kept until deleted.
+    pub fn list_tags(&self) -> Result<Vec<TagUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, t.name, COUNT(lt.lockbox_id) AS count FROM tags t
+             LEFT JOIN lockbox_tags lt ON lt.tag_id = t.id
+             GROUP BY t.id ORDER BY count DESC, t.name ASC",
+        )?;
+        let tags = stmt.query_map([], |row| {
+            Ok(TagUsage { id: row.get(0)?, name: row.get(1)?, count: row.get(2)? })
+        })?;
+        Ok(tags.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn tag_id(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT id FROM tags WHERE name = ?", params![name], |row| row.get(0))
+            .ok())
+    }
+
+    fn lockboxes_tagged(&self, tag_id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare("SELECT lockbox_id FROM lockbox_tags WHERE tag_id = ?")?;
+        let ids = stmt.query_map(params![tag_id], |row| row.get(0))?;
+        Ok(ids.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Bumps `updated_at` of lockboxes changed by a tag- or category-wide
+    /// operation and refreshes their search rows. Run inside the operation's
+    /// transaction, after the change.
+    fn touch_lockboxes(&self, ids: &[i64]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        for id in ids {
+            self.conn.execute("UPDATE lockboxes SET updated_at = ?1 WHERE id = ?2", params![now, id])?;
+            self.index_lockbox(*id)?;
+        }
+        Ok(())
+    }
+
+    /// Logs the change of `field` on each lockbox, once the operation is committed.
+    fn log_field_updated(&self, ids: &[i64], field: &str) -> Result<()> {
+        for id in ids {
+            self.log_access_event(*id, &AccessEvent::FieldUpdated { fields: vec![field.to_string()] })?;
+        }
+        Ok(())
+    }
+
+    /// Renames a tag on every lockbox carrying it. Renaming onto another
+    /// existing tag is refused; that is a merge.
+    pub fn rename_tag(&self, name: &str, new_name: &str) -> Result<()> {
+        let id = self.tag_id(name)?.ok_or_else(|| LockboxError::TagNotFound { name: name.to_string() })?;
+        if name == new_name {
+            return Ok(());
+        }
+        if self.tag_id(new_name)?.is_some() {
+            return Err(LockboxError::TagExists { name: new_name.to_string() });
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let affected = self.lockboxes_tagged(id)?;
+        tx.execute("UPDATE tags SET name = ?1 WHERE id = ?2", params![new_name, id])?;
+        self.touch_lockboxes(&affected)?;
+        tx.commit()?;
+        self.log_field_updated(&affected, "tags")
+    }
+
+    /// Replaces every `sources` tag by `target`, creating it if needed.
+    /// Lockboxes keep the position of the tag being replaced.
+    pub fn merge_tags(&self, sources: &[String], target: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)", params![target, now])?;
+        let target_id = self.tag_id(target)?.ok_or_else(|| LockboxError::TagNotFound { name: target.to_string() })?;
+
+        let mut affected = Vec::new();
+        for source in sources.iter().filter(|source| source.as_str() != target) {
+            let source_id = self.tag_id(source)?.ok_or_else(|| LockboxError::TagNotFound { name: source.clone() })?;
+            affected.extend(self.lockboxes_tagged(source_id)?);
+            // Lockboxes already carrying the target just lose the source.
+            tx.execute(
+                "UPDATE OR IGNORE lockbox_tags SET tag_id = ?1 WHERE tag_id = ?2",
+                params![target_id, source_id],
+            )?;
+            tx.execute("DELETE FROM lockbox_tags WHERE tag_id = ?", params![source_id])?;
+            tx.execute("DELETE FROM tags WHERE id = ?", params![source_id])?;
+        }
+        affected.sort_unstable();
+        affected.dedup();
+        self.touch_lockboxes(&affected)?;
+        tx.commit()?;
+        self.log_field_updated(&affected, "tags")
+    }
+
+    /// Removes a tag from every lockbox and deletes it.
+    pub fn delete_tag(&self, name: &str) -> Result<()> {
+        let id = self.tag_id(name)?.ok_or_else(|| LockboxError::TagNotFound { name: name.to_string() })?;
+        let tx = self.conn.unchecked_transaction()?;
+        let affected = self.lockboxes_tagged(id)?;
+        tx.execute("DELETE FROM lockbox_tags WHERE tag_id = ?", params![id])?;
+        tx.execute("DELETE FROM tags WHERE id = ?", params![id])?;
+        self.touch_lockboxes(&affected)?;
+        tx.commit()?;
+        self.log_field_updated(&affected, "tags")
+    }
+
+    const SELECT_CATEGORY: &'static str = "SELECT id, name, icon, color, position, \
+        default_unlock_delay_seconds, default_relock_delay_seconds, default_penalty_seconds, \
+        default_reflection_checklist, min_unlock_delay_seconds, \
+        (SELECT COUNT(*) FROM lockboxes WHERE lockboxes.category = categories.name) \
+        FROM categories";
+
+    fn row_to_category(row: &rusqlite::Row) -> rusqlite::Result<Category> {
+        Ok(Category {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            icon: row.get(2)?,
+            color: row.get(3)?,
+            position: row.get(4)?,
+            policies: CategoryPolicies {
+                unlock_delay_seconds: row.get(5)?,
+                relock_delay_seconds: row.get(6)?,
+                penalty_seconds: row.get(7)?,
+                reflection_checklist: row.get(8)?,
+                min_unlock_delay_seconds: row.get(9)?,
+            },
+            lockbox_count: row.get(10)?,
+        })
+    }
+
+    /// Categories in display order.
+    pub fn list_categories(&self) -> Result<Vec<Category>> {
+        let query = format!("{} ORDER BY position ASC, name ASC", Self::SELECT_CATEGORY);
+        let mut stmt = self.conn.prepare(&query)?;
+        let categories = stmt.query_map([], Self::row_to_category)?;
+        Ok(categories.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn get_category(&self, id: i64) -> Result<Category> {
+        let query = format!("{} WHERE id = ?", Self::SELECT_CATEGORY);
+        self.conn
+            .query_row(&query, params![id], Self::row_to_category)
+            .map_err(|_| LockboxError::CategoryNotFound { id })
+    }
+
+    fn category_id(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT id FROM categories WHERE name = ?", params![name], |row| row.get(0))
+            .ok())
+    }
+
+    /// Policies of the category `name`, creating it last in order and without
+    /// defaults if missing. Callers run it inside their transaction.
+    fn ensure_category(&self, name: &str) -> Result<CategoryPolicies> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn.execute(
+            "INSERT OR IGNORE INTO categories (name, position, created_at, updated_at)
+             SELECT ?1, COALESCE(MAX(position) + 1, 0), ?2, ?2 FROM categories",
+            params![name, now],
+        )?;
+        Ok(self.conn.query_row(
+            "SELECT default_unlock_delay_seconds, default_relock_delay_seconds, default_penalty_seconds,
+                    default_reflection_checklist, min_unlock_delay_seconds
+             FROM categories WHERE name = ?",
+            params![name],
+            |row| {
+                Ok(CategoryPolicies {
+                    unlock_delay_seconds: row.get(0)?,
+                    relock_delay_seconds: row.get(1)?,
+                    penalty_seconds: row.get(2)?,
+                    reflection_checklist: row.get(3)?,
+                    min_unlock_delay_seconds: row.get(4)?,
+                })
+            },
+        )?)
+    }
+
+    /// Raises the unlock delay of the category's lockboxes that are below its
+    /// floor, returning their ids. Raising a delay is allowed even while
+    /// locked, so this never loosens a lockbox; each raise is recorded, so no
+    /// earlier export brings the old delay back. Run inside a transaction.
+    fn raise_to_category_floor(&self, name: &str, floor: Option<i64>) -> Result<Vec<i64>> {
+        let Some(floor) = floor else {
+            return Ok(Vec::new());
+        };
+        let below: Vec<(i64, String)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, name FROM lockboxes WHERE category = ?1 AND unlock_delay_seconds < ?2")?;
+            let rows = stmt.query_map(params![name, floor], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        for (id, lockbox_name) in &below {
+            self.conn
+                .execute("UPDATE lockboxes SET unlock_delay_seconds = ?1 WHERE id = ?2", params![floor, id])?;
+            self.record_delay_raise(lockbox_name)?;
+        }
+        let ids: Vec<i64> = below.into_iter().map(|(id, _)| id).collect();
+        self.touch_lockboxes(&ids)?;
+        Ok(ids)
+    }
+
+    pub fn create_category(&self, req: CategoryRequest) -> Result<Category> {
+        if self.category_id(&req.name)?.is_some() {
+            return Err(LockboxError::CategoryExists { name: req.name });
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO categories (name, icon, color, position,
+                default_unlock_delay_seconds, default_relock_delay_seconds, default_penalty_seconds,
+                default_reflection_checklist, min_unlock_delay_seconds, created_at, updated_at)
+             SELECT ?1, ?2, ?3, COALESCE(MAX(position) + 1, 0), ?4, ?5, ?6, ?7, ?8, ?9, ?9 FROM categories",
+            params![
+                req.name,
+                req.icon,
+                req.color,
+                req.policies.unlock_delay_seconds,
+                req.policies.relock_delay_seconds,
+                req.policies.penalty_seconds,
+                req.policies.reflection_checklist,
+                req.policies.min_unlock_delay_seconds,
+                now,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+        self.get_category(id)
+    }
+
+    /// Replaces a category's name, look and policies. A rename carries over
+    /// to its lockboxes, and a new floor raises the delays below it.
+    pub fn update_category(&self, id: i64, req: CategoryRequest) -> Result<Category> {
+        let current = self.get_category(id)?;
+        if req.name != current.name && self.category_id(&req.name)?.is_some() {
+            return Err(LockboxError::CategoryExists { name: req.name });
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE categories SET name = ?1, icon = ?2, color = ?3,
+                default_unlock_delay_seconds = ?4, default_relock_delay_seconds = ?5,
+                default_penalty_seconds = ?6, default_reflection_checklist = ?7,
+                min_unlock_delay_seconds = ?8, updated_at = ?9
+             WHERE id = ?10",
+            params![
+                req.name,
+                req.icon,
+                req.color,
+                req.policies.unlock_delay_seconds,
+                req.policies.relock_delay_seconds,
+                req.policies.penalty_seconds,
+                req.policies.reflection_checklist,
+                req.policies.min_unlock_delay_seconds,
+                now,
+                id,
+            ],
+        )?;
+        let renamed: Vec<i64> = if req.name != current.name {
+            let ids = self.lockboxes_in_category(&current.name)?;
+            tx.execute(
+                "UPDATE lockboxes SET category = ?1 WHERE category = ?2",
+                params![req.name, current.name],
+            )?;
+            self.touch_lockboxes(&ids)?;
+            ids
+        } else {
+            Vec::new()
+        };
+        let raised = self.raise_to_category_floor(&req.name, req.policies.min_unlock_delay_seconds)?;
+        tx.commit()?;
+
+        self.log_field_updated(&renamed, "category")?;
+        self.log_field_updated(&raised, "unlock_delay_seconds")?;
+        self.get_category(id)
+    }
+
+    /// Sets the display order to that of `ids`; categories left out keep
+    /// their position after the listed ones.
+    pub fn reorder_categories(&self, ids: &[i64]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("UPDATE categories SET position = position + ?1", params![ids.len() as i64])?;
+        for (position, id) in ids.iter().enumerate() {
+            let updated = tx.execute(
+                "UPDATE categories SET position = ?1 WHERE id = ?2",
+                params![position as i64, id],
+            )?;
+            if updated == 0 {
+                return Err(LockboxError::CategoryNotFound { id: *id });
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Deletes a category; its lockboxes become uncategorized.
+    pub fn delete_category(&self, id: i64) -> Result<()> {
+        let category = self.get_category(id)?;
+        let tx = self.conn.unchecked_transaction()?;
+        let affected = self.lockboxes_in_category(&category.name)?;
+        tx.execute("UPDATE lockboxes SET category = NULL WHERE category = ?", params![category.name])?;
+        self.touch_lockboxes(&affected)?;
+        tx.execute("DELETE FROM categories WHERE id = ?", params![id])?;
+        tx.commit()?;
+        self.log_field_updated(&affected, "category")
+    }
+
+    fn lockboxes_in_category(&self, name: &str) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare("SELECT id FROM lockboxes WHERE category = ?")?;
+        let ids = stmt.query_map(params![name], |row| row.get(0))?;
+        Ok(ids.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Every folder, siblings sorted by name.
+    pub fn list_folders(&self) -> Result<Vec<Folder>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT f.id, f.name, f.parent_id,
+                (SELECT COUNT(*) FROM lockboxes WHERE lockboxes.folder_id = f.id)
+             FROM folders f ORDER BY f.name COLLATE NOCASE, f.id",
+        )?;
+        let folders = stmt.query_map([], |row| {
+            Ok(Folder { id: row.get(0)?, name: row.get(1)?, parent_id: row.get(2)?, lockbox_count: row.get(3)? })
+        })?;
+        Ok(folders.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_folder(&self, id: i64) -> Result<Folder> {
+        self.conn
+            .query_row(
+                "SELECT id, name, parent_id, (SELECT COUNT(*) FROM lockboxes WHERE folder_id = ?1)
+                 FROM folders WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(Folder { id: row.get(0)?, name: row.get(1)?, parent_id: row.get(2)?, lockbox_count: row.get(3)? })
+                },
+            )
+            .map_err(|_| LockboxError::FolderNotFound { id })
+    }
+
+    /// Refuses a name already used by another folder under the same parent.
+    fn check_folder_name(&self, name: &str, parent_id: Option<i64>, except: Option<i64>) -> Result<()> {
+        let taken: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM folders WHERE name = ?1 AND parent_id IS ?2 AND id IS NOT ?3)",
+            params![name, parent_id, except],
+            |row| row.get(0),
+        )?;
+        if taken {
+            return Err(LockboxError::FolderExists { name: name.to_string() });
+        }
+        Ok(())
+    }
+
+    pub fn create_folder(&self, name: &str, parent_id: Option<i64>) -> Result<Folder> {
+        if let Some(parent_id) = parent_id {
+            self.get_folder(parent_id)?;
+        }
+        self.check_folder_name(name, parent_id, None)?;
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn.execute(
+            "INSERT INTO folders (name, parent_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+            params![name, parent_id, now],
+        )?;
+        self.get_folder(self.conn.last_insert_rowid())
+    }
+
+    pub fn rename_folder(&self, id: i64, name: &str) -> Result<Folder> {
+        let folder = self.get_folder(id)?;
+        self.check_folder_name(name, folder.parent_id, Some(id))?;
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn.execute(
+            "UPDATE folders SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, now, id],
+        )?;
+        self.get_folder(id)
+    }
+
+    /// Moves a folder, with its whole subtree, under `parent_id` (the root
+    /// when `None`). A folder cannot move into itself or a descendant.
+    pub fn move_folder(&self, id: i64, parent_id: Option<i64>) -> Result<Folder> {
+        let folder = self.get_folder(id)?;
+        if let Some(parent_id) = parent_id {
+            self.get_folder(parent_id)?;
+            if self.folder_subtree(id)?.contains(&parent_id) {
+                return Err(LockboxError::FolderCycle { id, parent_id });
+            }
+        }
+        self.check_folder_name(&folder.name, parent_id, Some(id))?;
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn.execute(
+            "UPDATE folders SET parent_id = ?1, updated_at = ?2 WHERE id = ?3",
+            params![parent_id, now, id],
+        )?;
+        self.get_folder(id)
+    }
+
+    /// Deletes a folder; its lockboxes and subfolders move up to its parent.
+    pub fn delete_folder(&self, id: i64) -> Result<()> {
+        let folder = self.get_folder(id)?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+        let affected = self.lockboxes_in_folders(&[id])?;
+        for child in self.child_folders(id)? {
+            let name = self.get_folder(child)?.name;
+            self.check_folder_name(&name, folder.parent_id, Some(child))?;
+            tx.execute(
+                "UPDATE folders SET parent_id = ?1, updated_at = ?2 WHERE id = ?3",
+                params![folder.parent_id, now, child],
+            )?;
+        }
+        tx.execute("UPDATE lockboxes SET folder_id = ?1 WHERE folder_id = ?2", params![folder.parent_id, id])?;
+        self.touch_lockboxes(&affected)?;
+        tx.execute("DELETE FROM folders WHERE id = ?", params![id])?;
+        tx.commit()?;
+        self.log_field_updated(&affected, "folder")
+    }
+
+    /// Moves lockboxes into `folder_id`, or to the root when `None`.
+    pub fn move_lockboxes(&self, ids: &[i64], folder_id: Option<i64>) -> Result<()> {
+        if let Some(folder_id) = folder_id {
+            self.get_folder(folder_id)?;
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        let mut moved = Vec::new();
+        for id in ids {
+            let current = self.get_lockbox(*id)?.ok_or(LockboxError::NotFound { id: *id })?;
+            if current.folder_id != folder_id {
+                tx.execute("UPDATE lockboxes SET folder_id = ?1 WHERE id = ?2", params![folder_id, id])?;
+                moved.push(*id);
+            }
+        }
+        self.touch_lockboxes(&moved)?;
+        tx.commit()?;
+        self.log_field_updated(&moved, "folder")
+    }
+
+    fn child_folders(&self, id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare("SELECT id FROM folders WHERE parent_id = ? ORDER BY id")?;
+        let ids = stmt.query_map(params![id], |row| row.get(0))?;
+        Ok(ids.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// The folder and all of its descendants.
+    fn folder_subtree(&self, id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE subtree(id) AS (
+                SELECT ?1
+                UNION SELECT f.id FROM folders f JOIN subtree s ON f.parent_id = s.id
+             )
+             SELECT id FROM subtree",
+        )?;
+        let ids = stmt.query_map(params![id], |row| row.get(0))?;
+        Ok(ids.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn lockboxes_in_folders(&self, folder_ids: &[i64]) -> Result<Vec<i64>> {
+        let placeholders = vec!["?"; folder_ids.len()].join(", ");
+        let query = format!("SELECT id FROM lockboxes WHERE folder_id IN ({}) ORDER BY id", placeholders);
+        let mut stmt = self.conn.prepare(&query)?;
+        let ids = stmt.query_map(params_from_iter(folder_ids.iter()), |row| row.get(0))?;
+        Ok(ids.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Lockboxes of a folder and of all its subfolders.
+    pub fn lockboxes_in_folder_tree(&self, id: i64) -> Result<Vec<Lockbox>> {
+        self.get_folder(id)?;
+        let mut lockboxes = Vec::new();
+        for lockbox_id in self.lockboxes_in_folders(&self.folder_subtree(id)?)? {
+            lockboxes.extend(self.get_lockbox(lockbox_id)?);
+        }
+        Ok(lockboxes)
+    }
+
+    /// Relocks every unlocked lockbox of the folder tree as `relock_lockbox`
+    /// does, all or none; locked boxes and running countdowns are left alone.
+    pub fn relock_folder(&self, id: i64) -> Result<Vec<Lockbox>> {
+        let unlocked: Vec<i64> = self
+            .lockboxes_in_folder_tree(id)?
+            .into_iter()
+            .filter(|lockbox| !lockbox.is_locked)
+            .map(|lockbox| lockbox.id)
+            .collect();
+        let tx = self.conn.unchecked_transaction()?;
+        for &lockbox_id in &unlocked {
+            self.write_relock(lockbox_id, &AccessEvent::RelockManual {})?;
+        }
+        tx.commit()?;
+        self.lockboxes_by_id(&unlocked)
+    }
+
+    /// Extends the unlock delay of every lockbox of the folder tree as
+    /// `extend_unlock_delay` does, all or none.
+    pub fn extend_folder_delays(&self, id: i64, additional_seconds: i64) -> Result<Vec<Lockbox>> {
+        let ids: Vec<i64> = self.lockboxes_in_folder_tree(id)?.into_iter().map(|lockbox| lockbox.id).collect();
+        let tx = self.conn.unchecked_transaction()?;
+        for &lockbox_id in &ids {
+            self.write_delay_extension(lockbox_id, additional_seconds)?;
+        }
+        tx.commit()?;
+        self.lockboxes_by_id(&ids)
+    }
+
+    fn lockboxes_by_id(&self, ids: &[i64]) -> Result<Vec<Lockbox>> {
+        ids.iter().map(|&id| self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })).collect()
+    }
+
     pub fn unlock_lockbox(&self, id: i64) -> Result<Lockbox> {
         let now = chrono::Utc::now().timestamp_millis();
-        let current = self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
 
         let unlock_timestamp = now + (current.unlock_delay_seconds * 1000);
 
@@ -486,19 +2086,26 @@ impl Database {
             params![unlock_timestamp, now, id],
         )?;
 
-        self.log_access_event(id, "unlock_requested")?;
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.log_access_event(
+            id,
+            &AccessEvent::UnlockRequested {
+                unlock_delay_seconds: current.unlock_delay_seconds,
+                unlock_at: unlock_timestamp,
+            },
+        )?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
     }
 
     pub fn cancel_unlock(&self, id: i64) -> Result<Lockbox> {
         let now = chrono::Utc::now().timestamp_millis();
-        let current = self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
 
-        let new_delay = if current.penalty_enabled {
-            current.unlock_delay_seconds + current.penalty_seconds
-        } else {
-            current.unlock_delay_seconds
-        };
+        let penalty_seconds = if current.penalty_enabled { current.penalty_seconds } else { 0 };
+        let new_delay = current.unlock_delay_seconds + penalty_seconds;
+        // The countdown started `unlock_delay_seconds` before its target (extensions move both).
+        let waited_seconds = current
+            .unlock_timestamp
+            .map(|ts| (now - (ts - current.unlock_delay_seconds * 1000)).max(0) / 1000);
 
         self.conn.execute(
             "UPDATE lockboxes SET is_locked = 1, unlock_timestamp = NULL, scheduled_unlock_at = NULL,
@@ -506,14 +2113,33 @@ impl Database {
              WHERE id = ?3",
             params![new_delay, now, id],
         )?;
+        if penalty_seconds > 0 {
+            self.record_delay_raise(&current.name)?;
+        }
 
-        self.log_access_event(id, "unlock_cancelled")?;
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.log_access_event(
+            id,
+            &AccessEvent::UnlockCancelled {
+                penalty_seconds,
+                unlock_delay_seconds: new_delay,
+                waited_seconds,
+            },
+        )?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
     }
 
     pub fn extend_unlock_delay(&self, id: i64, additional_seconds: i64) -> Result<Lockbox> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.write_delay_extension(id, additional_seconds)?;
+        tx.commit()?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
+    }
+
+    /// Extends the delay, records the raise and logs it inside the caller's
+    /// transaction.
+    fn write_delay_extension(&self, id: i64, additional_seconds: i64) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
-        let current = self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
 
         let additional_ms = additional_seconds * 1000;
         let new_delay = current.unlock_delay_seconds + additional_seconds;
@@ -527,29 +2153,47 @@ impl Database {
              WHERE id = ?5",
             params![new_delay, new_unlock_timestamp, new_scheduled, now, id],
         )?;
+        self.record_delay_raise(&current.name)?;
 
-        self.log_access_event(id, "extend_delay")?;
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.append_access_event(
+            id,
+            &AccessEvent::ExtendDelay { additional_seconds, unlock_delay_seconds: new_delay },
+        )
     }
 
     pub fn complete_unlock(&self, id: i64) -> Result<Lockbox> {
         let now = chrono::Utc::now().timestamp_millis();
-        let current = self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
 
         let relock_timestamp = now + (current.relock_delay_seconds * 1000);
 
         self.conn.execute(
             "UPDATE lockboxes SET is_locked = 0, unlock_timestamp = NULL,
-                relock_timestamp = ?1, updated_at = ?2
+                relock_timestamp = ?1, views_this_unlock = 0, updated_at = ?2
              WHERE id = ?3",
             params![relock_timestamp, now, id],
         )?;
 
-        self.log_access_event(id, "unlock_completed")?;
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.log_access_event(
+            id,
+            &AccessEvent::UnlockCompleted { relock_delay_seconds: current.relock_delay_seconds },
+        )?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
     }
 
     pub fn relock_lockbox(&self, id: i64) -> Result<Lockbox> {
+        self.relock_with_event(id, &AccessEvent::RelockManual {})
+    }
+
+    fn relock_with_event(&self, id: i64, event: &AccessEvent) -> Result<Lockbox> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.write_relock(id, event)?;
+        tx.commit()?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
+    }
+
+    /// Relocks the box and logs `event` inside the caller's transaction.
+    fn write_relock(&self, id: i64, event: &AccessEvent) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
 
         self.conn.execute(
@@ -559,12 +2203,65 @@ impl Database {
             params![now, id],
         )?;
 
-        self.log_access_event(id, "relock_manual")?;
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.append_access_event(id, event)
+    }
+
+    /// Logs a content view and relocks the box once its per-unlock view limit is reached.
+    /// `updated_at` is left untouched so the frontend does not refetch in a loop.
+    pub fn record_content_view(&self, id: i64) -> Result<Lockbox> {
+        self.count_view(id, |views_this_unlock, max_views_per_unlock| AccessEvent::ContentViewed {
+            views_this_unlock,
+            max_views_per_unlock,
+        })
+    }
+
+    /// Logs the export of the contents of `ids` in clear as `format`, each
+    /// counted as a view, in one transaction.
+    pub fn record_content_exports(&self, ids: &[i64], format: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for &id in ids {
+            self.write_view(id, |views_this_unlock, max_views_per_unlock| AccessEvent::ContentExported {
+                format: format.to_string(),
+                views_this_unlock,
+                max_views_per_unlock,
+            })?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Counts a view, logs `event` built from the new count and limit, and
+    /// relocks the box once the limit is reached.
+    fn count_view(&self, id: i64, event: impl FnOnce(i64, Option<i64>) -> AccessEvent) -> Result<Lockbox> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.write_view(id, event)?;
+        tx.commit()?;
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
+    }
+
+    /// `count_view` inside the caller's transaction.
+    fn write_view(&self, id: i64, event: impl FnOnce(i64, Option<i64>) -> AccessEvent) -> Result<()> {
+        self.conn.execute(
+            "UPDATE lockboxes SET views_this_unlock = views_this_unlock + 1 WHERE id = ?1",
+            params![id],
+        )?;
+        let current = self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
+        self.append_access_event(id, &event(current.views_this_unlock, current.max_views_per_unlock))?;
+
+        match current.max_views_per_unlock {
+            Some(max) if current.views_this_unlock >= max => {
+                self.write_relock(id, &AccessEvent::ViewLimitRelocked { views: current.views_this_unlock })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_content_copy(&self, id: i64) -> Result<()> {
+        self.log_access_event(id, &AccessEvent::ContentCopied {})
     }
 
     pub fn use_panic_code(&self, id: i64, code_hash: &str) -> Result<Option<Lockbox>> {
-        let current = self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let current = self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?;
 
         let stored_hash = match &current.panic_code_hash {
             Some(h) => h.clone(),
@@ -584,13 +2281,16 @@ impl Database {
 
         self.conn.execute(
             "UPDATE lockboxes SET is_locked = 0, unlock_timestamp = NULL, scheduled_unlock_at = NULL,
-                relock_timestamp = ?1, panic_code_used = 1, updated_at = ?2
+                relock_timestamp = ?1, panic_code_used = 1, views_this_unlock = 0, updated_at = ?2
              WHERE id = ?3",
             params![relock_timestamp, now, id],
         )?;
 
-        self.log_access_event(id, "panic_used")?;
-        Ok(Some(self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?))
+        self.log_access_event(
+            id,
+            &AccessEvent::PanicUsed { relock_delay_seconds: current.relock_delay_seconds },
+        )?;
+        Ok(Some(self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })?))
     }
 
     pub fn reset_panic_code(&self, id: i64, new_code_hash: Option<&str>) -> Result<Lockbox> {
@@ -602,52 +2302,863 @@ impl Database {
             params![new_code_hash, now, id],
         )?;
 
-        self.get_lockbox(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        self.get_lockbox(id)?.ok_or(LockboxError::NotFound { id })
     }
 
-    pub fn log_access_event(&self, lockbox_id: i64, event_type: &str) -> Result<()> {
+    pub fn log_access_event(&self, lockbox_id: i64, event: &AccessEvent) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.append_access_event(lockbox_id, event)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Chains `event` onto the access log inside the caller's transaction.
+    fn append_access_event(&self, lockbox_id: i64, event: &AccessEvent) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
+        let (event_type, details) = event.to_record()?;
+        let prev_hash = self.access_log_tip()?;
+
+        // The row id is part of the hash, so it is known only after the insert.
+        self.conn.execute(
+            "INSERT INTO access_log (timestamp, payload, prev_hash, lockbox_tag, event_tag)
+             VALUES (?1, '', ?2, ?3, ?4)",
+            params![now, prev_hash, lockbox_tag(lockbox_id, self.log_key()?), event_tag(&event_type, self.log_key()?)],
+        )?;
+        let entry = AccessLogEntry {
+            id: self.conn.last_insert_rowid(),
+            lockbox_id,
+            event_type,
+            timestamp: now,
+            details: Some(details),
+        };
+        let hash = audit::entry_hash(self.vault_key()?, &prev_hash, &entry);
         self.conn.execute(
-            "INSERT INTO access_log (lockbox_id, event_type, timestamp) VALUES (?1, ?2, ?3)",
-            params![lockbox_id, event_type, now],
+            "UPDATE access_log SET payload = ?1, hash = ?2 WHERE id = ?3",
+            params![encrypt_access_log_payload(&entry, self.log_key()?)?, hash, entry.id],
         )?;
+        self.set_chain_head(&ChainHead::new(self.vault_key()?, entry.id, &hash))
+    }
+
+    /// Hash the next access log row chains onto. Taken from the authenticated
+    /// head rather than the last row, so rows deleted from the end of the log
+    /// leave a broken link instead of being silently papered over.
+    fn access_log_tip(&self) -> Result<String> {
+        let vault_key = self.vault_key()?;
+        if let Some(head) = self.chain_head()?.filter(|h| h.is_authentic(vault_key)) {
+            return Ok(head.hash);
+        }
+        let last_hash: Option<Option<String>> = self
+            .conn
+            .query_row("SELECT hash FROM access_log ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+            .ok();
+        match last_hash.flatten() {
+            Some(hash) => Ok(hash),
+            None => Ok(self.chain_anchor()?.map(|a| a.genesis).unwrap_or_default()),
+        }
+    }
+
+    fn chain_anchor(&self) -> Result<Option<ChainAnchor>> {
+        Ok(self
+            .get_setting(audit::ANCHOR_SETTING_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    fn chain_head(&self) -> Result<Option<ChainHead>> {
+        Ok(self
+            .get_setting(audit::HEAD_SETTING_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    fn set_chain_head(&self, head: &ChainHead) -> Result<()> {
+        self.set_setting(audit::HEAD_SETTING_KEY, &serde_json::to_string(head)?)
+    }
+
+    /// Starts a new chain from a fresh anchor and re-hashes every existing
+    /// row onto it. Only run when the chain is first introduced.
+    fn rebuild_access_log_chain(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let anchor = ChainAnchor::new(self.vault_key()?, chrono::Utc::now().timestamp_millis());
+        self.set_setting(audit::ANCHOR_SETTING_KEY, &serde_json::to_string(&anchor)?)?;
+
+        let entries: Vec<AccessLogEntry> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, lockbox_id, event_type, timestamp FROM access_log ORDER BY id ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(AccessLogEntry {
+                    id: row.get(0)?,
+                    lockbox_id: row.get(1)?,
+                    event_type: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    // The details column is added after the chain (migration 5).
+                    details: None,
+                })
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut prev_hash = anchor.genesis.clone();
+        for entry in &entries {
+            let hash = audit::entry_hash(self.vault_key()?, &prev_hash, entry);
+            tx.execute(
+                "UPDATE access_log SET prev_hash = ?1, hash = ?2 WHERE id = ?3",
+                params![prev_hash, hash, entry.id],
+            )?;
+            prev_hash = hash;
+        }
+        if let Some(last) = entries.last() {
+            self.set_chain_head(&ChainHead::new(self.vault_key()?, last.id, &prev_hash))?;
+        }
+
+        tx.commit()?;
         Ok(())
     }
 
-    pub fn get_access_log(&self, lockbox_id: i64) -> Result<Vec<AccessLogEntry>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, lockbox_id, event_type, timestamp FROM access_log
-             WHERE lockbox_id = ? ORDER BY timestamp DESC LIMIT 50",
+    /// Calls `f` for every access log entry, oldest first, without loading
+    /// the whole log in memory. Rows that no longer decrypt are skipped;
+    /// `verify_access_log` reports them.
+    pub fn for_each_access_log_entry(&self, mut f: impl FnMut(AccessLogEntry)) -> Result<()> {
+        let mut stmt = self.conn.prepare(&format!("{} ORDER BY timestamp ASC, id ASC", SELECT_ACCESS_LOG))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            if let Ok(entry) = AccessLogRow::from_row(row)?.decrypt(self.log_key()?) {
+                f(entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Walks the whole access log chain.
+    pub fn verify_access_log(&self) -> Result<ChainReport> {
+        let mut verifier = ChainVerifier::new(self.vault_key()?, self.chain_anchor()?, self.chain_head()?);
+
+        let mut stmt = self.conn.prepare(&format!("{} ORDER BY id ASC", SELECT_ACCESS_LOG))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let row = AccessLogRow::from_row(row)?;
+            match row.decrypt(self.log_key()?) {
+                Ok(entry) => verifier.push(&entry, row.prev_hash.as_deref(), row.hash.as_deref()),
+                Err(_) => verifier.push_unreadable(row.id, row.hash.as_deref()),
+            }
+        }
+
+        Ok(verifier.finish())
+    }
+
+    /// Cheap startup check: the anchor is authentic and the oldest row still
+    /// chains onto it.
+    pub fn verify_access_log_anchor(&self) -> Result<bool> {
+        let vault_key = self.vault_key()?;
+        let Some(anchor) = self.chain_anchor()?.filter(|a| a.is_authentic(vault_key)) else {
+            return Ok(false);
+        };
+
+        let first = self
+            .conn
+            .query_row(&format!("{} ORDER BY id ASC LIMIT 1", SELECT_ACCESS_LOG), [], AccessLogRow::from_row)
+            .ok();
+
+        match first {
+            Some(row) => {
+                let Ok(entry) = row.decrypt(self.log_key()?) else {
+                    return Ok(false);
+                };
+                let prev_hash = row.prev_hash.unwrap_or_default();
+                Ok(prev_hash == anchor.genesis
+                    && row.hash.as_deref() == Some(audit::entry_hash(self.vault_key()?, &prev_hash, &entry).as_str()))
+            }
+            // An empty log is only expected if nothing was ever logged, or
+            // if everything was compacted and the anchor moved to the head.
+            None => Ok(self.chain_head()?.is_none_or(|head| head.hash == anchor.genesis)),
+        }
+    }
+
+    /// Rolls access log rows older than `cutoff` into `access_log_monthly`,
+    /// optionally archives them, and removes them. Only a prefix of the chain
+    /// is removed, up to the first row at or after `cutoff`, and the anchor is
+    /// moved onto the last removed hash so the remaining rows still verify.
+    pub fn compact_access_log(&self, cutoff: i64, archive: bool) -> Result<CompactionReport> {
+        let chain = self.verify_access_log()?;
+        if !chain.valid {
+            return Err(LockboxError::AccessLogTampered { first_broken_id: chain.first_broken_id });
+        }
+
+        let first_kept: Option<i64> = self.conn.query_row(
+            "SELECT MIN(id) FROM access_log WHERE timestamp >= ?",
+            params![cutoff],
+            |row| row.get(0),
         )?;
+        let removed: Vec<ArchivedEntry> = {
+            let mut stmt = self.conn.prepare(&format!("{} WHERE id < ? ORDER BY id ASC", SELECT_ACCESS_LOG))?;
+            let rows = stmt.query_map(params![first_kept.unwrap_or(i64::MAX)], AccessLogRow::from_row)?;
+            let mut removed = Vec::new();
+            for row in rows {
+                let row = row?;
+                removed.push(ArchivedEntry {
+                    entry: row.decrypt(self.log_key()?)?,
+                    prev_hash: row.prev_hash,
+                    hash: row.hash,
+                });
+            }
+            removed
+        };
+        let Some(last) = removed.last() else {
+            return Ok(CompactionReport { cutoff: Some(cutoff), ..Default::default() });
+        };
+        let last_id = last.entry.id;
+        let last_hash = last.hash.clone().unwrap_or_default();
 
-        let entries = stmt.query_map(params![lockbox_id], |row| {
-            Ok(AccessLogEntry {
-                id: row.get(0)?,
-                lockbox_id: row.get(1)?,
-                event_type: row.get(2)?,
-                timestamp: row.get(3)?,
-            })
-        })?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let entries: Vec<AccessLogEntry> = removed.iter().map(|r| r.entry.clone()).collect();
+        let aggregates = retention::aggregate(&entries);
+        let removed_entries = removed.len() as i64;
+        // Written under a temporary name, and only given its own once the
+        // entries are gone, so that a failed compaction leaves no archive of
+        // entries still in the log.
+        let pending = if archive {
+            Some(self.write_access_log_archive(AccessLogArchive {
+                version: retention::ARCHIVE_VERSION,
+                created_at: now,
+                cutoff,
+                entries: removed,
+            })?)
+        } else {
+            None
+        };
+
+        if let Err(e) = self.delete_compacted(&aggregates, last_id, &last_hash, now) {
+            if let Some((temp, _)) = &pending {
+                let _ = std::fs::remove_file(temp);
+            }
+            return Err(e);
+        }
+        let archive_file = match pending {
+            Some((temp, path)) => {
+                std::fs::rename(&temp, &path).map_err(|e| LockboxError::ArchiveFailed { detail: e.to_string() })?;
+                Some(path.display().to_string())
+            }
+            None => None,
+        };
+
+        Ok(CompactionReport {
+            cutoff: Some(cutoff),
+            removed_entries,
+            aggregates_updated: aggregates.len() as i64,
+            archive_file,
+        })
+    }
+
+    /// Replaces the entries up to `last_id` with `aggregates` and anchors the
+    /// chain at `last_hash`, in one transaction.
+    fn delete_compacted(&self, aggregates: &[MonthlyAggregate], last_id: i64, last_hash: &str, now: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.merge_access_log_aggregates(aggregates)?;
+        tx.execute("DELETE FROM access_log WHERE id <= ?", params![last_id])?;
+        let anchor = ChainAnchor::at(self.vault_key()?, last_hash, now);
+        self.set_setting(audit::ANCHOR_SETTING_KEY, &serde_json::to_string(&anchor)?)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_archive_dir() -> PathBuf {
+        if let Some(data_dir) = dirs::data_local_dir() {
+            data_dir.join("com.lockbox.local").join("archive")
+        } else {
+            PathBuf::from("lockbox_archive")
+        }
+    }
+
+    /// Writes `archive` encrypted under the vault key to a temporary file;
+    /// returns it with the path to rename it to.
+    fn write_access_log_archive(&self, archive: AccessLogArchive) -> Result<(PathBuf, PathBuf)> {
+        let failed = |e: std::io::Error| LockboxError::ArchiveFailed { detail: e.to_string() };
+        let dir = Self::get_archive_dir();
+        std::fs::create_dir_all(&dir).map_err(failed)?;
+
+        let first_id = archive.entries.first().map_or(0, |e| e.entry.id);
+        let last_id = archive.entries.last().map_or(0, |e| e.entry.id);
+        let path = dir.join(format!("access_log_{}-{}.enc", first_id, last_id));
+        let temp = path.with_extension("enc.tmp");
+        let encrypted = crypto::encrypt(&serde_json::to_string(&archive)?, self.vault_key()?)?;
+        std::fs::write(&temp, encrypted).map_err(failed)?;
+        Ok((temp, path))
+    }
+
+    /// Adds `aggregates` to the stored monthly roll-ups, one encrypted
+    /// payload per month.
+    fn merge_access_log_aggregates(&self, aggregates: &[MonthlyAggregate]) -> Result<()> {
+        let mut months: BTreeMap<&str, Vec<MonthlyAggregate>> = BTreeMap::new();
+        for aggregate in aggregates {
+            let month = match months.entry(aggregate.month.as_str()) {
+                std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::btree_map::Entry::Vacant(entry) => {
+                    entry.insert(self.read_month_aggregates(&aggregate.month)?.unwrap_or_default())
+                }
+            };
+            retention::merge_into(month, aggregate);
+        }
+        for (month, aggregates) in &months {
+            self.write_month_aggregates(month, aggregates)?;
+        }
+        Ok(())
+    }
+
+    fn read_month_aggregates(&self, month: &str) -> Result<Option<Vec<MonthlyAggregate>>> {
+        let payload: Option<String> = self
+            .conn
+            .query_row("SELECT payload FROM access_log_monthly WHERE month = ?", params![month], |row| row.get(0))
+            .ok();
+        payload
+            .map(|payload| Ok(serde_json::from_str(&crypto::decrypt_with_key(&payload, self.log_key()?)?)?))
+            .transpose()
+    }
+
+    fn write_month_aggregates(&self, month: &str, aggregates: &[MonthlyAggregate]) -> Result<()> {
+        let payload = crypto::encrypt_with_key(&serde_json::to_string(aggregates)?, self.log_key()?)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO access_log_monthly (month, payload) VALUES (?1, ?2)",
+            params![month, payload],
+        )?;
+        Ok(())
+    }
+
+    /// Monthly roll-ups of compacted access log rows. Months that no longer
+    /// decrypt are skipped.
+    pub fn get_access_log_aggregates(&self) -> Result<Vec<MonthlyAggregate>> {
+        let log_key = self.log_key()?;
+        let mut stmt = self.conn.prepare("SELECT payload FROM access_log_monthly ORDER BY month ASC")?;
+        let payloads = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(payloads
+            .iter()
+            .filter_map(|payload| crypto::decrypt_with_key(payload, log_key).ok())
+            .filter_map(|json| serde_json::from_str::<Vec<MonthlyAggregate>>(&json).ok())
+            .flatten()
+            .collect())
+    }
+
+    pub fn query_access_log(&self, query: &AccessLogQuery) -> Result<AccessLogPage> {
+        let limit = query
+            .limit
+            .unwrap_or(ACCESS_LOG_DEFAULT_PAGE_SIZE)
+            .clamp(1, ACCESS_LOG_MAX_PAGE_SIZE) as usize;
+        let cursor = query.cursor.as_deref().map(decode_cursor).transpose()?;
+        let key = self.log_key()?;
+        let (mut conditions, mut values) = access_log_conditions(query, key);
+
+        let counts_by_event = if query.with_counts {
+            Some(self.count_access_log(&conditions, &values)?)
+        } else {
+            None
+        };
 
-        entries.collect()
+        if let Some((timestamp, id)) = cursor {
+            conditions.push("(timestamp < ? OR (timestamp = ? AND id < ?))".to_string());
+            values.extend([Value::Integer(timestamp), Value::Integer(timestamp), Value::Integer(id)]);
+        }
+        let mut stmt = self.conn.prepare(&format!(
+            "{}{} ORDER BY timestamp DESC, id DESC",
+            SELECT_ACCESS_LOG,
+            where_clause(&conditions)
+        ))?;
+        let mut rows = stmt.query(params_from_iter(values.iter()))?;
+        let mut entries = Vec::new();
+        let mut has_more = false;
+        while let Some(row) = rows.next()? {
+            let Ok(entry) = AccessLogRow::from_row(row)?.decrypt(key) else {
+                continue;
+            };
+            if entries.len() == limit {
+                has_more = true;
+                break;
+            }
+            entries.push(entry);
+        }
+        let next_cursor = if has_more { entries.last().map(encode_cursor) } else { None };
+
+        Ok(AccessLogPage {
+            entries,
+            next_cursor,
+            total: counts_by_event.as_ref().map(|counts| counts.values().sum()),
+            counts_by_event,
+        })
     }
 
-    pub fn get_global_access_log(&self) -> Result<Vec<AccessLogEntry>> {
+    /// Number of entries of each event type among the rows matching
+    /// `conditions`. Rows are grouped by blind index; only the first row of
+    /// each group is decrypted, for the event type's name.
+    fn count_access_log(&self, conditions: &[String], values: &[Value]) -> Result<BTreeMap<String, i64>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT MIN(id), COUNT(*) FROM access_log{} GROUP BY event_tag",
+            where_clause(conditions)
+        ))?;
+        let groups: Vec<(i64, i64)> = stmt
+            .query_map(params_from_iter(values.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut counts = BTreeMap::new();
+        for (id, count) in groups {
+            let row = self.conn.query_row(&format!("{} WHERE id = ?", SELECT_ACCESS_LOG), params![id], |row| {
+                AccessLogRow::from_row(row)
+            })?;
+            if let Ok(entry) = row.decrypt(self.log_key()?) {
+                *counts.entry(entry.event_type).or_insert(0) += count;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Every row of the vault, for a full backup. Access log rows that no
+    /// longer decrypt are left out, as everywhere else.
+    pub fn backup_vault(&self) -> Result<BackupVault> {
+        let mut vault = BackupVault {
+            lockboxes: self.get_all_lockboxes()?.into_iter().map(BackupLockbox::from).collect(),
+            aggregates: self.get_access_log_aggregates()?,
+            ..Default::default()
+        };
+
+        let mut stmt = self.conn.prepare("SELECT name, created_at FROM tags ORDER BY id")?;
+        let tags = stmt.query_map([], |row| Ok(BackupTag { name: row.get(0)?, created_at: row.get(1)? }))?;
+        vault.tags = tags.collect::<rusqlite::Result<_>>()?;
+
         let mut stmt = self.conn.prepare(
-            "SELECT id, lockbox_id, event_type, timestamp FROM access_log
-             ORDER BY timestamp DESC",
+            "SELECT id, name, icon, color, position, default_unlock_delay_seconds,
+                default_relock_delay_seconds, default_penalty_seconds, default_reflection_checklist,
+                min_unlock_delay_seconds, created_at, updated_at
+             FROM categories ORDER BY id",
         )?;
+        let categories = stmt.query_map([], |row| {
+            Ok(BackupCategory {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                icon: row.get(2)?,
+                color: row.get(3)?,
+                position: row.get(4)?,
+                default_unlock_delay_seconds: row.get(5)?,
+                default_relock_delay_seconds: row.get(6)?,
+                default_penalty_seconds: row.get(7)?,
+                default_reflection_checklist: row.get(8)?,
+                min_unlock_delay_seconds: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
+            })
+        })?;
+        vault.categories = categories.collect::<rusqlite::Result<_>>()?;
 
-        let entries = stmt.query_map([], |row| {
-            Ok(AccessLogEntry {
+        let mut stmt =
+            self.conn.prepare("SELECT id, name, parent_id, created_at, updated_at FROM folders ORDER BY id")?;
+        let folders = stmt.query_map([], |row| {
+            Ok(BackupFolder {
                 id: row.get(0)?,
-                lockbox_id: row.get(1)?,
-                event_type: row.get(2)?,
-                timestamp: row.get(3)?,
+                name: row.get(1)?,
+                parent_id: row.get(2)?,
+                created_at: row.get(3)?,
+                updated_at: row.get(4)?,
             })
         })?;
+        vault.folders = folders.collect::<rusqlite::Result<_>>()?;
+
+        let mut stmt = self.conn.prepare("SELECT id, name, deleted_at FROM lockbox_tombstones ORDER BY id")?;
+        let tombstones = stmt.query_map([], |row| {
+            Ok(BackupTombstone { id: row.get(0)?, name: row.get(1)?, deleted_at: row.get(2)? })
+        })?;
+        vault.tombstones = tombstones.collect::<rusqlite::Result<_>>()?;
+
+        for key in backup::BACKED_UP_SETTINGS {
+            if let Some(value) = self.get_setting(key)? {
+                vault.settings.insert(key.to_string(), value);
+            }
+        }
+        vault.delay_raises = self.delay_raises()?.into_iter().collect();
+
+        let mut stmt = self.conn.prepare(&format!("{} ORDER BY id ASC", SELECT_ACCESS_LOG))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            if let Ok(entry) = AccessLogRow::from_row(row)?.decrypt(self.log_key()?) {
+                vault.access_log.push(entry);
+            }
+        }
+        Ok(vault)
+    }
+
+    /// Replaces the vault with a backup, keeping every id. Only allowed while
+    /// no lockbox exists, so a restore can never loosen a lockbox in use. The
+    /// access history is re-chained under this machine's vault key from a
+    /// fresh anchor; the backup signature vouches for it. This vault's delay
+    /// raises, export counter and id outlive the restore, and lockboxes the
+    /// backup holds from before a raise are left out; returns their names.
+    pub fn restore_vault(&self, vault: BackupVault) -> Result<Vec<String>> {
+        let count: i64 = self.conn.query_row("SELECT COUNT(*) FROM lockboxes", [], |row| row.get(0))?;
+        if count > 0 {
+            return Err(LockboxError::VaultNotEmpty { count });
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let vault_id = self.get_setting(transfer::VAULT_ID_SETTING_KEY)?;
+        let counter = self.export_counter()?;
+        let outdated = backup::outdated_lockboxes(&vault, vault_id.as_deref(), self.delay_raises()?);
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "DELETE FROM lockbox_tags;
+            DELETE FROM tags;
+            DELETE FROM lockbox_search;
+            DELETE FROM folders;
+            DELETE FROM categories;
+            DELETE FROM lockbox_tombstones;
+            DELETE FROM access_log;
+            DELETE FROM access_log_monthly;",
+        )?;
+
+        for c in &vault.categories {
+            tx.execute(
+                "INSERT INTO categories (id, name, icon, color, position, default_unlock_delay_seconds,
+                    default_relock_delay_seconds, default_penalty_seconds, default_reflection_checklist,
+                    min_unlock_delay_seconds, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    c.id,
+                    c.name,
+                    c.icon,
+                    c.color,
+                    c.position,
+                    c.default_unlock_delay_seconds,
+                    c.default_relock_delay_seconds,
+                    c.default_penalty_seconds,
+                    c.default_reflection_checklist,
+                    c.min_unlock_delay_seconds,
+                    c.created_at,
+                    c.updated_at
+                ],
+            )?;
+        }
+        // Parents are linked once every folder exists.
+        for f in &vault.folders {
+            tx.execute(
+                "INSERT INTO folders (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                params![f.id, f.name, f.created_at, f.updated_at],
+            )?;
+        }
+        for f in &vault.folders {
+            tx.execute("UPDATE folders SET parent_id = ?1 WHERE id = ?2", params![f.parent_id, f.id])?;
+        }
+        for tag in &vault.tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (name, created_at) VALUES (?1, ?2)",
+                params![tag.name, tag.created_at],
+            )?;
+        }
+
+        for lb in &vault.lockboxes {
+            // Its history stays readable under its name.
+            if outdated.contains(&lb.name) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO lockbox_tombstones (id, name, deleted_at) VALUES (?1, ?2, ?3)",
+                    params![lb.id, lb.name, now],
+                )?;
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO lockboxes (id, name, content, category, is_locked, unlock_delay_seconds,
+                    relock_delay_seconds, unlock_timestamp, relock_timestamp, created_at, updated_at,
+                    reflection_enabled, reflection_message, reflection_checklist,
+                    penalty_enabled, penalty_seconds, panic_code_hash, panic_code_used,
+                    scheduled_unlock_at, max_views_per_unlock, views_this_unlock, folder_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                    ?19, ?20, ?21, ?22)",
+                params![
+                    lb.id,
+                    lb.name,
+                    lb.content,
+                    lb.category,
+                    lb.is_locked as i32,
+                    lb.unlock_delay_seconds,
+                    lb.relock_delay_seconds,
+                    lb.unlock_timestamp,
+                    lb.relock_timestamp,
+                    lb.created_at,
+                    lb.updated_at,
+                    lb.reflection_enabled as i32,
+                    lb.reflection_message,
+                    lb.reflection_checklist,
+                    lb.penalty_enabled as i32,
+                    lb.penalty_seconds,
+                    lb.panic_code_hash,
+                    lb.panic_code_used as i32,
+                    lb.scheduled_unlock_at,
+                    lb.max_views_per_unlock,
+                    lb.views_this_unlock,
+                    lb.folder_id
+                ],
+            )?;
+            if let Some(category) = lb.category.as_deref() {
+                self.ensure_category(category)?;
+            }
+            self.set_lockbox_tags(lb.id, &parse_tag_names(lb.tags.as_deref()))?;
+            self.index_lockbox(lb.id)?;
+        }
+
+        for t in &vault.tombstones {
+            tx.execute(
+                "INSERT OR REPLACE INTO lockbox_tombstones (id, name, deleted_at) VALUES (?1, ?2, ?3)",
+                params![t.id, t.name, t.deleted_at],
+            )?;
+        }
+        // Ids of lockboxes deleted before the backup or left out are never
+        // handed out again.
+        let left_out = vault.lockboxes.iter().filter(|lb| outdated.contains(&lb.name)).map(|lb| lb.id);
+        if let Some(last_id) = vault.tombstones.iter().map(|t| t.id).chain(left_out).max() {
+            tx.execute(
+                "INSERT INTO sqlite_sequence (name, seq) SELECT 'lockboxes', 0
+                 WHERE NOT EXISTS (SELECT 1 FROM sqlite_sequence WHERE name = 'lockboxes')",
+                [],
+            )?;
+            tx.execute("UPDATE sqlite_sequence SET seq = MAX(seq, ?1) WHERE name = 'lockboxes'", params![last_id])?;
+        }
+        let mut months: BTreeMap<String, Vec<MonthlyAggregate>> = BTreeMap::new();
+        for aggregate in vault.aggregates {
+            months.entry(aggregate.month.clone()).or_default().push(aggregate);
+        }
+        for (month, aggregates) in &months {
+            self.write_month_aggregates(month, aggregates)?;
+        }
+        for (name, counter) in &vault.delay_raises {
+            tx.execute(
+                "INSERT INTO lockbox_delay_raises (name, export_counter) VALUES (?1, ?2)
+                 ON CONFLICT(name) DO UPDATE SET export_counter = MAX(export_counter, excluded.export_counter)",
+                params![name, counter],
+            )?;
+        }
+        for (key, value) in vault.settings.iter().filter(|(key, _)| backup::BACKED_UP_SETTINGS.contains(&key.as_str())) {
+            match key.as_str() {
+                transfer::VAULT_ID_SETTING_KEY if vault_id.is_some() => {}
+                transfer::EXPORT_COUNTER_SETTING_KEY => {
+                    let counter = counter.max(value.parse().unwrap_or_default());
+                    self.set_setting(key, &counter.to_string())?;
+                }
+                _ => self.set_setting(key, value)?,
+            }
+        }
+
+        let anchor = ChainAnchor::new(self.vault_key()?, now);
+        self.set_setting(audit::ANCHOR_SETTING_KEY, &serde_json::to_string(&anchor)?)?;
+        let mut prev_hash = anchor.genesis.clone();
+        let log_key = self.log_key()?;
+        for entry in &vault.access_log {
+            let hash = audit::entry_hash(self.vault_key()?, &prev_hash, entry);
+            tx.execute(
+                "INSERT INTO access_log (id, timestamp, payload, prev_hash, hash, lockbox_tag, event_tag)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    entry.id,
+                    entry.timestamp,
+                    encrypt_access_log_payload(entry, log_key)?,
+                    prev_hash,
+                    hash,
+                    lockbox_tag(entry.lockbox_id, log_key),
+                    event_tag(&entry.event_type, log_key)
+                ],
+            )?;
+            prev_hash = hash;
+        }
+        match vault.access_log.last() {
+            Some(last) => self.set_chain_head(&ChainHead::new(self.vault_key()?, last.id, &prev_hash))?,
+            None => {
+                tx.execute("DELETE FROM settings WHERE key = ?", params![audit::HEAD_SETTING_KEY])?;
+            }
+        }
+
+        tx.commit()?;
+        let mut outdated: Vec<String> = outdated.into_iter().collect();
+        outdated.sort();
+        Ok(outdated)
+    }
+
+    fn snapshot_key(&self) -> Result<[u8; crypto::KEY_LENGTH]> {
+        Ok(crypto::derive_subkey(self.vault_key()?, snapshot::SNAPSHOT_KEY_PURPOSE))
+    }
+
+    /// Database copy in clear while a snapshot is taken or restored, next
+    /// to the live database so it is no more exposed than that one.
+    fn snapshot_temp_path() -> PathBuf {
+        Self::get_db_path().with_extension("snapshot.tmp")
+    }
+
+    /// Copies the live database with the SQLite online backup API and writes
+    /// it to `path`, encrypted under the snapshot key.
+    pub fn write_snapshot(&self, path: &Path, created_at: i64) -> Result<()> {
+        let failed = |e: std::io::Error| LockboxError::SnapshotFailed { detail: e.to_string() };
+        let temp = Self::snapshot_temp_path();
+        let _ = std::fs::remove_file(&temp);
+        let copied = self
+            .conn
+            .backup(DatabaseName::Main, &temp, None)
+            .map_err(LockboxError::from)
+            .and_then(|_| std::fs::read(&temp).map_err(failed));
+        let _ = std::fs::remove_file(&temp);
+        let data = snapshot::seal(&copied?, created_at, &self.snapshot_key()?)?;
+
+        // Written aside then renamed, so a listed snapshot is always whole
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, data).map_err(failed)?;
+        std::fs::rename(&partial, path).map_err(failed)
+    }
+
+    /// Replaces the live database with the snapshot `data`, once it has
+    /// decrypted and passed SQLite's integrity check. A restore never opens a
+    /// lockbox or loosens a policy: lockboxes still present keep their lock
+    /// state and take the stricter of each policy, deleted ones come back
+    /// locked with their countdowns carried over. Machine-bound settings, the
+    /// export counter, delay raises and access history are kept from the
+    /// live database, see `merge_live_state`.
+    pub fn restore_snapshot(&mut self, data: &[u8], name: &str) -> Result<()> {
+        let (created_at, database) = snapshot::open(data, name, &self.snapshot_key()?)?;
+        let temp = Self::snapshot_temp_path();
+        std::fs::write(&temp, database).map_err(|e| LockboxError::SnapshotFailed { detail: e.to_string() })?;
+        let restored = self.swap_in_snapshot(&temp, name, created_at);
+        let _ = std::fs::remove_file(&temp);
+        restored
+    }
+
+    fn swap_in_snapshot(&mut self, temp: &Path, name: &str, created_at: i64) -> Result<()> {
+        let version = Self::check_snapshot(temp).ok_or_else(|| LockboxError::SnapshotInvalid { name: name.to_string() })?;
+        if version > SCHEMA_VERSION {
+            return Err(LockboxError::UnsupportedBackupVersion { version: version.into() });
+        }
+        let live: HashMap<i64, Lockbox> = self.get_all_lockboxes()?.into_iter().map(|lb| (lb.id, lb)).collect();
+        let live_path = self.conn.path().map(str::to_string).unwrap_or_default();
+
+        // The snapshot is brought in line with the live vault in its own
+        // file, in one transaction, so that the live database is only
+        // replaced once every fix-up went through.
+        let staged = Database {
+            conn: Connection::open(temp)?,
+            keys: self.keys.clone(),
+        };
+        staged.initialize()?;
+        staged.migrate()?;
+        staged.conn.execute("ATTACH DATABASE ?1 AS live", params![live_path])?;
+        let merged = staged.merge_live_state(&live, created_at);
+        staged.conn.execute("DETACH DATABASE live", [])?;
+        merged?;
+        drop(staged);
+
+        self.conn.restore(DatabaseName::Main, temp, None::<fn(Progress)>)?;
+        Ok(())
+    }
+
+    /// Carries into this snapshot database what a restore never rolls back,
+    /// from the live one attached as `live`: machine-bound settings, the
+    /// export counter and delay raises, the whole access history, and the
+    /// lock state and stricter policies of the `live` lockboxes still present.
+    /// Deleted lockboxes come back locked with their countdowns carried over,
+    /// and every restored lockbox gets a `snapshot_restored` event.
+    fn merge_live_state(&self, live: &HashMap<i64, Lockbox>, created_at: i64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let tx = self.conn.unchecked_transaction()?;
+
+        for key in KEPT_ON_RESTORE {
+            tx.execute("DELETE FROM main.settings WHERE key = ?1", params![key])?;
+            tx.execute(
+                "INSERT INTO main.settings (key, value) SELECT key, value FROM live.settings WHERE key = ?1",
+                params![key],
+            )?;
+        }
+        let live_counter: Option<String> = tx
+            .query_row(
+                "SELECT value FROM live.settings WHERE key = ?1",
+                params![transfer::EXPORT_COUNTER_SETTING_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let counter = self.export_counter()?.max(live_counter.and_then(|value| value.parse().ok()).unwrap_or_default());
+        self.set_setting(transfer::EXPORT_COUNTER_SETTING_KEY, &counter.to_string())?;
+        tx.execute_batch(
+            "INSERT INTO main.lockbox_delay_raises (name, export_counter)
+                SELECT name, export_counter FROM live.lockbox_delay_raises WHERE true
+                ON CONFLICT(name) DO UPDATE SET export_counter = MAX(export_counter, excluded.export_counter);
+
+            DELETE FROM main.access_log;
+            INSERT INTO main.access_log (id, timestamp, payload, prev_hash, hash, lockbox_tag, event_tag)
+                SELECT id, timestamp, payload, prev_hash, hash, lockbox_tag, event_tag FROM live.access_log;
+            DELETE FROM main.access_log_monthly;
+            INSERT INTO main.access_log_monthly (month, payload) SELECT month, payload FROM live.access_log_monthly;
+
+            INSERT OR IGNORE INTO main.lockbox_tombstones (id, name, deleted_at)
+                SELECT id, name, deleted_at FROM live.lockbox_tombstones
+                WHERE id NOT IN (SELECT id FROM main.lockboxes);
+            DELETE FROM main.lockbox_tombstones WHERE id IN (SELECT id FROM main.lockboxes);
+
+            UPDATE main.sqlite_sequence
+                SET seq = MAX(seq, IFNULL((SELECT l.seq FROM live.sqlite_sequence l WHERE l.name = sqlite_sequence.name), 0));
+            INSERT INTO main.sqlite_sequence (name, seq)
+                SELECT name, seq FROM live.sqlite_sequence WHERE name NOT IN (SELECT name FROM main.sqlite_sequence);",
+        )?;
+
+        let restored: HashMap<i64, Lockbox> = self.get_all_lockboxes()?.into_iter().map(|lb| (lb.id, lb)).collect();
+        // Lockboxes created since the snapshot keep their history under their name.
+        for current in live.values().filter(|lb| !restored.contains_key(&lb.id)) {
+            tx.execute(
+                "INSERT OR REPLACE INTO main.lockbox_tombstones (id, name, deleted_at) VALUES (?1, ?2, ?3)",
+                params![current.id, current.name, now],
+            )?;
+        }
+
+        for restored in restored.into_values() {
+            match live.get(&restored.id) {
+                Some(current) => {
+                    tx.execute(
+                        "UPDATE main.lockboxes SET is_locked = ?1, unlock_timestamp = ?2, relock_timestamp = ?3,
+                            scheduled_unlock_at = ?4, views_this_unlock = ?5,
+                            panic_code_used = MAX(panic_code_used, ?6)
+                         WHERE id = ?7",
+                        params![
+                            current.is_locked,
+                            current.unlock_timestamp,
+                            current.relock_timestamp,
+                            current.scheduled_unlock_at,
+                            current.views_this_unlock,
+                            current.panic_code_used,
+                            current.id
+                        ],
+                    )?;
+                    let stricter = transfer::merge(&ExportLockbox::from(current.clone()), &restored);
+                    if !Self::changed_fields(&stricter, &restored).is_empty() {
+                        self.write_lockbox_update(stricter)?;
+                    }
+                }
+                None => {
+                    let mut lb = BackupLockbox::from(restored);
+                    backup::carry_over_timers(&mut lb, created_at, now);
+                    tx.execute(
+                        "UPDATE main.lockboxes SET is_locked = ?1, unlock_timestamp = ?2, relock_timestamp = ?3 WHERE id = ?4",
+                        params![lb.is_locked, lb.unlock_timestamp, lb.relock_timestamp, lb.id],
+                    )?;
+                }
+            }
+        }
+        let mut ids: Vec<i64> = tx
+            .prepare("SELECT id FROM main.lockboxes")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        ids.sort();
+        for id in ids {
+            self.append_access_event(id, &AccessEvent::SnapshotRestored { created_at })?;
+        }
 
-        entries.collect()
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Schema version of the database file at `path`, if SQLite finds it
+    /// intact.
+    fn check_snapshot(path: &Path) -> Option<i32> {
+        let conn = Connection::open(path).ok()?;
+        let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0)).ok()?;
+        if integrity != "ok" {
+            return None;
+        }
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0)).ok()
     }
 
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
@@ -669,6 +3180,133 @@ impl Database {
         Ok(())
     }
 
+    /// Random id of this vault, created on first use. Backed up, so that a
+    /// restored vault still recognizes its own exports.
+    pub fn vault_id(&self) -> Result<String> {
+        if let Some(id) = self.get_setting(transfer::VAULT_ID_SETTING_KEY)? {
+            return Ok(id);
+        }
+        let id = crypto::generate_key();
+        self.set_setting(transfer::VAULT_ID_SETTING_KEY, &id)?;
+        Ok(id)
+    }
+
+    /// Signing key of this device, created on first use and stored sealed
+    /// under the vault key, itself sealed under the master password. Not
+    /// part of backups: a restored vault gets a new one, and other machines
+    /// have to trust it again. A stored key that no longer unseals is an
+    /// error until `reset_device_key` replaces it.
+    pub fn device_key(&self) -> Result<DeviceKey> {
+        match self.get_setting(identity::DEVICE_KEY_SETTING_KEY)? {
+            Some(sealed) => {
+                DeviceKey::unseal(&sealed, &self.device_seal_key()?).map_err(|_| LockboxError::DeviceKeyUnreadable)
+            }
+            None => self.store_device_key(DeviceKey::generate()),
+        }
+    }
+
+    /// Replaces a signing key that no longer unseals. One that still does
+    /// is kept, so trust given on other machines is not lost by accident.
+    pub fn reset_device_key(&self) -> Result<DeviceKey> {
+        match self.device_key() {
+            Err(LockboxError::DeviceKeyUnreadable) => self.store_device_key(DeviceKey::generate()),
+            result => result,
+        }
+    }
+
+    fn store_device_key(&self, device: DeviceKey) -> Result<DeviceKey> {
+        self.set_setting(identity::DEVICE_KEY_SETTING_KEY, &device.seal(&self.device_seal_key()?)?)?;
+        self.set_setting(identity::DEVICE_PUBLIC_KEY_SETTING_KEY, &device.public_key())?;
+        Ok(device)
+    }
+
+    fn device_seal_key(&self) -> Result<[u8; crypto::KEY_LENGTH]> {
+        Ok(crypto::derive_subkey(self.vault_key()?, identity::DEVICE_KEY_PURPOSE))
+    }
+
+    /// Public key of this device, read without the signing key. `None` for
+    /// a vault whose key predates the stored public key and no longer unseals.
+    pub fn device_public_key(&self) -> Result<Option<String>> {
+        if let Some(public_key) = self.get_setting(identity::DEVICE_PUBLIC_KEY_SETTING_KEY)? {
+            return Ok(Some(public_key));
+        }
+        match self.device_key() {
+            Ok(device) => {
+                self.set_setting(identity::DEVICE_PUBLIC_KEY_SETTING_KEY, &device.public_key())?;
+                Ok(Some(device.public_key()))
+            }
+            Err(LockboxError::DeviceKeyUnreadable) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn trusted_devices(&self) -> Result<Vec<TrustedDevice>> {
+        match self.get_setting(identity::TRUSTED_DEVICES_SETTING_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Trusts the exports of another device, or renames one already trusted.
+    pub fn trust_device(&self, public_key: &str, name: &str) -> Result<Vec<TrustedDevice>> {
+        let public_key = identity::parse_public_key(public_key)?;
+        let fingerprint = identity::fingerprint(&public_key);
+        let name = match name.trim() {
+            "" => fingerprint.clone(),
+            name => name.to_string(),
+        };
+        let mut devices = self.trusted_devices()?;
+        match devices.iter_mut().find(|device| device.public_key == public_key) {
+            Some(device) => device.name = name,
+            None => devices.push(TrustedDevice {
+                public_key,
+                name,
+                fingerprint,
+                added_at: chrono::Utc::now().timestamp_millis(),
+            }),
+        }
+        self.set_setting(identity::TRUSTED_DEVICES_SETTING_KEY, &serde_json::to_string(&devices)?)?;
+        Ok(devices)
+    }
+
+    pub fn remove_trusted_device(&self, public_key: &str) -> Result<Vec<TrustedDevice>> {
+        let mut devices = self.trusted_devices()?;
+        devices.retain(|device| device.public_key != public_key);
+        self.set_setting(identity::TRUSTED_DEVICES_SETTING_KEY, &serde_json::to_string(&devices)?)?;
+        Ok(devices)
+    }
+
+    fn export_counter(&self) -> Result<i64> {
+        Ok(self
+            .get_setting(transfer::EXPORT_COUNTER_SETTING_KEY)?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0))
+    }
+
+    /// Bumps the export counter and returns the value for the new file.
+    pub fn next_export_counter(&self) -> Result<i64> {
+        let counter = self.export_counter()? + 1;
+        self.set_setting(transfer::EXPORT_COUNTER_SETTING_KEY, &counter.to_string())?;
+        Ok(counter)
+    }
+
+    /// Records that the delays of `name` were raised: no export made so far
+    /// may bring that lockbox back.
+    fn record_delay_raise(&self, name: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO lockbox_delay_raises (name, export_counter) VALUES (?1, ?2)",
+            params![name, self.export_counter()?],
+        )?;
+        Ok(())
+    }
+
+    /// Export counter at the last delay raise of each lockbox name.
+    pub fn delay_raises(&self) -> Result<HashMap<String, i64>> {
+        let mut stmt = self.conn.prepare("SELECT name, export_counter FROM lockbox_delay_raises")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
     pub fn check_and_update_states(&self) -> Result<Vec<Lockbox>> {
         let now = chrono::Utc::now().timestamp_millis();
         let now_monotonic = Self::monotonic_now_ms();
@@ -678,13 +3316,18 @@ impl Database {
             return self.get_all_lockboxes();
         }
 
-        // Collect IDs that will complete countdown-based unlock
-        let countdown_ids: Vec<i64> = {
+        // Collect events for lockboxes that will complete countdown-based unlock
+        let countdown_events: Vec<(i64, AccessEvent)> = {
             let mut stmt = self.conn.prepare(
-                "SELECT id FROM lockboxes WHERE is_locked = 1 AND unlock_timestamp IS NOT NULL AND unlock_timestamp <= ?1"
+                "SELECT id, relock_delay_seconds FROM lockboxes WHERE is_locked = 1 AND unlock_timestamp IS NOT NULL AND unlock_timestamp <= ?1"
             )?;
-            let ids: Vec<i64> = stmt.query_map(params![now], |row| row.get(0))?.filter_map(|r| r.ok()).collect();
-            ids
+            let events: Vec<(i64, AccessEvent)> = stmt
+                .query_map(params![now], |row| {
+                    Ok((row.get(0)?, AccessEvent::UnlockCompleted { relock_delay_seconds: row.get(1)? }))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+            events
         };
 
         self.conn.execute(
@@ -692,22 +3335,34 @@ impl Database {
              SET is_locked = 0,
                  relock_timestamp = ?1 + (relock_delay_seconds * 1000),
                  unlock_timestamp = NULL,
+                 views_this_unlock = 0,
                  updated_at = ?1
              WHERE is_locked = 1 AND unlock_timestamp IS NOT NULL AND unlock_timestamp <= ?1",
             params![now],
         )?;
 
-        for id in countdown_ids {
-            let _ = self.log_access_event(id, "unlock_completed");
+        for (id, event) in countdown_events {
+            let _ = self.log_access_event(id, &event);
         }
 
-        // Collect IDs that will complete scheduled unlock
-        let scheduled_ids: Vec<i64> = {
+        // Collect events for lockboxes that will complete scheduled unlock
+        let scheduled_events: Vec<(i64, AccessEvent)> = {
             let mut stmt = self.conn.prepare(
-                "SELECT id FROM lockboxes WHERE is_locked = 1 AND scheduled_unlock_at IS NOT NULL AND scheduled_unlock_at <= ?1 AND unlock_timestamp IS NULL"
+                "SELECT id, scheduled_unlock_at, relock_delay_seconds FROM lockboxes WHERE is_locked = 1 AND scheduled_unlock_at IS NOT NULL AND scheduled_unlock_at <= ?1 AND unlock_timestamp IS NULL"
             )?;
-            let ids: Vec<i64> = stmt.query_map(params![now], |row| row.get(0))?.filter_map(|r| r.ok()).collect();
-            ids
+            let events: Vec<(i64, AccessEvent)> = stmt
+                .query_map(params![now], |row| {
+                    Ok((
+                        row.get(0)?,
+                        AccessEvent::ScheduledUnlockCompleted {
+                            scheduled_at: row.get(1)?,
+                            relock_delay_seconds: row.get(2)?,
+                        },
+                    ))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+            events
         };
 
         self.conn.execute(
@@ -715,14 +3370,15 @@ impl Database {
              SET is_locked = 0,
                  relock_timestamp = ?1 + (relock_delay_seconds * 1000),
                  scheduled_unlock_at = NULL,
+                 views_this_unlock = 0,
                  updated_at = ?1
              WHERE is_locked = 1 AND scheduled_unlock_at IS NOT NULL AND scheduled_unlock_at <= ?1
                AND unlock_timestamp IS NULL",
             params![now],
         )?;
 
-        for id in scheduled_ids {
-            let _ = self.log_access_event(id, "scheduled_unlock_completed");
+        for (id, event) in scheduled_events {
+            let _ = self.log_access_event(id, &event);
         }
 
         // Collect IDs that will auto-relock
@@ -744,9 +3400,76 @@ impl Database {
         )?;
 
         for id in relock_ids {
-            let _ = self.log_access_event(id, "auto_relocked");
+            let _ = self.log_access_event(id, &AccessEvent::AutoRelocked {});
         }
 
         self.get_all_lockboxes()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(path: &Path) -> Database {
+        let mut db = Database { conn: Connection::open(path).unwrap(), keys: None };
+        db.initialize().unwrap();
+        db.open_vault("master").unwrap();
+        db
+    }
+
+    fn request(name: &str) -> CreateLockboxRequest {
+        CreateLockboxRequest {
+            name: name.to_string(),
+            content: "secret".to_string(),
+            category: None,
+            unlock_delay_seconds: Some(60),
+            relock_delay_seconds: Some(60),
+            reflection_enabled: None,
+            reflection_message: None,
+            reflection_checklist: None,
+            penalty_enabled: None,
+            penalty_seconds: None,
+            panic_code_hash: None,
+            scheduled_unlock_at: None,
+            tags: None,
+            max_views_per_unlock: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_restore_keeps_live_history_and_state() {
+        let dir = std::env::temp_dir().join(format!("lockbox-restore-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut db = open(&dir.join("live.db"));
+        let bank = db.create_lockbox(request("bank")).unwrap();
+        let device = DeviceKey::generate().public_key();
+        db.trust_device(&device, "laptop").unwrap();
+        let snapshot = dir.join("snapshot.db");
+        db.conn.backup(DatabaseName::Main, &snapshot, None).unwrap();
+
+        // Since the snapshot: an export, a raise, a countdown, a device
+        // removed and a new lockbox
+        let counter = db.next_export_counter().unwrap();
+        db.extend_unlock_delay(bank.id, 600).unwrap();
+        db.unlock_lockbox(bank.id).unwrap();
+        db.remove_trusted_device(&device).unwrap();
+        let later = db.create_lockbox(request("later")).unwrap();
+        let counted = AccessLogQuery { with_counts: true, ..Default::default() };
+        let logged = db.query_access_log(&counted).unwrap().total.unwrap();
+
+        db.swap_in_snapshot(&snapshot, "snapshot", 0).unwrap();
+        assert_eq!(db.export_counter().unwrap(), counter);
+        assert!(db.delay_raises().unwrap().contains_key("bank"));
+        assert!(db.trusted_devices().unwrap().is_empty());
+        let restored = db.get_lockbox(bank.id).unwrap().unwrap();
+        assert_eq!(restored.unlock_delay_seconds, 660);
+        assert!(restored.unlock_timestamp.is_some());
+        assert!(db.get_lockbox(later.id).unwrap().is_none());
+        // The live history, and one restore event for the lockbox brought back
+        assert_eq!(db.query_access_log(&counted).unwrap().total, Some(logged + 1));
+        assert!(db.verify_access_log().unwrap().valid);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
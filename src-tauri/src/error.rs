@@ -0,0 +1,311 @@
+use crate::crypto::CryptoError;
+use crate::i18n::{self, Locale};
+use crate::validation::FieldError;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, LockboxError>;
+
+/// Errors returned by the database layer and every Tauri command.
+///
+/// Serialized across IPC as `{ code, params, message }`: `code` is a stable
+/// snake_case identifier the frontend switches on to localize, `params` holds
+/// the structured values for interpolation, and `message` is the text from the
+/// backend catalog in the current locale. `Display` stays in English for logs.
+#[derive(Error, Debug)]
+pub enum LockboxError {
+    #[error("Lockbox {id} not found")]
+    NotFound { id: i64 },
+    #[error("Lockbox {id} is locked")]
+    Locked { id: i64 },
+    #[error("Cannot edit content while locked")]
+    ContentLocked,
+    #[error("Cannot reduce unlock delay while locked ({current}s -> {requested}s)")]
+    UnlockDelayReduction { current: i64, requested: i64 },
+    #[error("Cannot reduce relock delay while locked ({current}s -> {requested}s)")]
+    RelockDelayReduction { current: i64, requested: i64 },
+    #[error("Cannot raise or remove the view limit while locked")]
+    ViewLimitIncrease { current: i64, requested: Option<i64> },
+    #[error("Cannot change emergency code while locked")]
+    PanicCodeLocked,
+    #[error("Cannot set or modify scheduled unlock while locked")]
+    ScheduleLocked,
+    #[error("Cannot move scheduled unlock to an earlier date")]
+    ScheduleEarlier { current: i64, requested: i64 },
+    #[error("Scheduled unlock date must be in the future")]
+    ScheduleInPast { requested: i64 },
+    #[error("Additional delay must be positive")]
+    NonPositiveExtension { additional_seconds: i64 },
+    #[error("Invalid file format: {detail}")]
+    InvalidImportFormat { detail: String },
+    #[error("Integrity check failed for '{name}': the file may have been tampered with.")]
+    IntegrityCheckFailed { name: String },
+    #[error("Failed to decrypt '{name}': wrong source password?")]
+    SourceDecryptionFailed { name: String },
+    #[error("Invalid input: {}", field_list(errors))]
+    Validation { errors: Vec<FieldError> },
+    #[error("Imported lockbox '{name}' is invalid: {}", field_list(errors))]
+    InvalidImportedLockbox { name: String, errors: Vec<FieldError> },
+    #[error("Invalid access log cursor: {cursor}")]
+    InvalidCursor { cursor: String },
+    #[error("Unknown timezone: {timezone}")]
+    InvalidTimezone { timezone: String },
+    #[error("Access log failed verification; refusing to compact it (first broken entry: {first_broken_id:?})")]
+    AccessLogTampered { first_broken_id: Option<i64> },
+    #[error("A master password is required")]
+    MasterPasswordRequired,
+    #[error("Tag '{name}' not found")]
+    TagNotFound { name: String },
+    #[error("Tag '{name}' already exists")]
+    TagExists { name: String },
+    #[error("Category {id} not found")]
+    CategoryNotFound { id: i64 },
+    #[error("Category '{name}' already exists")]
+    CategoryExists { name: String },
+    #[error("Folder {id} not found")]
+    FolderNotFound { id: i64 },
+    #[error("A folder named '{name}' already exists here")]
+    FolderExists { name: String },
+    #[error("Folder {id} cannot be moved into its own subfolder {parent_id}")]
+    FolderCycle { id: i64, parent_id: i64 },
+    #[error("Category '{category}' requires an unlock delay of at least {minimum}s (got {requested}s)")]
+    BelowCategoryMinimum { category: String, minimum: i64, requested: i64 },
+    #[error("Unsupported backup version {version}")]
+    UnsupportedBackupVersion { version: i64 },
+    #[error("Backup signature is invalid: the file was modified or made under another master password")]
+    BackupSignatureInvalid,
+    #[error("Export manifest is invalid: the file was modified, truncated or made under another master password")]
+    ManifestInvalid,
+    #[error("The file was signed by a device that is not trusted ({fingerprint})")]
+    UntrustedDevice { public_key: String, fingerprint: String },
+    #[error("Not a valid device public key")]
+    InvalidDeviceKey,
+    #[error("This device's signing key can no longer be read")]
+    DeviceKeyUnreadable,
+    #[error("This file is protected by a passphrase")]
+    PassphraseRequired,
+    #[error("Incorrect passphrase, or the file was modified")]
+    PassphraseIncorrect,
+    #[error("Passphrase must be at least {min_length} characters")]
+    PassphraseTooShort { min_length: usize },
+    #[error("A backup can only be restored into an empty vault ({count} lockboxes present)")]
+    VaultNotEmpty { count: i64 },
+    #[error("Access log archive could not be written: {detail}")]
+    ArchiveFailed { detail: String },
+    #[error("Automatic backup failed: {detail}")]
+    SnapshotFailed { detail: String },
+    #[error("Backup '{name}' failed its integrity check")]
+    SnapshotInvalid { name: String },
+    #[error("This file cannot be imported: {detail}")]
+    UnsupportedForeignFile { detail: String },
+    #[error("Unsupported locale: {locale}")]
+    UnsupportedLocale { locale: String },
+    #[error("Cryptographic operation failed: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("Serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Database could not be migrated: {detail}")]
+    MigrationBlocked { detail: String },
+    #[error("Vault key is unavailable: {detail}")]
+    VaultKeyUnavailable { detail: String },
+    #[error("Internal state is unavailable")]
+    StatePoisoned,
+}
+
+impl LockboxError {
+    /// Stable identifier exposed to the frontend. Never rename an existing code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LockboxError::NotFound { .. } => "not_found",
+            LockboxError::Locked { .. } => "locked",
+            LockboxError::ContentLocked => "content_locked",
+            LockboxError::UnlockDelayReduction { .. } => "unlock_delay_reduction",
+            LockboxError::RelockDelayReduction { .. } => "relock_delay_reduction",
+            LockboxError::ViewLimitIncrease { .. } => "view_limit_increase",
+            LockboxError::PanicCodeLocked => "panic_code_locked",
+            LockboxError::ScheduleLocked => "schedule_locked",
+            LockboxError::ScheduleEarlier { .. } => "schedule_earlier",
+            LockboxError::ScheduleInPast { .. } => "schedule_in_past",
+            LockboxError::NonPositiveExtension { .. } => "non_positive_extension",
+            LockboxError::InvalidImportFormat { .. } => "invalid_import_format",
+            LockboxError::IntegrityCheckFailed { .. } => "integrity_check_failed",
+            LockboxError::SourceDecryptionFailed { .. } => "source_decryption_failed",
+            LockboxError::Validation { .. } => "validation",
+            LockboxError::InvalidImportedLockbox { .. } => "invalid_imported_lockbox",
+            LockboxError::InvalidCursor { .. } => "invalid_cursor",
+            LockboxError::InvalidTimezone { .. } => "invalid_timezone",
+            LockboxError::AccessLogTampered { .. } => "access_log_tampered",
+            LockboxError::ArchiveFailed { .. } => "archive_failed",
+            LockboxError::MasterPasswordRequired => "master_password_required",
+            LockboxError::SnapshotFailed { .. } => "snapshot_failed",
+            LockboxError::SnapshotInvalid { .. } => "snapshot_invalid",
+            LockboxError::UnsupportedForeignFile { .. } => "unsupported_foreign_file",
+            LockboxError::TagNotFound { .. } => "tag_not_found",
+            LockboxError::TagExists { .. } => "tag_exists",
+            LockboxError::CategoryNotFound { .. } => "category_not_found",
+            LockboxError::CategoryExists { .. } => "category_exists",
+            LockboxError::BelowCategoryMinimum { .. } => "below_category_minimum",
+            LockboxError::FolderNotFound { .. } => "folder_not_found",
+            LockboxError::FolderExists { .. } => "folder_exists",
+            LockboxError::FolderCycle { .. } => "folder_cycle",
+            LockboxError::UnsupportedBackupVersion { .. } => "unsupported_backup_version",
+            LockboxError::BackupSignatureInvalid => "backup_signature_invalid",
+            LockboxError::ManifestInvalid => "manifest_invalid",
+            LockboxError::UntrustedDevice { .. } => "untrusted_device",
+            LockboxError::InvalidDeviceKey => "invalid_device_key",
+            LockboxError::DeviceKeyUnreadable => "device_key_unreadable",
+            LockboxError::PassphraseRequired => "passphrase_required",
+            LockboxError::PassphraseIncorrect => "passphrase_incorrect",
+            LockboxError::PassphraseTooShort { .. } => "passphrase_too_short",
+            LockboxError::VaultNotEmpty { .. } => "vault_not_empty",
+            LockboxError::UnsupportedLocale { .. } => "unsupported_locale",
+            LockboxError::Crypto(_) => "crypto",
+            LockboxError::Serialization(_) => "serialization",
+            LockboxError::Database(_) => "database",
+            LockboxError::MigrationBlocked { .. } => "migration_blocked",
+            LockboxError::VaultKeyUnavailable { .. } => "vault_key_unavailable",
+            LockboxError::StatePoisoned => "state_poisoned",
+        }
+    }
+
+    /// Structured parameters for message interpolation on the frontend.
+    pub fn params(&self) -> Value {
+        match self {
+            LockboxError::NotFound { id }
+            | LockboxError::Locked { id }
+            | LockboxError::CategoryNotFound { id }
+            | LockboxError::FolderNotFound { id } => json!({ "id": id }),
+            LockboxError::UnlockDelayReduction { current, requested }
+            | LockboxError::RelockDelayReduction { current, requested }
+            | LockboxError::ScheduleEarlier { current, requested } => {
+                json!({ "current": current, "requested": requested })
+            }
+            LockboxError::ViewLimitIncrease { current, requested } => {
+                json!({ "current": current, "requested": requested })
+            }
+            LockboxError::ScheduleInPast { requested } => json!({ "requested": requested }),
+            LockboxError::NonPositiveExtension { additional_seconds } => {
+                json!({ "additional_seconds": additional_seconds })
+            }
+            LockboxError::InvalidImportFormat { detail }
+            | LockboxError::MigrationBlocked { detail }
+            | LockboxError::VaultKeyUnavailable { detail }
+            | LockboxError::ArchiveFailed { detail }
+            | LockboxError::SnapshotFailed { detail }
+            | LockboxError::UnsupportedForeignFile { detail } => json!({ "detail": detail }),
+            LockboxError::IntegrityCheckFailed { name }
+            | LockboxError::SourceDecryptionFailed { name }
+            | LockboxError::TagNotFound { name }
+            | LockboxError::TagExists { name }
+            | LockboxError::CategoryExists { name }
+            | LockboxError::FolderExists { name }
+            | LockboxError::SnapshotInvalid { name } => json!({ "name": name }),
+            LockboxError::FolderCycle { id, parent_id } => json!({ "id": id, "parent_id": parent_id }),
+            LockboxError::BelowCategoryMinimum { category, minimum, requested } => {
+                json!({ "category": category, "minimum": minimum, "requested": requested })
+            }
+            LockboxError::Validation { errors } => {
+                json!({ "fields": field_list(errors), "errors": errors })
+            }
+            LockboxError::InvalidImportedLockbox { name, errors } => {
+                json!({ "name": name, "fields": field_list(errors), "errors": errors })
+            }
+            LockboxError::InvalidCursor { cursor } => json!({ "cursor": cursor }),
+            LockboxError::InvalidTimezone { timezone } => json!({ "timezone": timezone }),
+            LockboxError::AccessLogTampered { first_broken_id } => {
+                json!({ "first_broken_id": first_broken_id })
+            }
+            LockboxError::UnsupportedLocale { locale } => json!({ "locale": locale }),
+            LockboxError::UnsupportedBackupVersion { version } => json!({ "version": version }),
+            LockboxError::VaultNotEmpty { count } => json!({ "count": count }),
+            LockboxError::PassphraseTooShort { min_length } => json!({ "min_length": min_length }),
+            LockboxError::UntrustedDevice { public_key, fingerprint } => {
+                json!({ "public_key": public_key, "fingerprint": fingerprint })
+            }
+            LockboxError::Crypto(e) => json!({ "detail": e.to_string() }),
+            LockboxError::Serialization(e) => json!({ "detail": e.to_string() }),
+            LockboxError::Database(e) => json!({ "detail": e.to_string() }),
+            LockboxError::ContentLocked
+            | LockboxError::PanicCodeLocked
+            | LockboxError::ScheduleLocked
+            | LockboxError::BackupSignatureInvalid
+            | LockboxError::ManifestInvalid
+            | LockboxError::InvalidDeviceKey
+            | LockboxError::DeviceKeyUnreadable
+            | LockboxError::PassphraseRequired
+            | LockboxError::PassphraseIncorrect
+            | LockboxError::MasterPasswordRequired
+            | LockboxError::StatePoisoned => json!({}),
+        }
+    }
+
+    /// User-facing message from the backend catalog.
+    pub fn localized(&self, locale: Locale) -> String {
+        let params = self.params();
+        let args: Vec<(&str, String)> = match &params {
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| {
+                    let value = v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                    (k.as_str(), value)
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        i18n::tr(locale, &format!("error.{}", self.code()), &args)
+    }
+}
+
+fn field_list(errors: &[FieldError]) -> String {
+    errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+impl<T> From<std::sync::PoisonError<T>> for LockboxError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        LockboxError::StatePoisoned
+    }
+}
+
+impl Serialize for LockboxError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LockboxError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("params", &self.params())?;
+        state.serialize_field("message", &self.localized(i18n::current()))?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_params() {
+        let err = LockboxError::UnlockDelayReduction { current: 60, requested: 10 };
+        let value = serde_json::to_value(&err).unwrap();
+
+        assert_eq!(value["code"], "unlock_delay_reduction");
+        assert_eq!(value["params"]["current"], 60);
+        assert_eq!(value["params"]["requested"], 10);
+        assert!(value["message"].as_str().unwrap().contains("unlock delay"));
+    }
+
+    #[test]
+    fn test_localized_message_interpolates_params() {
+        let err = LockboxError::IntegrityCheckFailed { name: "Steam".to_string() };
+        assert_eq!(
+            err.localized(Locale::Fr),
+            "« Steam » n'a pas passé le contrôle d'intégrité : le fichier a peut-être été modifié."
+        );
+    }
+
+    #[test]
+    fn test_database_error_maps_to_database_code() {
+        let err: LockboxError = rusqlite::Error::QueryReturnedNoRows.into();
+        assert_eq!(err.code(), "database");
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Version stamped into every `access_log.details` payload as `"v"`.
+/// Bump it when a payload changes shape so readers can tell old rows apart.
+pub const DETAILS_VERSION: i64 = 1;
+
+/// An access log event together with its structured payload.
+///
+/// Serialized internally tagged, so `event_type` is the snake_case variant
+/// name and the remaining fields become the `details` JSON column.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum AccessEvent {
+    UnlockRequested {
+        unlock_delay_seconds: i64,
+        unlock_at: i64,
+    },
+    UnlockCancelled {
+        /// Penalty added to the unlock delay, 0 when penalties are disabled.
+        penalty_seconds: i64,
+        /// Unlock delay after the penalty.
+        unlock_delay_seconds: i64,
+        /// Time spent waiting since the request, unknown for scheduled unlocks.
+        waited_seconds: Option<i64>,
+    },
+    ExtendDelay {
+        additional_seconds: i64,
+        unlock_delay_seconds: i64,
+    },
+    UnlockCompleted {
+        relock_delay_seconds: i64,
+    },
+    ScheduledUnlockCompleted {
+        scheduled_at: i64,
+        relock_delay_seconds: i64,
+    },
+    PanicUsed {
+        relock_delay_seconds: i64,
+    },
+    RelockManual {},
+    AutoRelocked {},
+    ViewLimitRelocked {
+        views: i64,
+    },
+    ContentViewed {
+        views_this_unlock: i64,
+        max_views_per_unlock: Option<i64>,
+    },
+    ContentCopied {},
+    /// Content written in clear to a file for another password manager, or
+    /// to a portable export (`format` "portable"). Counts as a view.
+    ContentExported {
+        format: String,
+        views_this_unlock: i64,
+        max_views_per_unlock: Option<i64>,
+    },
+    FieldUpdated {
+        /// Names of the changed fields. Values are never logged.
+        fields: Vec<String>,
+    },
+    /// The vault was restored from an automatic backup taken at `created_at`.
+    SnapshotRestored {
+        created_at: i64,
+    },
+    ClockTamperDetected {
+        was_unlocked: bool,
+        cancelled_unlock: bool,
+        cancelled_schedule: bool,
+    },
+}
+
+impl AccessEvent {
+    /// Splits the event into its `event_type` and versioned `details` payload.
+    pub fn to_record(&self) -> Result<(String, Value)> {
+        let mut value = serde_json::to_value(self)?;
+        let map = value
+            .as_object_mut()
+            .expect("internally tagged enums serialize to objects");
+        let event_type = match map.remove("event_type") {
+            Some(Value::String(event_type)) => event_type,
+            _ => unreachable!("internally tagged enums carry their tag"),
+        };
+        map.insert("v".to_string(), Value::from(DETAILS_VERSION));
+        Ok((event_type, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_record_splits_type_and_versioned_details() {
+        let event = AccessEvent::ExtendDelay { additional_seconds: 300, unlock_delay_seconds: 900 };
+        let (event_type, details) = event.to_record().unwrap();
+
+        assert_eq!(event_type, "extend_delay");
+        assert_eq!(
+            details,
+            serde_json::json!({ "v": 1, "additional_seconds": 300, "unlock_delay_seconds": 900 })
+        );
+    }
+
+    #[test]
+    fn test_empty_payload_still_carries_version() {
+        let (event_type, details) = AccessEvent::ContentCopied {}.to_record().unwrap();
+        assert_eq!(event_type, "content_copied");
+        assert_eq!(details, serde_json::json!({ "v": 1 }));
+    }
+}
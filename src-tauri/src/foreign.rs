@@ -0,0 +1,800 @@
+//! Imports from other password managers: KeePass (XML export or KDBX 4
+//! database), Bitwarden (unencrypted JSON export) and the CSV exports of
+//! browsers and 1Password. Entries become an import file with contents in
+//! clear, checked and previewed like any other import.
+//!
+//! Exports the other way round, in clear, as KeePass XML or CSV.
+
+use crate::error::{LockboxError, Result};
+use crate::kdbx::{self, ProtectedValues};
+use crate::transfer::{ExportData, ExportLockbox};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use quick_xml::escape::{escape, resolve_predefined_entity};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// File formats an import can read besides Lockbox's own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForeignFormat {
+    KeepassXml,
+    /// Sent base64-encoded, opened with the import passphrase.
+    Kdbx,
+    Bitwarden,
+    Csv,
+}
+
+/// File formats an export in clear can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClearFormat {
+    KeepassXml,
+    Csv,
+}
+
+impl ClearFormat {
+    /// Name logged with each exported lockbox.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClearFormat::KeepassXml => "keepass_xml",
+            ClearFormat::Csv => "csv",
+        }
+    }
+}
+
+/// What the folders (groups) of the source become.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderMapping {
+    /// One category per folder, named after its path, e.g. "Work / Email".
+    #[default]
+    Category,
+    /// One tag per folder of the path.
+    Tags,
+    Ignore,
+}
+
+/// How the entries of a foreign file become lockboxes. Sources have no
+/// delays, so every entry gets the ones chosen for the import.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForeignImport {
+    pub format: ForeignFormat,
+    pub unlock_delay_seconds: i64,
+    pub relock_delay_seconds: i64,
+    #[serde(default)]
+    pub folders: FolderMapping,
+}
+
+/// One entry of the source, whatever its format.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForeignEntry {
+    pub title: String,
+    pub username: String,
+    pub password: String,
+    pub url: String,
+    pub notes: String,
+    /// Any other field, e.g. TOTP secrets or card numbers, in source order.
+    pub fields: Vec<(String, String)>,
+    /// Path of the entry's folder, outermost first.
+    pub folder: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl ForeignEntry {
+    /// Entry of a lockbox, reading back the "Label: value" lines that open
+    /// its content; the rest is kept as notes. A content of one unlabelled
+    /// line is taken as the password.
+    fn from_lockbox(lockbox: &ExportLockbox) -> Self {
+        let mut entry = ForeignEntry {
+            title: lockbox.name.clone(),
+            folder: lockbox
+                .category
+                .iter()
+                .flat_map(|category| category.split(" / "))
+                .map(str::to_string)
+                .filter(|name| !name.is_empty())
+                .collect(),
+            tags: lockbox.tags.as_deref().and_then(|tags| serde_json::from_str(tags).ok()).unwrap_or_default(),
+            ..ForeignEntry::default()
+        };
+
+        let content = lockbox.content.trim_end();
+        let mut lines = content.lines().peekable();
+        while let Some((label, value)) = lines.peek().copied().and_then(labelled) {
+            lines.next();
+            let slot = match label {
+                "Username" => Some(&mut entry.username),
+                "Password" => Some(&mut entry.password),
+                "URL" => Some(&mut entry.url),
+                _ => None,
+            };
+            match slot {
+                Some(slot) if slot.is_empty() => *slot = value.to_string(),
+                _ => entry.fields.push((label.to_string(), value.to_string())),
+            }
+        }
+        let notes = lines.collect::<Vec<_>>().join("\n");
+        if content.lines().count() == 1 && labelled(content).is_none() {
+            entry.password = content.to_string();
+        } else {
+            entry.notes = notes.trim().to_string();
+        }
+        entry
+    }
+
+    /// Lockbox name: the title, else the URL or user name.
+    fn name(&self) -> String {
+        [&self.title, &self.url, &self.username]
+            .into_iter()
+            .map(|value| value.trim())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Lockbox content: one "Label: value" line per field, then the notes.
+    fn content(&self) -> String {
+        let standard = [("Username", &self.username), ("Password", &self.password), ("URL", &self.url)];
+        let lines: Vec<String> = standard
+            .into_iter()
+            .map(|(label, value)| (label, value.as_str()))
+            .chain(self.fields.iter().map(|(label, value)| (label.as_str(), value.as_str())))
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect();
+        match (lines.is_empty(), self.notes.trim()) {
+            (_, "") => lines.join("\n"),
+            (true, notes) => notes.to_string(),
+            (false, notes) => format!("{}\n\n{}", lines.join("\n"), notes),
+        }
+    }
+
+    fn into_lockbox(self, options: &ForeignImport) -> ExportLockbox {
+        let folder: Vec<String> =
+            self.folder.iter().map(|name| name.trim().to_string()).filter(|name| !name.is_empty()).collect();
+        let mut tags: Vec<String> = Vec::new();
+        let folder_tags = if options.folders == FolderMapping::Tags { folder.clone() } else { Vec::new() };
+        for tag in folder_tags.into_iter().chain(self.tags.iter().map(|tag| tag.trim().to_string())) {
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        ExportLockbox {
+            name: self.name(),
+            content: self.content(),
+            category: Some(folder.join(" / "))
+                .filter(|path| options.folders == FolderMapping::Category && !path.is_empty()),
+            unlock_delay_seconds: options.unlock_delay_seconds,
+            relock_delay_seconds: options.relock_delay_seconds,
+            reflection_enabled: false,
+            reflection_message: None,
+            reflection_checklist: None,
+            penalty_enabled: false,
+            penalty_seconds: 0,
+            tags: (!tags.is_empty()).then(|| serde_json::to_string(&tags).unwrap_or_default()),
+            max_views_per_unlock: None,
+            signature: None,
+        }
+    }
+}
+
+/// Label and value of a "Label: value" content line.
+fn labelled(line: &str) -> Option<(&str, &str)> {
+    let (label, value) = line.split_once(": ")?;
+    let plain = !label.is_empty() && label.len() <= 40 && label.trim() == label && !label.contains(['"', '<']);
+    plain.then_some((label, value))
+}
+
+fn invalid(detail: &str) -> LockboxError {
+    LockboxError::InvalidImportFormat { detail: detail.to_string() }
+}
+
+/// Reads a foreign file into an import file whose contents are in clear.
+/// A KDBX database needs its master password as `passphrase`.
+pub fn parse(data: &str, options: &ForeignImport, passphrase: Option<&str>) -> Result<ExportData> {
+    let entries = match options.format {
+        ForeignFormat::KeepassXml => keepass_entries(data, None)?,
+        ForeignFormat::Kdbx => {
+            let bytes = BASE64.decode(data.trim()).map_err(|_| invalid("not a KeePass database"))?;
+            let (xml, mut protected) = kdbx::open(&bytes, passphrase.ok_or(LockboxError::PassphraseRequired)?)?;
+            keepass_entries(&xml, Some(&mut protected))?
+        }
+        ForeignFormat::Bitwarden => bitwarden_entries(data)?,
+        ForeignFormat::Csv => csv_entries(data)?,
+    };
+    Ok(ExportData {
+        notice: None,
+        version: String::new(),
+        exported_at: 0,
+        lockboxes: entries.into_iter().map(|entry| entry.into_lockbox(options)).collect(),
+        manifest: None,
+        device_verified: false,
+        source: Some(options.format),
+    })
+}
+
+/// Writes `lockboxes`, with their contents in clear, in `format`.
+pub fn write(format: ClearFormat, lockboxes: &[ExportLockbox]) -> String {
+    let entries = lockboxes.iter().map(ForeignEntry::from_lockbox);
+    match format {
+        ClearFormat::KeepassXml => keepass_xml(entries),
+        ClearFormat::Csv => csv(entries),
+    }
+}
+
+/// A group of the KeePass document being written, one per folder.
+#[derive(Default)]
+struct Group {
+    name: String,
+    groups: Vec<Group>,
+    entries: Vec<ForeignEntry>,
+}
+
+impl Group {
+    /// Files `entry` under the subgroups of its folder path from `depth` on.
+    fn insert(&mut self, entry: ForeignEntry, depth: usize) {
+        let Some(name) = entry.folder.get(depth).cloned() else {
+            self.entries.push(entry);
+            return;
+        };
+        let index = match self.groups.iter().position(|group| group.name == name) {
+            Some(index) => index,
+            None => {
+                self.groups.push(Group { name, ..Group::default() });
+                self.groups.len() - 1
+            }
+        };
+        self.groups[index].insert(entry, depth + 1);
+    }
+
+    fn write(&self, xml: &mut String, indent: usize) {
+        let pad = "\t".repeat(indent);
+        xml.push_str(&format!("{pad}<Group>\n{pad}\t<UUID>{}</UUID>\n", keepass_uuid()));
+        xml.push_str(&format!("{pad}\t<Name>{}</Name>\n", xml_text(&self.name)));
+        for entry in &self.entries {
+            write_keepass_entry(xml, entry, indent + 1);
+        }
+        for group in &self.groups {
+            group.write(xml, indent + 1);
+        }
+        xml.push_str(&format!("{pad}</Group>\n"));
+    }
+}
+
+/// KeePass 2 XML document of `entries`, each category a group under a
+/// top "Lockbox" group.
+fn keepass_xml(entries: impl Iterator<Item = ForeignEntry>) -> String {
+    let mut root = Group { name: "Lockbox".to_string(), ..Group::default() };
+    for entry in entries {
+        root.insert(entry, 0);
+    }
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<KeePassFile>\n");
+    xml.push_str("\t<Meta>\n\t\t<Generator>Lockbox</Generator>\n\t</Meta>\n\t<Root>\n");
+    root.write(&mut xml, 2);
+    xml.push_str("\t</Root>\n</KeePassFile>\n");
+    xml
+}
+
+fn write_keepass_entry(xml: &mut String, entry: &ForeignEntry, indent: usize) {
+    let pad = "\t".repeat(indent);
+    xml.push_str(&format!("{pad}<Entry>\n{pad}\t<UUID>{}</UUID>\n", keepass_uuid()));
+    if !entry.tags.is_empty() {
+        xml.push_str(&format!("{pad}\t<Tags>{}</Tags>\n", xml_text(&entry.tags.join(";"))));
+    }
+    let standard = [
+        ("Title", &entry.title),
+        ("UserName", &entry.username),
+        ("Password", &entry.password),
+        ("URL", &entry.url),
+        ("Notes", &entry.notes),
+    ];
+    // KeePass needs unique keys, so a second "URL" becomes "URL (2)"
+    let mut keys: Vec<String> = Vec::new();
+    for (label, value) in standard.into_iter().chain(entry.fields.iter().map(|(label, value)| (label.as_str(), value))) {
+        let mut key = label.to_string();
+        for n in 2.. {
+            if !keys.contains(&key) {
+                break;
+            }
+            key = format!("{} ({})", label, n);
+        }
+        let protect = if key == "Password" { " ProtectInMemory=\"True\"" } else { "" };
+        xml.push_str(&format!(
+            "{pad}\t<String><Key>{}</Key><Value{}>{}</Value></String>\n",
+            xml_text(&key),
+            protect,
+            xml_text(value)
+        ));
+        keys.push(key);
+    }
+    xml.push_str(&format!("{pad}</Entry>\n"));
+}
+
+fn keepass_uuid() -> String {
+    BASE64.encode(rand::random::<[u8; 16]>())
+}
+
+/// `text` escaped for XML, without the control characters XML 1.0 forbids.
+fn xml_text(text: &str) -> String {
+    let allowed: String = text.chars().filter(|&c| c >= ' ' || matches!(c, '\t' | '\n' | '\r')).collect();
+    escape(&allowed).into_owned()
+}
+
+/// Columns of a CSV export, recognised by the CSV import of most password managers.
+const CSV_HEADER: [&str; 8] = ["name", "url", "username", "password", "totp", "notes", "folder", "tags"];
+
+/// CSV document of `entries`. Fields other than a TOTP secret have no
+/// column, so they open the notes as "Label: value" lines.
+fn csv(entries: impl Iterator<Item = ForeignEntry>) -> String {
+    let mut out = String::new();
+    let mut push = |record: &[&str]| {
+        out.push_str(&record.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    };
+    push(&CSV_HEADER);
+    for entry in entries {
+        let totp = entry.fields.iter().position(|(label, _)| TOTP_COLUMNS.contains(&label.to_lowercase().as_str()));
+        let mut notes: Vec<String> = entry
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != totp)
+            .map(|(_, (label, value))| format!("{}: {}", label, value))
+            .collect();
+        if !entry.notes.is_empty() {
+            if !notes.is_empty() {
+                notes.push(String::new());
+            }
+            notes.push(entry.notes.clone());
+        }
+        push(&[
+            &entry.title,
+            &entry.url,
+            &entry.username,
+            &entry.password,
+            totp.map(|index| entry.fields[index].1.as_str()).unwrap_or_default(),
+            &notes.join("\n"),
+            &entry.folder.join("/"),
+            &entry.tags.join(", "),
+        ]);
+    }
+    out
+}
+
+/// `field` quoted as RFC 4180 asks when it holds a delimiter, quote, line
+/// break or surrounding spaces.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', ';', '"', '\r', '\n']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Entries of a KeePass XML document, outside the recycle bin and history.
+/// Protected values are revealed with `protected` when read from a KDBX file.
+fn keepass_entries(xml: &str, mut protected: Option<&mut ProtectedValues>) -> Result<Vec<ForeignEntry>> {
+    let xml_error = |e: &dyn std::fmt::Display| invalid(&e.to_string());
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().expand_empty_elements = true;
+
+    let mut path: Vec<String> = Vec::new();
+    // Name of each open group, and whether it is (in) the recycle bin
+    let mut groups: Vec<(String, bool)> = Vec::new();
+    let mut recycle_bin = None;
+    let mut entry: Option<ForeignEntry> = None;
+    let (mut text, mut key, mut value) = (String::new(), String::new(), String::new());
+    let mut is_protected = false;
+    let mut entries = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(&e))? {
+            Event::Start(element) => {
+                let name = element.name().0.to_string();
+                let in_history = path.iter().any(|open| open == "History");
+                match name.as_str() {
+                    _ if path.is_empty() && name != "KeePassFile" => return Err(invalid("not a KeePass XML file")),
+                    "Group" => groups.push((String::new(), groups.last().is_some_and(|group| group.1))),
+                    "Entry" if !in_history => entry = Some(ForeignEntry::default()),
+                    "Value" => {
+                        let attribute = element.try_get_attribute("Protected").map_err(|e| xml_error(&e))?;
+                        is_protected = attribute.is_some_and(|attribute| attribute.value == "True");
+                    }
+                    _ => {}
+                }
+                text.clear();
+                path.push(name);
+            }
+            Event::Text(content) => text.push_str(&content.xml10_content()),
+            Event::CData(content) => text.push_str(&content.xml10_content()),
+            Event::GeneralRef(reference) => {
+                match reference.resolve_char_ref().map_err(|e| xml_error(&e))? {
+                    Some(c) => text.push(c),
+                    None => text.push_str(resolve_predefined_entity(&reference).unwrap_or_default()),
+                }
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str).unwrap_or_default();
+                let in_history = path.iter().any(|open| open == "History");
+                let text = std::mem::take(&mut text);
+                match (parent, name.as_str()) {
+                    ("Meta", "RecycleBinUUID") => recycle_bin = Some(text),
+                    ("Group", "Name") => {
+                        if let Some(group) = groups.last_mut() {
+                            group.0 = text;
+                        }
+                    }
+                    ("Group", "UUID") => {
+                        if let Some(group) = groups.last_mut() {
+                            group.1 |= recycle_bin.as_ref() == Some(&text);
+                        }
+                    }
+                    ("String", "Key") => key = text,
+                    // Every protected value is revealed, history included, to
+                    // keep the stream in step.
+                    ("String", "Value") => {
+                        value = match protected.as_deref_mut() {
+                            Some(protected) if is_protected => protected.reveal(&text)?,
+                            _ => text,
+                        }
+                    }
+                    ("Entry", "String") if !in_history => {
+                        if let Some(entry) = entry.as_mut() {
+                            let value = std::mem::take(&mut value);
+                            match key.as_str() {
+                                "Title" => entry.title = value,
+                                "UserName" => entry.username = value,
+                                "Password" => entry.password = value,
+                                "URL" => entry.url = value,
+                                "Notes" => entry.notes = value,
+                                _ => entry.fields.push((key.clone(), value)),
+                            }
+                        }
+                    }
+                    ("Entry", "Tags") if !in_history => {
+                        if let Some(entry) = entry.as_mut() {
+                            entry.tags = split_list(&text);
+                        }
+                    }
+                    (_, "Entry") if !in_history => {
+                        if let Some(mut entry) = entry.take() {
+                            // The top group is the database itself
+                            entry.folder = groups.iter().skip(1).map(|group| group.0.clone()).collect();
+                            if !groups.last().is_some_and(|group| group.1) {
+                                entries.push(entry);
+                            }
+                        }
+                    }
+                    (_, "Group") => {
+                        groups.pop();
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Entries of an unencrypted Bitwarden JSON export.
+fn bitwarden_entries(data: &str) -> Result<Vec<ForeignEntry>> {
+    let export: Value = serde_json::from_str(data).map_err(|e| invalid(&e.to_string()))?;
+    if export.get("encrypted").and_then(Value::as_bool) == Some(true) {
+        return Err(LockboxError::UnsupportedForeignFile {
+            detail: "encrypted Bitwarden export; export the vault again as unencrypted JSON".to_string(),
+        });
+    }
+    let items = export.get("items").and_then(Value::as_array).ok_or_else(|| invalid("not a Bitwarden export"))?;
+    let names = |key: &str| -> Vec<(String, String)> {
+        export.get(key).and_then(Value::as_array).into_iter().flatten().filter_map(|folder| {
+            Some((folder.get("id")?.as_str()?.to_string(), folder.get("name")?.as_str()?.to_string()))
+        })
+        .collect()
+    };
+    let (folders, collections) = (names("folders"), names("collections"));
+    let text = |value: Option<&Value>| match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Number(number)) => number.to_string(),
+        _ => String::new(),
+    };
+
+    Ok(items
+        .iter()
+        .map(|item| {
+            let mut entry = ForeignEntry {
+                title: text(item.get("name")),
+                notes: text(item.get("notes")),
+                ..Default::default()
+            };
+            if let Some(login) = item.get("login") {
+                entry.username = text(login.get("username"));
+                entry.password = text(login.get("password"));
+                let uris = login.get("uris").and_then(Value::as_array).into_iter().flatten();
+                for (index, uri) in uris.map(|uri| text(uri.get("uri"))).filter(|uri| !uri.is_empty()).enumerate() {
+                    match index {
+                        0 => entry.url = uri,
+                        _ => entry.fields.push(("URL".to_string(), uri)),
+                    }
+                }
+                entry.fields.push(("TOTP".to_string(), text(login.get("totp"))));
+            }
+            // Cards, identities and SSH keys: every field, labelled after its key
+            for kind in ["card", "identity", "sshKey"] {
+                if let Some(Value::Object(fields)) = item.get(kind) {
+                    entry.fields.extend(fields.iter().map(|(key, value)| (label(key), text(Some(value)))));
+                }
+            }
+            let custom = item.get("fields").and_then(Value::as_array).into_iter().flatten();
+            entry.fields.extend(custom.map(|field| (text(field.get("name")), text(field.get("value")))));
+            entry.fields.retain(|(_, value)| !value.is_empty());
+
+            let folder = folders
+                .iter()
+                .find(|(id, _)| Some(id.as_str()) == item.get("folderId").and_then(Value::as_str))
+                .or_else(|| {
+                    let first = item.get("collectionIds").and_then(Value::as_array).and_then(|ids| ids.first());
+                    collections.iter().find(|(id, _)| Some(id.as_str()) == first.and_then(Value::as_str))
+                });
+            // Bitwarden nests folders by naming them "Parent/Child"
+            entry.folder = folder.map(|(_, name)| name.split('/').map(str::to_string).collect()).unwrap_or_default();
+            entry
+        })
+        .collect())
+}
+
+/// "cardholderName" as "Cardholder name".
+fn label(key: &str) -> String {
+    let mut label = String::new();
+    for (index, c) in key.chars().enumerate() {
+        match c {
+            _ if index == 0 => label.extend(c.to_uppercase()),
+            'A'..='Z' => {
+                label.push(' ');
+                label.push(c.to_ascii_lowercase());
+            }
+            _ => label.push(c),
+        }
+    }
+    label
+}
+
+/// Header names of each field, in lower case, across the CSV layouts of
+/// Chrome, Firefox, Safari, 1Password, Bitwarden, LastPass and KeePassXC.
+const TITLE_COLUMNS: &[&str] = &["title", "name"];
+const USERNAME_COLUMNS: &[&str] = &["username", "login_username", "login", "user", "email"];
+const PASSWORD_COLUMNS: &[&str] = &["password", "login_password"];
+const URL_COLUMNS: &[&str] = &["url", "login_uri", "website", "uri"];
+const NOTES_COLUMNS: &[&str] = &["notes", "note", "extra", "comments"];
+const FOLDER_COLUMNS: &[&str] = &["folder", "grouping", "group"];
+const TAGS_COLUMNS: &[&str] = &["tags"];
+const TOTP_COLUMNS: &[&str] = &["totp", "otpauth", "login_totp", "otp"];
+
+/// Entries of a password CSV, recognised by its header line.
+fn csv_entries(data: &str) -> Result<Vec<ForeignEntry>> {
+    let mut records = csv_records(data).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or_else(|| invalid("empty CSV file"))?
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|name| names.contains(&name.as_str()));
+    let (title, password) = (column(TITLE_COLUMNS), column(PASSWORD_COLUMNS));
+    if title.is_none() && password.is_none() {
+        return Err(invalid("no name or password column in the CSV header"));
+    }
+    let (username, url, notes) = (column(USERNAME_COLUMNS), column(URL_COLUMNS), column(NOTES_COLUMNS));
+    let (folder, tags, totp) = (column(FOLDER_COLUMNS), column(TAGS_COLUMNS), column(TOTP_COLUMNS));
+
+    Ok(records
+        .map(|record| {
+            let cell = |index: Option<usize>| index.and_then(|i| record.get(i)).cloned().unwrap_or_default();
+            ForeignEntry {
+                title: cell(title),
+                username: cell(username),
+                password: cell(password),
+                url: cell(url),
+                notes: cell(notes),
+                fields: Some(("TOTP".to_string(), cell(totp))).into_iter().filter(|(_, v)| !v.is_empty()).collect(),
+                folder: cell(folder)
+                    .split(['/', '\\'])
+                    .map(str::to_string)
+                    .filter(|name| !name.is_empty())
+                    .collect(),
+                tags: split_list(&cell(tags)),
+            }
+        })
+        .filter(|entry| !entry.name().is_empty() || !entry.password.is_empty())
+        .collect())
+}
+
+/// Splits a CSV document (RFC 4180) into records. The delimiter is a comma,
+/// or a semicolon when the header line has more of them.
+fn csv_records(data: &str) -> Vec<Vec<String>> {
+    let data = data.trim_start_matches('\u{feff}');
+    let header = data.lines().next().unwrap_or_default();
+    let delimiter = if header.matches(';').count() > header.matches(',').count() { ';' } else { ',' };
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c if c == delimiter && !quoted => record.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|record| record.iter().any(|field| !field.is_empty()));
+    records
+}
+
+/// Tags written as "a; b" or "a, b".
+fn split_list(value: &str) -> Vec<String> {
+    value.split([';', ',']).map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(folders: FolderMapping) -> ForeignImport {
+        ForeignImport { format: ForeignFormat::Csv, unlock_delay_seconds: 3600, relock_delay_seconds: 60, folders }
+    }
+
+    #[test]
+    fn test_keepass_xml_skips_history_and_recycle_bin() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+            <KeePassFile><Meta><RecycleBinUUID>BIN</RecycleBinUUID></Meta><Root>
+            <Group><UUID>ROOT</UUID><Name>Database</Name>
+              <Group><UUID>G1</UUID><Name>Work &amp; Co</Name>
+                <Entry><String><Key>Title</Key><Value>Mail</Value></String>
+                  <String><Key>Password</Key><Value ProtectInMemory="True">p&lt;w</Value></String>
+                  <String><Key>otp</Key><Value>SECRET</Value></String>
+                  <String><Key>Notes</Key><Value /></String><Tags>a;b</Tags>
+                  <History><Entry><String><Key>Title</Key><Value>Old</Value></String></Entry></History>
+                </Entry>
+              </Group>
+              <Group><UUID>BIN</UUID><Name>Recycle Bin</Name>
+                <Entry><String><Key>Title</Key><Value>Deleted</Value></String></Entry>
+              </Group>
+            </Group></Root></KeePassFile>"#;
+        let entries = keepass_entries(xml, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.title, "Mail");
+        assert_eq!(entry.folder, vec!["Work & Co"]);
+        assert_eq!(entry.tags, vec!["a", "b"]);
+        assert_eq!(entry.content(), "Password: p<w\notp: SECRET");
+        assert!(keepass_entries("<Other/>", None).is_err());
+    }
+
+    #[test]
+    fn test_bitwarden_items_and_folders() {
+        let json = r#"{"encrypted": false,
+            "folders": [{"id": "f1", "name": "Finance/Cards"}],
+            "items": [
+              {"type": 1, "name": "Bank", "notes": "pin in safe", "folderId": "f1",
+               "login": {"username": "me", "password": "pw", "totp": null,
+                         "uris": [{"uri": "https://bank.example"}, {"uri": "https://m.bank.example"}]},
+               "fields": [{"name": "Customer id", "value": "42", "type": 0}]},
+              {"type": 3, "name": "Visa", "folderId": null,
+               "card": {"cardholderName": "Me", "number": "4111", "code": null}}
+            ]}"#;
+        let entries = bitwarden_entries(json).unwrap();
+        assert_eq!(entries[0].folder, vec!["Finance", "Cards"]);
+        assert_eq!(
+            entries[0].content(),
+            "Username: me\nPassword: pw\nURL: https://bank.example\nURL: https://m.bank.example\nCustomer id: 42\n\npin in safe"
+        );
+        assert_eq!(entries[1].content(), "Cardholder name: Me\nNumber: 4111");
+
+        let lockbox = entries[0].clone().into_lockbox(&options(FolderMapping::Tags));
+        assert_eq!(lockbox.category, None);
+        assert_eq!(lockbox.tags.as_deref(), Some(r#"["Finance","Cards"]"#));
+        let lockbox = entries[0].clone().into_lockbox(&options(FolderMapping::Category));
+        assert_eq!(lockbox.category.as_deref(), Some("Finance / Cards"));
+        assert!(matches!(
+            bitwarden_entries(r#"{"encrypted": true, "data": "..."}"#),
+            Err(LockboxError::UnsupportedForeignFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_csv_layouts() {
+        // Chrome, with a quoted field spanning two lines
+        let chrome = "name,url,username,password,note\r\nMail,https://mail.example,me,\"p,\"\"w\",\"line 1\nline 2\"\r\n";
+        let entries = csv_entries(chrome).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].password, "p,\"w");
+        assert_eq!(entries[0].notes, "line 1\nline 2");
+
+        // Firefox has no name column; the URL names the lockbox
+        let firefox = "\u{feff}\"url\",\"username\",\"password\",\"guid\"\n\"https://a.example\",\"me\",\"pw\",\"{1}\"\n";
+        let lockbox = csv_entries(firefox).unwrap().remove(0).into_lockbox(&options(FolderMapping::Category));
+        assert_eq!(lockbox.name, "https://a.example");
+        assert_eq!(lockbox.unlock_delay_seconds, 3600);
+
+        // A semicolon-separated export with folders
+        let entries = csv_entries("Title;Password;Group\nWifi;secret;Home/Network\n").unwrap();
+        assert_eq!(entries[0].folder, vec!["Home", "Network"]);
+        assert!(csv_entries("a,b\n1,2\n").is_err());
+    }
+
+    #[test]
+    fn test_lockbox_content_read_back_as_entry() {
+        let entry = ForeignEntry {
+            title: "Bank".to_string(),
+            username: "me".to_string(),
+            password: "pw".to_string(),
+            url: "https://bank.example".to_string(),
+            notes: "Pin: in the safe\nsecond line".to_string(),
+            fields: vec![("URL".to_string(), "https://m.bank.example".to_string()), ("TOTP".to_string(), "SECRET".to_string())],
+            folder: vec!["Finance".to_string(), "Cards".to_string()],
+            tags: vec!["money".to_string()],
+        };
+        let lockbox = entry.clone().into_lockbox(&options(FolderMapping::Category));
+        assert_eq!(ForeignEntry::from_lockbox(&lockbox), entry);
+
+        let lockbox = ExportLockbox { content: "hunter2\n".to_string(), category: None, tags: None, ..lockbox };
+        let entry = ForeignEntry::from_lockbox(&lockbox);
+        assert_eq!((entry.password.as_str(), entry.notes.as_str()), ("hunter2", ""));
+        let lockbox = ExportLockbox { content: "Call the bank\nthen: wait".to_string(), ..lockbox };
+        assert_eq!(ForeignEntry::from_lockbox(&lockbox).notes, "Call the bank\nthen: wait");
+    }
+
+    #[test]
+    fn test_clear_exports_read_back() {
+        let lockbox = |name: &str, content: &str, category: Option<&str>| ExportLockbox {
+            content: content.to_string(),
+            category: category.map(str::to_string),
+            ..ForeignEntry { title: name.to_string(), ..ForeignEntry::default() }
+                .into_lockbox(&options(FolderMapping::Category))
+        };
+        let lockboxes = vec![
+            lockbox("Mail", "Username: me\nPassword: p<w&\"\nURL: a\nURL: b\nTOTP: SECRET\n\nnotes, \"quoted\"", Some("Work / Email")),
+            lockbox("Wifi", "secret", None),
+        ];
+
+        let entries = keepass_entries(&write(ClearFormat::KeepassXml, &lockboxes), None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title, "Wifi");
+        assert_eq!(entries[0].password, "secret");
+        assert_eq!(entries[1].folder, vec!["Work", "Email"]);
+        assert_eq!(
+            entries[1].content(),
+            "Username: me\nPassword: p<w&\"\nURL: a\nURL (2): b\nTOTP: SECRET\n\nnotes, \"quoted\""
+        );
+
+        let entries = csv_entries(&write(ClearFormat::Csv, &lockboxes)).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].folder, vec!["Work", "Email"]);
+        assert_eq!(entries[0].notes, "URL: b\n\nnotes, \"quoted\"");
+        assert_eq!(
+            entries[0].content(),
+            "Username: me\nPassword: p<w&\"\nURL: a\nTOTP: SECRET\n\nURL: b\n\nnotes, \"quoted\""
+        );
+        assert_eq!(entries[1].password, "secret");
+    }
+}
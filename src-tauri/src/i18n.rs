@@ -0,0 +1,255 @@
+use crate::db::Database;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Settings key holding the user's chosen UI locale (e.g. "fr").
+pub const LOCALE_SETTING_KEY: &str = "locale";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    En,
+    Fr,
+}
+
+impl Locale {
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// Parses a BCP 47 / POSIX locale tag such as "fr", "fr-CA" or "fr_FR.UTF-8".
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "fr" => Some(Locale::Fr),
+            _ => None,
+        }
+    }
+
+    /// Locale reported by the operating system, English if unsupported.
+    pub fn system() -> Locale {
+        sys_locale::get_locale()
+            .and_then(|tag| Locale::from_tag(&tag))
+            .unwrap_or(Locale::En)
+    }
+
+    /// Locale stored in settings, falling back to the system locale.
+    pub fn resolve(db: &Database) -> Locale {
+        db.get_setting(LOCALE_SETTING_KEY)
+            .ok()
+            .flatten()
+            .and_then(|tag| Locale::from_tag(&tag))
+            .unwrap_or_else(Locale::system)
+    }
+
+    fn from_index(index: u8) -> Locale {
+        match index {
+            1 => Locale::Fr,
+            _ => Locale::En,
+        }
+    }
+
+    fn index(self) -> u8 {
+        match self {
+            Locale::En => 0,
+            Locale::Fr => 1,
+        }
+    }
+}
+
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+/// Locale used for backend-generated text (errors, dialogs, notifications).
+pub fn current() -> Locale {
+    Locale::from_index(CURRENT_LOCALE.load(Ordering::Relaxed))
+}
+
+pub fn set_current(locale: Locale) {
+    CURRENT_LOCALE.store(locale.index(), Ordering::Relaxed);
+}
+
+/// Looks up `key` for `locale` and substitutes `{name}` placeholders.
+/// Falls back to English, then to the key itself.
+pub fn tr(locale: Locale, key: &str, args: &[(&str, String)]) -> String {
+    let template = catalog(locale, key)
+        .or_else(|| catalog(Locale::En, key))
+        .unwrap_or(key);
+
+    args.iter().fold(template.to_string(), |acc, (name, value)| {
+        acc.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+fn catalog(locale: Locale, key: &str) -> Option<&'static str> {
+    match locale {
+        Locale::En => en(key),
+        Locale::Fr => fr(key),
+    }
+}
+
+fn en(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "dialog.outdated.title" => "Update required",
+        "dialog.outdated.body" => "Outdated version.\n\nThis database was modified by a newer version of Lockbox and requires at least version {required}.\nYou are currently using version {current}.\n\nPlease update the application to access your data.",
+        "dialog.blocked.title" => "Action required",
+        "dialog.blocked.body" => "Update blocked.\n\nPlease close every instance of the Lockbox application before continuing.\n\nError: {detail}",
+        "dialog.log_tampered.title" => "Access history altered",
+        "dialog.log_tampered.body" => "The start of the access history no longer matches its anchor. Entries may have been deleted or edited outside of Lockbox.\n\nYou can check the full history from the statistics window.",
+        "notification.unlocked.title" => "Lockbox Unlocked",
+        "notification.unlocked.body" => "\"{name}\" is now unlocked and accessible.",
+        "notification.scheduled.title" => "Scheduled Unlock",
+        "notification.scheduled.body" => "\"{name}\" is now unlocked.",
+        "notification.relocked.title" => "Lockbox Relocked",
+        "notification.relocked.body" => "\"{name}\" has been automatically relocked.",
+        "notification.tamper.title" => "Clock Tamper Detected",
+        "notification.tamper.body" => "System clock manipulation detected. Active lockboxes have been relocked.",
+        "export.notice" => "Lockbox Local export. Content is encrypted and signed; do not edit this file by hand.",
+        "error.not_found" => "This lockbox no longer exists.",
+        "error.locked" => "This lockbox is locked.",
+        "error.content_locked" => "Content cannot be edited while the lockbox is locked.",
+        "error.unlock_delay_reduction" => "The unlock delay cannot be reduced while locked ({current}s → {requested}s).",
+        "error.relock_delay_reduction" => "The relock delay cannot be reduced while locked ({current}s → {requested}s).",
+        "error.view_limit_increase" => "The view limit cannot be raised or removed while locked.",
+        "error.panic_code_locked" => "The emergency code cannot be changed while locked.",
+        "error.schedule_locked" => "The scheduled unlock cannot be changed while locked.",
+        "error.schedule_earlier" => "The scheduled unlock cannot be moved to an earlier date.",
+        "error.schedule_in_past" => "The scheduled unlock date must be in the future.",
+        "error.non_positive_extension" => "The additional delay must be positive.",
+        "error.invalid_import_format" => "Invalid file format. Make sure this is a Lockbox Local export file.",
+        "error.integrity_check_failed" => "\"{name}\" failed the integrity check: the file may have been tampered with.",
+        "error.source_decryption_failed" => "Could not decrypt \"{name}\": wrong source password?",
+        "error.validation" => "Invalid value for: {fields}.",
+        "error.invalid_imported_lockbox" => "\"{name}\" contains invalid values: {fields}.",
+        "error.invalid_cursor" => "The access history page is no longer valid. Please reload it.",
+        "error.invalid_timezone" => "Unknown timezone: {timezone}.",
+        "error.access_log_tampered" => "The access history failed its integrity check, so old entries were not compacted.",
+        "error.archive_failed" => "The access history archive could not be written: {detail}",
+        "error.snapshot_failed" => "The automatic backup failed: {detail}",
+        "error.snapshot_invalid" => "The backup \"{name}\" failed its integrity check: it is damaged, was modified, or belongs to another installation.",
+        "error.unsupported_foreign_file" => "This file cannot be imported: {detail}",
+        "error.unsupported_backup_version" => "This backup was made by a newer version of Lockbox Local (format {version}).",
+        "error.backup_signature_invalid" => "The backup failed its signature check: it was modified, or made under another master password.",
+        "error.manifest_invalid" => "The export file failed its signature check: it was modified, lockboxes were removed, or it was made under another master password.",
+        "error.untrusted_device" => "This file was signed by a device you do not trust yet (fingerprint {fingerprint}).",
+        "error.invalid_device_key" => "This is not a valid device public key.",
+        "error.device_key_unreadable" => "This device's signing key can no longer be read. Create a new one under Settings → Trusted devices, then trust it again on your other machines.",
+        "error.passphrase_required" => "This file is protected by a passphrase.",
+        "error.passphrase_incorrect" => "Incorrect passphrase, or the file was modified.",
+        "error.passphrase_too_short" => "The passphrase must be at least {min_length} characters long.",
+        "error.vault_not_empty" => "A backup can only be restored into an empty vault; this one holds {count} lockbox(es).",
+        "error.master_password_required" => "Set and enter a master password first.",
+        "error.tag_not_found" => "Tag \"{name}\" does not exist.",
+        "error.tag_exists" => "A tag named \"{name}\" already exists.",
+        "error.category_not_found" => "This category does not exist.",
+        "error.category_exists" => "A category named \"{name}\" already exists.",
+        "error.folder_not_found" => "This folder does not exist.",
+        "error.folder_exists" => "A folder named \"{name}\" already exists here.",
+        "error.folder_cycle" => "A folder cannot be moved into one of its own subfolders.",
+        "error.below_category_minimum" => "Lockboxes in \"{category}\" need an unlock delay of at least {minimum}s ({requested}s requested).",
+        "error.unsupported_locale" => "Unsupported language: {locale}.",
+        "error.crypto" => "A cryptographic operation failed.",
+        "error.serialization" => "The data could not be serialized.",
+        "error.database" => "Database error: {detail}",
+        "error.migration_blocked" => "The database could not be migrated: {detail}",
+        "error.vault_key_unavailable" => "The vault key could not be read or created: {detail}",
+        "error.state_poisoned" => "Internal error. Please restart the application.",
+        _ => return None,
+    })
+}
+
+fn fr(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "dialog.outdated.title" => "Mise à jour requise",
+        "dialog.outdated.body" => "Version obsolète.\n\nCette base de données a été modifiée par une version plus récente de Lockbox et nécessite au minimum la version {required}.\nVous utilisez actuellement la version {current}.\n\nVeuillez mettre à jour l'application pour accéder à vos données.",
+        "dialog.blocked.title" => "Action requise",
+        "dialog.blocked.body" => "Mise à jour bloquée.\n\nVeuillez fermer toutes les instances de l'application Lockbox avant de continuer.\n\nErreur: {detail}",
+        "dialog.log_tampered.title" => "Historique des accès altéré",
+        "dialog.log_tampered.body" => "Le début de l'historique des accès ne correspond plus à son ancre. Des entrées ont peut-être été supprimées ou modifiées en dehors de Lockbox.\n\nVous pouvez vérifier l'historique complet depuis la fenêtre des statistiques.",
+        "notification.unlocked.title" => "Lockbox Déverrouillée",
+        "notification.unlocked.body" => "\"{name}\" est maintenant déverrouillée et accessible.",
+        "notification.scheduled.title" => "Déverrouillage Planifié",
+        "notification.scheduled.body" => "\"{name}\" est maintenant déverrouillée.",
+        "notification.relocked.title" => "Lockbox Reverrouillée",
+        "notification.relocked.body" => "\"{name}\" a été automatiquement reverrouillée.",
+        "notification.tamper.title" => "Manipulation d'Horloge Détectée",
+        "notification.tamper.body" => "Une manipulation de l'horloge système a été détectée. Les lockboxes actives ont été reverrouillées.",
+        "export.notice" => "Export Lockbox Local. Le contenu est chiffré et signé ; ne modifiez pas ce fichier à la main.",
+        "error.not_found" => "Cette lockbox n'existe plus.",
+        "error.locked" => "Cette lockbox est verrouillée.",
+        "error.content_locked" => "Le contenu ne peut pas être modifié tant que la lockbox est verrouillée.",
+        "error.unlock_delay_reduction" => "Le délai de déverrouillage ne peut pas être réduit tant qu'elle est verrouillée ({current}s → {requested}s).",
+        "error.relock_delay_reduction" => "Le délai de reverrouillage ne peut pas être réduit tant qu'elle est verrouillée ({current}s → {requested}s).",
+        "error.view_limit_increase" => "La limite de consultations ne peut pas être augmentée ou retirée tant qu'elle est verrouillée.",
+        "error.panic_code_locked" => "Le code d'urgence ne peut pas être modifié tant qu'elle est verrouillée.",
+        "error.schedule_locked" => "Le déverrouillage planifié ne peut pas être modifié tant qu'elle est verrouillée.",
+        "error.schedule_earlier" => "Le déverrouillage planifié ne peut pas être avancé à une date antérieure.",
+        "error.schedule_in_past" => "La date de déverrouillage planifié doit être dans le futur.",
+        "error.non_positive_extension" => "Le délai supplémentaire doit être positif.",
+        "error.invalid_import_format" => "Format de fichier invalide. Vérifiez qu'il s'agit bien d'un export Lockbox Local.",
+        "error.integrity_check_failed" => "« {name} » n'a pas passé le contrôle d'intégrité : le fichier a peut-être été modifié.",
+        "error.source_decryption_failed" => "Impossible de déchiffrer « {name} » : mauvais mot de passe source ?",
+        "error.validation" => "Valeur invalide pour : {fields}.",
+        "error.invalid_imported_lockbox" => "« {name} » contient des valeurs invalides : {fields}.",
+        "error.invalid_cursor" => "Cette page de l'historique des accès n'est plus valide. Veuillez la recharger.",
+        "error.invalid_timezone" => "Fuseau horaire inconnu : {timezone}.",
+        "error.access_log_tampered" => "L'historique des accès n'a pas passé la vérification d'intégrité : les anciennes entrées n'ont pas été compactées.",
+        "error.archive_failed" => "L'archive de l'historique des accès n'a pas pu être écrite : {detail}",
+        "error.snapshot_failed" => "La sauvegarde automatique a échoué : {detail}",
+        "error.snapshot_invalid" => "La sauvegarde « {name} » n'a pas passé la vérification d'intégrité : elle est endommagée, a été modifiée, ou appartient à une autre installation.",
+        "error.unsupported_foreign_file" => "Ce fichier ne peut pas être importé : {detail}",
+        "error.unsupported_backup_version" => "Cette sauvegarde a été faite par une version plus récente de Lockbox Local (format {version}).",
+        "error.backup_signature_invalid" => "La sauvegarde n'a pas passé la vérification de signature : elle a été modifiée, ou faite sous un autre mot de passe maître.",
+        "error.manifest_invalid" => "Le fichier d'export n'a pas passé la vérification de signature : il a été modifié, des lockboxes en ont été retirées, ou il a été fait sous un autre mot de passe maître.",
+        "error.untrusted_device" => "Ce fichier a été signé par un appareil auquel vous ne faites pas encore confiance (empreinte {fingerprint}).",
+        "error.invalid_device_key" => "Ce n'est pas une clé publique d'appareil valide.",
+        "error.device_key_unreadable" => "La clé de signature de cet appareil ne peut plus être lue. Créez-en une nouvelle dans Paramètres → Appareils de confiance, puis faites-lui de nouveau confiance sur vos autres machines.",
+        "error.passphrase_required" => "Ce fichier est protégé par une phrase de passe.",
+        "error.passphrase_incorrect" => "Phrase de passe incorrecte, ou le fichier a été modifié.",
+        "error.passphrase_too_short" => "La phrase de passe doit contenir au moins {min_length} caractères.",
+        "error.vault_not_empty" => "Une sauvegarde ne peut être restaurée que dans un coffre vide ; celui-ci contient {count} lockbox(es).",
+        "error.master_password_required" => "Définissez et saisissez d'abord un mot de passe maître.",
+        "error.tag_not_found" => "Le tag « {name} » n'existe pas.",
+        "error.tag_exists" => "Un tag nommé « {name} » existe déjà.",
+        "error.category_not_found" => "Cette catégorie n'existe pas.",
+        "error.category_exists" => "Une catégorie nommée « {name} » existe déjà.",
+        "error.folder_not_found" => "Ce dossier n'existe pas.",
+        "error.folder_exists" => "Un dossier nommé « {name} » existe déjà ici.",
+        "error.folder_cycle" => "Un dossier ne peut pas être déplacé dans l'un de ses sous-dossiers.",
+        "error.below_category_minimum" => "Les lockboxes de « {category} » exigent un délai de déverrouillage d'au moins {minimum}s ({requested}s demandés).",
+        "error.unsupported_locale" => "Langue non prise en charge : {locale}.",
+        "error.crypto" => "Une opération cryptographique a échoué.",
+        "error.serialization" => "Les données n'ont pas pu être sérialisées.",
+        "error.database" => "Erreur de base de données : {detail}",
+        "error.migration_blocked" => "La base de données n'a pas pu être migrée : {detail}",
+        "error.vault_key_unavailable" => "La clé du coffre n'a pas pu être lue ou créée : {detail}",
+        "error.state_poisoned" => "Erreur interne. Veuillez redémarrer l'application.",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Locale::from_tag("fr_FR.UTF-8"), Some(Locale::Fr));
+        assert_eq!(Locale::from_tag("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("de-DE"), None);
+    }
+
+    #[test]
+    fn test_tr_interpolates_and_falls_back() {
+        let body = tr(Locale::Fr, "notification.relocked.body", &[("name", "Steam".to_string())]);
+        assert_eq!(body, "\"Steam\" a été automatiquement reverrouillée.");
+        assert_eq!(tr(Locale::Fr, "missing.key", &[]), "missing.key");
+    }
+}
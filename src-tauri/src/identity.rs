@@ -0,0 +1,148 @@
+//! Device identity: an Ed25519 key pair per vault that signs exports, so
+//! that another machine verifies them against a public key it trusts
+//! instead of the exporting master password. Knowing the master password
+//! hash is no longer enough to forge an export.
+
+use crate::crypto::{self, KEY_LENGTH};
+use crate::error::{LockboxError, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Settings key of the device signing key, sealed under the vault key.
+pub const DEVICE_KEY_SETTING_KEY: &str = "device_signing_key";
+
+/// Settings key of the device public key, kept in clear so that checking
+/// files never needs the signing key.
+pub const DEVICE_PUBLIC_KEY_SETTING_KEY: &str = "device_public_key";
+
+/// Settings key of the trusted device list, as JSON.
+pub const TRUSTED_DEVICES_SETTING_KEY: &str = "trusted_devices";
+
+/// Purpose of the vault subkey sealing the device signing key.
+pub const DEVICE_KEY_PURPOSE: &str = "device_key";
+
+/// Signing half of the device identity. Never leaves the machine unsealed.
+pub struct DeviceKey(SigningKey);
+
+impl DeviceKey {
+    pub fn generate() -> Self {
+        let mut secret = [0u8; KEY_LENGTH];
+        OsRng.fill_bytes(&mut secret);
+        DeviceKey(SigningKey::from_bytes(&secret))
+    }
+
+    /// Encrypts the secret key under `key` for storage.
+    pub fn seal(&self, key: &[u8; KEY_LENGTH]) -> Result<String> {
+        Ok(crypto::encrypt_with_key(&hex::encode(self.0.to_bytes()), key)?)
+    }
+
+    /// Reverses `seal`.
+    pub fn unseal(sealed: &str, key: &[u8; KEY_LENGTH]) -> Result<Self> {
+        let secret = hex::decode(crypto::decrypt_with_key(sealed, key)?)
+            .ok()
+            .and_then(|bytes| <[u8; KEY_LENGTH]>::try_from(bytes).ok())
+            .ok_or(crypto::CryptoError::InvalidFormat)?;
+        Ok(DeviceKey(SigningKey::from_bytes(&secret)))
+    }
+
+    /// Hex-encoded public key, as shared with other machines.
+    pub fn public_key(&self) -> String {
+        hex::encode(self.0.verifying_key().to_bytes())
+    }
+
+    /// Hex-encoded Ed25519 signature of `data`.
+    pub fn sign(&self, data: &str) -> String {
+        hex::encode(self.0.sign(data.as_bytes()).to_bytes())
+    }
+}
+
+/// Parses a hex-encoded public key, rejecting anything that is not a valid
+/// Ed25519 point.
+fn verifying_key(public_key: &str) -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(public_key.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// Checks a public key pasted by the user, returning it normalized.
+pub fn parse_public_key(public_key: &str) -> Result<String> {
+    verifying_key(public_key)
+        .map(|key| hex::encode(key.to_bytes()))
+        .ok_or(LockboxError::InvalidDeviceKey)
+}
+
+/// Verifies a signature made by `DeviceKey::sign`.
+pub fn verify(public_key: &str, data: &str, signature: &str) -> bool {
+    let Some(key) = verifying_key(public_key) else {
+        return false;
+    };
+    let Some(signature) = hex::decode(signature).ok().and_then(|bytes| Signature::from_slice(&bytes).ok()) else {
+        return false;
+    };
+    key.verify(data.as_bytes(), &signature).is_ok()
+}
+
+/// Short form of a public key for comparing by eye, e.g. "3F2A-91C0-…".
+pub fn fingerprint(public_key: &str) -> String {
+    let digest = hex::encode_upper(Sha256::digest(public_key.trim().to_lowercase().as_bytes()));
+    digest.as_bytes()[..20]
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// This machine's identity as shown in settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceIdentity {
+    pub public_key: String,
+    pub fingerprint: String,
+    /// False when the signing key no longer unseals; exports fail until it
+    /// is replaced.
+    pub can_sign: bool,
+}
+
+impl DeviceIdentity {
+    pub fn of(public_key: String, can_sign: bool) -> Self {
+        DeviceIdentity { fingerprint: fingerprint(&public_key), public_key, can_sign }
+    }
+}
+
+/// Another machine whose exports are accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedDevice {
+    pub public_key: String,
+    pub name: String,
+    pub fingerprint: String,
+    pub added_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures_verify_only_under_their_key() {
+        let device = DeviceKey::generate();
+        let signature = device.sign("file");
+        assert!(verify(&device.public_key(), "file", &signature));
+        assert!(!verify(&device.public_key(), "edited", &signature));
+        assert!(!verify(&DeviceKey::generate().public_key(), "file", &signature));
+        assert!(!verify("not a key", "file", &signature));
+    }
+
+    #[test]
+    fn test_sealed_key_round_trip() {
+        let device = DeviceKey::generate();
+        let key = [7u8; KEY_LENGTH];
+        let sealed = device.seal(&key).unwrap();
+        assert_eq!(DeviceKey::unseal(&sealed, &key).unwrap().public_key(), device.public_key());
+        assert!(DeviceKey::unseal(&sealed, &[8u8; KEY_LENGTH]).is_err());
+
+        assert_eq!(parse_public_key(&device.public_key().to_uppercase()).unwrap(), device.public_key());
+        assert!(matches!(parse_public_key("abcd"), Err(LockboxError::InvalidDeviceKey)));
+        assert_eq!(fingerprint(&device.public_key()).len(), 24);
+    }
+}
@@ -0,0 +1,451 @@
+//! Reading KeePass databases in the KDBX 4 format, opened with their master
+//! password, down to the XML document KeePass itself would export.
+//!
+//! Layout: a clear header (cipher, KDF parameters, seeds) followed by its
+//! SHA-256 and HMAC-SHA256, then the encrypted payload as HMAC-authenticated
+//! blocks. The decrypted payload has an inner header, holding the key of the
+//! stream that masks protected values (passwords) inside the XML.
+
+use crate::error::{LockboxError, Result};
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{generic_array::GenericArray, BlockDecryptMut, BlockEncrypt, KeyInit, KeyIvInit, StreamCipher};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20::ChaCha20;
+use flate2::read::GzDecoder;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use std::io::Read;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SIGNATURE: [u8; 8] = [0x03, 0xd9, 0xa2, 0x9a, 0x67, 0xfb, 0x4b, 0xb5];
+
+const CIPHER_AES256: [u8; 16] = hex_uuid(*b"31c1f2e6bf714350be5805216afc5aff");
+const CIPHER_CHACHA20: [u8; 16] = hex_uuid(*b"d6038a2b8b6f4cb5a524339a31dbb59a");
+const KDF_AES: [u8; 16] = hex_uuid(*b"c9d9f39a628a4460bf740d08c18a4fea");
+const KDF_ARGON2D: [u8; 16] = hex_uuid(*b"ef636ddf8c29444b91f7a9a403e30a0c");
+const KDF_ARGON2ID: [u8; 16] = hex_uuid(*b"9e298b1956db4773b23dfc3ec6f0a1e6");
+
+/// Inner stream masking protected values; KDBX 4 files use ChaCha20.
+const INNER_STREAM_CHACHA20: u32 = 3;
+
+/// Upper bounds on the cost read from a file, so that a crafted file cannot
+/// exhaust memory or hang the import. They leave room for KeePassXC's
+/// defaults (64 MiB, about a second of passes) several times over.
+const MAX_MEMORY_BYTES: u64 = 256 * 1024 * 1024;
+const MAX_ITERATIONS: u64 = 16;
+const MAX_PARALLELISM: u32 = 8;
+const MAX_AES_ROUNDS: u64 = 100_000_000;
+
+const fn hex_uuid(hex: [u8; 32]) -> [u8; 16] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            _ => c - b'a' + 10,
+        }
+    }
+    let mut uuid = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        uuid[i] = (nibble(hex[2 * i]) << 4) | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    uuid
+}
+
+fn invalid(detail: &str) -> LockboxError {
+    LockboxError::InvalidImportFormat { detail: detail.to_string() }
+}
+
+fn unsupported(detail: &str) -> LockboxError {
+    LockboxError::UnsupportedForeignFile { detail: detail.to_string() }
+}
+
+/// True when `data` starts like a KeePass database.
+pub fn is_kdbx(data: &[u8]) -> bool {
+    data.starts_with(&SIGNATURE)
+}
+
+/// Masked values of the XML, revealed in document order.
+pub struct ProtectedValues(ChaCha20);
+
+impl ProtectedValues {
+    /// Reveals the next protected value, given as found in the XML. Every
+    /// protected value must be passed, history included, or the following
+    /// ones come out garbled.
+    pub fn reveal(&mut self, value: &str) -> Result<String> {
+        let mut bytes = BASE64.decode(value.trim()).map_err(|_| invalid("invalid protected value"))?;
+        self.0.apply_keystream(&mut bytes);
+        String::from_utf8(bytes).map_err(|_| invalid("invalid protected value"))
+    }
+}
+
+/// Decrypts a KDBX 4 database with its master password. Databases that also
+/// need a key file cannot be opened.
+pub fn open(data: &[u8], password: &str) -> Result<(String, ProtectedValues)> {
+    if !is_kdbx(data) {
+        return Err(invalid("not a KeePass database"));
+    }
+    let mut reader = ByteReader { data, pos: SIGNATURE.len() };
+    let version = reader.u32()?;
+    match version >> 16 {
+        4 => {}
+        3 => return Err(unsupported("KDBX 3.1 database; save it in the KDBX 4 format, or export it to KeePass XML")),
+        major => return Err(unsupported(&format!("KDBX version {}", major))),
+    }
+
+    let header = Header::read(&mut reader)?;
+    let header_bytes = &data[..reader.pos];
+    if reader.take(32)? != Sha256::digest(header_bytes).as_slice() {
+        return Err(invalid("corrupted KeePass header"));
+    }
+    let header_hmac = reader.take(32)?;
+
+    let composite = Sha256::digest(Sha256::digest(password.as_bytes()));
+    let transformed = header.kdf.transform(&composite)?;
+    let mut seeded = header.master_seed.clone();
+    seeded.extend_from_slice(&transformed);
+    let encryption_key = Sha256::digest(&seeded);
+    seeded.push(1);
+    let hmac_key = Sha512::digest(&seeded);
+
+    if block_mac(&hmac_key, u64::MAX, &[header_bytes]).verify_slice(header_hmac).is_err() {
+        return Err(LockboxError::PassphraseIncorrect);
+    }
+
+    let mut payload = Vec::new();
+    for index in 0u64.. {
+        let mac = reader.take(32)?;
+        let length = reader.u32()?;
+        let block = reader.take(length as usize)?;
+        let mac_data: [&[u8]; 3] = [&index.to_le_bytes(), &length.to_le_bytes(), block];
+        if block_mac(&hmac_key, index, &mac_data).verify_slice(mac).is_err() {
+            return Err(invalid("corrupted KeePass database"));
+        }
+        if block.is_empty() {
+            break;
+        }
+        payload.extend_from_slice(block);
+    }
+
+    let mut payload = match header.cipher {
+        Cipher::Aes256 => aes_cbc_decrypt(&payload, &encryption_key, &header.iv)?,
+        Cipher::ChaCha20 => {
+            let nonce: [u8; 12] = header.iv.as_slice().try_into().map_err(|_| invalid("invalid ChaCha20 nonce"))?;
+            ChaCha20::new(&encryption_key, &nonce.into()).apply_keystream(&mut payload);
+            payload
+        }
+    };
+    if header.compressed {
+        let mut inflated = Vec::new();
+        GzDecoder::new(payload.as_slice())
+            .read_to_end(&mut inflated)
+            .map_err(|e| invalid(&e.to_string()))?;
+        payload = inflated;
+    }
+
+    let mut inner = ByteReader { data: &payload, pos: 0 };
+    let mut stream_id = None;
+    let mut stream_key = None;
+    loop {
+        let id = inner.u8()?;
+        let length = inner.u32()? as usize;
+        let value = inner.take(length)?;
+        match id {
+            0 => break,
+            1 => stream_id = Some(ByteReader { data: value, pos: 0 }.u32()?),
+            2 => stream_key = Some(value),
+            _ => {} // attachments are not imported
+        }
+    }
+    if stream_id != Some(INNER_STREAM_CHACHA20) {
+        return Err(unsupported("protected values masked with another stream than ChaCha20"));
+    }
+    let stream_key = Sha512::digest(stream_key.ok_or_else(|| invalid("missing inner stream key"))?);
+    let stream = ChaCha20::new_from_slices(&stream_key[..32], &stream_key[32..44])
+        .map_err(|_| invalid("invalid inner stream key"))?;
+
+    let xml = String::from_utf8(payload[inner.pos..].to_vec()).map_err(|_| invalid("KeePass XML is not UTF-8"))?;
+    Ok((xml, ProtectedValues(stream)))
+}
+
+/// HMAC-SHA256 of `parts` under the key of block `index`; the header uses
+/// the key of block `u64::MAX`.
+fn block_mac(hmac_key: &[u8], index: u64, parts: &[&[u8]]) -> HmacSha256 {
+    let mut hasher = Sha512::new();
+    hasher.update(index.to_le_bytes());
+    hasher.update(hmac_key);
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(&hasher.finalize()).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+fn aes_cbc_decrypt(data: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    let cipher = cbc::Decryptor::<Aes256>::new_from_slices(key, iv).map_err(|_| invalid("invalid AES payload"))?;
+    // a wrong length or padding means a wrong key, the blocks being authenticated
+    cipher.decrypt_padded_vec_mut::<Pkcs7>(data).map_err(|_| LockboxError::PassphraseIncorrect)
+}
+
+enum Cipher {
+    Aes256,
+    ChaCha20,
+}
+
+enum Kdf {
+    Aes { seed: Vec<u8>, rounds: u64 },
+    Argon2 { algorithm: Algorithm, version: Version, salt: Vec<u8>, memory: u64, iterations: u64, parallelism: u32 },
+}
+
+impl Kdf {
+    fn transform(&self, composite: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Kdf::Aes { seed, rounds } => {
+                if seed.len() != 32 || *rounds > MAX_AES_ROUNDS {
+                    return Err(unsupported("AES-KDF parameters"));
+                }
+                let cipher = Aes256::new(GenericArray::from_slice(seed));
+                let mut key = composite.to_vec();
+                for _ in 0..*rounds {
+                    for half in key.chunks_mut(16) {
+                        cipher.encrypt_block(GenericArray::from_mut_slice(half));
+                    }
+                }
+                Ok(Sha256::digest(&key).to_vec())
+            }
+            Kdf::Argon2 { algorithm, version, salt, memory, iterations, parallelism } => {
+                if *memory > MAX_MEMORY_BYTES || *iterations > MAX_ITERATIONS || *parallelism > MAX_PARALLELISM {
+                    return Err(unsupported("Argon2 cost above the supported limits"));
+                }
+                let params = Params::new((*memory / 1024) as u32, *iterations as u32, *parallelism, Some(32))
+                    .map_err(|e| invalid(&e.to_string()))?;
+                let mut key = vec![0u8; 32];
+                Argon2::new(*algorithm, *version, params)
+                    .hash_password_into(composite, salt, &mut key)
+                    .map_err(|e| invalid(&e.to_string()))?;
+                Ok(key)
+            }
+        }
+    }
+}
+
+struct Header {
+    cipher: Cipher,
+    compressed: bool,
+    master_seed: Vec<u8>,
+    iv: Vec<u8>,
+    kdf: Kdf,
+}
+
+impl Header {
+    fn read(reader: &mut ByteReader) -> Result<Header> {
+        let (mut cipher, mut compressed, mut master_seed, mut iv, mut kdf) = (None, false, None, None, None);
+        loop {
+            let id = reader.u8()?;
+            let length = reader.u32()? as usize;
+            let value = reader.take(length)?;
+            match id {
+                0 => break,
+                2 => {
+                    cipher = Some(match value {
+                        v if v == CIPHER_AES256 => Cipher::Aes256,
+                        v if v == CIPHER_CHACHA20 => Cipher::ChaCha20,
+                        _ => return Err(unsupported("database cipher other than AES-256 or ChaCha20")),
+                    })
+                }
+                3 => compressed = ByteReader { data: value, pos: 0 }.u32()? == 1,
+                4 => master_seed = Some(value.to_vec()),
+                7 => iv = Some(value.to_vec()),
+                11 => kdf = Some(read_kdf(value)?),
+                _ => {}
+            }
+        }
+        let missing = |field: &str| invalid(&format!("missing {} in KeePass header", field));
+        Ok(Header {
+            cipher: cipher.ok_or_else(|| missing("cipher"))?,
+            compressed,
+            master_seed: master_seed.filter(|seed| seed.len() == 32).ok_or_else(|| missing("master seed"))?,
+            iv: iv.ok_or_else(|| missing("IV"))?,
+            kdf: kdf.ok_or_else(|| missing("KDF parameters"))?,
+        })
+    }
+}
+
+/// Reads the KDF parameters, stored as a KeePass "variant dictionary".
+fn read_kdf(data: &[u8]) -> Result<Kdf> {
+    let mut reader = ByteReader { data, pos: 0 };
+    if reader.u16()? >> 8 != 1 {
+        return Err(unsupported("KDF parameters version"));
+    }
+    let mut entries = std::collections::HashMap::new();
+    loop {
+        let kind = reader.u8()?;
+        if kind == 0 {
+            break;
+        }
+        let name_length = reader.u32()? as usize;
+        let name = String::from_utf8_lossy(reader.take(name_length)?).into_owned();
+        let length = reader.u32()? as usize;
+        entries.insert(name, reader.take(length)?);
+    }
+
+    let bytes = |name: &str| entries.get(name).map(|v| v.to_vec()).ok_or_else(|| invalid("incomplete KDF parameters"));
+    let number = |name: &str| -> Result<u64> {
+        let value = entries.get(name).ok_or_else(|| invalid("incomplete KDF parameters"))?;
+        let mut padded = [0u8; 8];
+        padded[..value.len().min(8)].copy_from_slice(&value[..value.len().min(8)]);
+        Ok(u64::from_le_bytes(padded))
+    };
+    let uuid = bytes("$UUID")?;
+    let argon2 = |algorithm| -> Result<Kdf> {
+        Ok(Kdf::Argon2 {
+            algorithm,
+            version: if number("V")? == 0x10 { Version::V0x10 } else { Version::V0x13 },
+            salt: bytes("S")?,
+            memory: number("M")?,
+            iterations: number("I")?,
+            parallelism: number("P")? as u32,
+        })
+    };
+    match uuid.as_slice() {
+        v if v == KDF_AES => Ok(Kdf::Aes { seed: bytes("S")?, rounds: number("R")? }),
+        v if v == KDF_ARGON2D => argon2(Algorithm::Argon2d),
+        v if v == KDF_ARGON2ID => argon2(Algorithm::Argon2id),
+        _ => Err(unsupported("unknown key derivation function")),
+    }
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(length).filter(|&end| end <= self.data.len());
+        let end = end.ok_or_else(|| invalid("truncated KeePass database"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap_or_default()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(out: &mut Vec<u8>, id: u8, value: &[u8]) {
+        out.push(id);
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(value);
+    }
+
+    /// A KDBX 4 file as KeePassXC writes it: ChaCha20, cheap AES-KDF, one
+    /// protected password.
+    fn database(password: &str, xml_password: &str) -> Vec<u8> {
+        let (seed, kdf_seed, iv, stream_key) = ([1u8; 32], [2u8; 32], [3u8; 12], [4u8; 64]);
+        let mut kdf = vec![0x00, 0x01];
+        for (kind, name, value) in [
+            (0x42u8, "$UUID", KDF_AES.to_vec()),
+            (0x05, "R", 10u64.to_le_bytes().to_vec()),
+            (0x42, "S", kdf_seed.to_vec()),
+        ] {
+            kdf.push(kind);
+            kdf.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            kdf.extend_from_slice(name.as_bytes());
+            kdf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            kdf.extend_from_slice(&value);
+        }
+        kdf.push(0);
+
+        let mut header = SIGNATURE.to_vec();
+        header.extend_from_slice(&0x0004_0000u32.to_le_bytes());
+        field(&mut header, 2, &CIPHER_CHACHA20);
+        field(&mut header, 3, &0u32.to_le_bytes());
+        field(&mut header, 4, &seed);
+        field(&mut header, 7, &iv);
+        field(&mut header, 11, &kdf);
+        field(&mut header, 0, b"\r\n\r\n");
+
+        let inner_key = Sha512::digest(stream_key);
+        let mut masked = xml_password.as_bytes().to_vec();
+        ChaCha20::new_from_slices(&inner_key[..32], &inner_key[32..44]).unwrap().apply_keystream(&mut masked);
+        let xml = format!(
+            "<KeePassFile><Root><Group><Name>Root</Name><Entry><String><Key>Password</Key>\
+             <Value Protected=\"True\">{}</Value></String></Entry></Group></Root></KeePassFile>",
+            BASE64.encode(masked)
+        );
+        let mut payload = Vec::new();
+        field(&mut payload, 1, &INNER_STREAM_CHACHA20.to_le_bytes());
+        field(&mut payload, 2, &stream_key);
+        field(&mut payload, 0, &[]);
+        payload.extend_from_slice(xml.as_bytes());
+
+        let composite = Sha256::digest(Sha256::digest(password.as_bytes()));
+        let kdf = Kdf::Aes { seed: kdf_seed.to_vec(), rounds: 10 };
+        let mut seeded = seed.to_vec();
+        seeded.extend_from_slice(&kdf.transform(&composite).unwrap());
+        let key = Sha256::digest(&seeded);
+        seeded.push(1);
+        let hmac_key = Sha512::digest(&seeded);
+        ChaCha20::new(&key, &iv.into()).apply_keystream(&mut payload);
+
+        let mut file = header.clone();
+        file.extend_from_slice(&Sha256::digest(&header));
+        file.extend_from_slice(&block_mac(&hmac_key, u64::MAX, &[&header]).finalize().into_bytes());
+        for (index, block) in [payload.as_slice(), &[]].into_iter().enumerate() {
+            let length = (block.len() as u32).to_le_bytes();
+            let mac_data: [&[u8]; 3] = [&(index as u64).to_le_bytes(), &length, block];
+            file.extend_from_slice(&block_mac(&hmac_key, index as u64, &mac_data).finalize().into_bytes());
+            file.extend_from_slice(&length);
+            file.extend_from_slice(block);
+        }
+        file
+    }
+
+    #[test]
+    fn test_open_reveals_protected_values() {
+        let data = database("master", "hunter2");
+        assert!(is_kdbx(&data));
+        let (xml, mut protected) = open(&data, "master").unwrap();
+        let masked = xml.split("\">").nth(1).unwrap().split('<').next().unwrap();
+        assert_eq!(protected.reveal(masked).unwrap(), "hunter2");
+
+        assert!(matches!(open(&data, "wrong"), Err(LockboxError::PassphraseIncorrect)));
+        let mut corrupted = data.clone();
+        let last = corrupted.len() - 40;
+        corrupted[last] ^= 1;
+        assert!(matches!(open(&corrupted, "master"), Err(LockboxError::InvalidImportFormat { .. })));
+    }
+
+    #[test]
+    fn test_kdf_refuses_costs_above_the_caps() {
+        let argon2 = |memory, iterations| Kdf::Argon2 {
+            algorithm: Algorithm::Argon2d,
+            version: Version::V0x13,
+            salt: vec![0; 32],
+            memory,
+            iterations,
+            parallelism: 2,
+        };
+        for kdf in [argon2(MAX_MEMORY_BYTES * 2, 2), argon2(1024 * 1024, MAX_ITERATIONS + 1)] {
+            assert!(matches!(kdf.transform(&[0; 32]), Err(LockboxError::UnsupportedForeignFile { .. })));
+        }
+        assert_eq!(argon2(1024 * 1024, 2).transform(&[0; 32]).unwrap().len(), 32);
+    }
+}
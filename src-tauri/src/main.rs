@@ -1,12 +1,28 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod audit;
+mod backup;
 mod db;
 mod crypto;
 mod commands;
+mod error;
+mod events;
+mod foreign;
+mod i18n;
+mod identity;
+mod kdbx;
+mod portable;
+mod retention;
+mod search;
+mod snapshot;
+mod stats;
+mod transfer;
+mod validation;
 
 use std::sync::Mutex;
 use db::Database;
+use i18n::Locale;
 use tauri::Manager;
 use tauri_plugin_dialog::DialogExt;
 
@@ -50,12 +66,18 @@ fn main() {
             // Initialize database here to handle errors gracefully
             match Database::new() {
                 Ok(db) => {
+                    let locale = Locale::resolve(&db);
+                    i18n::set_current(locale);
+
                     // Check if the database requires a newer version of the app
                     if let Ok(Some(min_version)) = db.get_setting("min_app_version") {
                         if !is_version_greater_or_equal(&app_version, &min_version) {
                             app.dialog()
-                                .message(&format!("Version obsolète.\n\nCette base de données a été modifiée par une version plus récente de Lockbox et nécessite au minimum la version {}.\nVous utilisez actuellement la version {}.\n\nVeuillez mettre à jour l'application pour accéder à vos données.", min_version, app_version))
-                                .title("Mise à jour requise")
+                                .message(i18n::tr(locale, "dialog.outdated.body", &[
+                                    ("required", min_version.clone()),
+                                    ("current", app_version.clone()),
+                                ]))
+                                .title(i18n::tr(locale, "dialog.outdated.title", &[]))
                                 .kind(tauri_plugin_dialog::MessageDialogKind::Error)
                                 .blocking_show();
                             
@@ -70,13 +92,27 @@ fn main() {
                         db: Mutex::new(db),
                         master_password_hash: Mutex::new(None),
                     });
+
+                    // Automatic backups, checked now and then periodically once the
+                    // vault is open; a failure is retried at the next check
+                    let handle = app.handle().clone();
+                    std::thread::spawn(move || loop {
+                        if let Ok(db) = handle.state::<AppState>().db.lock() {
+                            if db.is_vault_open() {
+                                let _ = snapshot::run(&db, chrono::Utc::now().timestamp_millis());
+                            }
+                        }
+                        std::thread::sleep(snapshot::CHECK_INTERVAL);
+                    });
                     Ok(())
                 }
                 Err(e) => {
-                    // Show a native error dialog if migration/initialization fails
+                    // Show a native error dialog if migration/initialization fails.
+                    // The database is unavailable here, so use the system locale.
+                    let locale = Locale::system();
                     app.dialog()
-                        .message(&format!("Mise à jour bloquée.\n\nVeuillez fermer toutes les instances de l'application Lockbox avant de continuer.\n\nErreur: {}", e))
-                        .title("Action requise")
+                        .message(i18n::tr(locale, "dialog.blocked.body", &[("detail", e.localized(locale))]))
+                        .title(i18n::tr(locale, "dialog.blocked.title", &[]))
                         .kind(tauri_plugin_dialog::MessageDialogKind::Error)
                         .blocking_show();
                     
@@ -87,7 +123,9 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_all_lockboxes,
+            commands::search_lockboxes,
             commands::get_lockbox,
+            commands::record_content_copy,
             commands::create_lockbox,
             commands::update_lockbox,
             commands::delete_lockbox,
@@ -96,15 +134,56 @@ fn main() {
             commands::extend_unlock_delay,
             commands::use_panic_code,
             commands::reset_panic_code,
-            commands::get_access_log,
-            commands::get_global_access_log,
+            commands::query_access_log,
+            commands::verify_access_log,
+            commands::get_statistics,
             commands::relock_lockbox,
             commands::export_lockboxes,
+            commands::export_portable,
+            commands::export_clear,
+            commands::preview_import,
             commands::import_lockboxes,
+            commands::export_backup,
+            commands::import_backup,
+            commands::get_backup_policy,
+            commands::set_backup_policy,
+            commands::list_backups,
+            commands::run_backup_now,
+            commands::restore_backup,
+            commands::get_device_identity,
+            commands::reset_device_key,
+            commands::list_trusted_devices,
+            commands::trust_device,
+            commands::remove_trusted_device,
             commands::set_master_password,
             commands::verify_master_password,
             commands::is_master_password_set,
             commands::check_and_update_lockboxes,
+            commands::get_validation_limits,
+            commands::set_validation_limits,
+            commands::list_tags,
+            commands::rename_tag,
+            commands::merge_tags,
+            commands::delete_tag,
+            commands::list_categories,
+            commands::create_category,
+            commands::update_category,
+            commands::reorder_categories,
+            commands::delete_category,
+            commands::list_folders,
+            commands::create_folder,
+            commands::rename_folder,
+            commands::move_folder,
+            commands::delete_folder,
+            commands::move_lockboxes,
+            commands::relock_folder,
+            commands::extend_folder_delays,
+            commands::get_retention_policy,
+            commands::set_retention_policy,
+            commands::compact_access_log,
+            commands::get_locale,
+            commands::set_locale,
+            commands::localize_notification,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
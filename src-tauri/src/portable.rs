@@ -0,0 +1,160 @@
+//! Portable exports: the whole export file encrypted under a passphrase of
+//! its own, with Argon2id, so that a new vault imports it knowing only that
+//! passphrase. Inside, contents are in clear; the envelope is the only
+//! encryption, and authenticates the file as a whole.
+
+use crate::crypto::{self, KEY_LENGTH};
+use crate::error::{LockboxError, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Value of `format`, telling portable files apart from plain exports.
+pub const PORTABLE_FORMAT: &str = "lockbox-portable";
+
+/// Version of the envelope written by this build.
+pub const PORTABLE_VERSION: i64 = 1;
+
+/// Shortest passphrase accepted for a new portable export.
+pub const MIN_PASSPHRASE_LENGTH: usize = 10;
+
+const SALT_LENGTH: usize = 16;
+
+/// Upper bounds on the cost read from a file, so that a crafted file cannot
+/// exhaust memory or hang the import.
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ITERATIONS: u32 = 32;
+const MAX_PARALLELISM: u32 = 16;
+
+/// Argon2id cost and salt, stored in clear so the file is self-contained.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// Base64-encoded.
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// OWASP's first Argon2id recommendation: 64 MiB, 3 passes.
+    pub fn new() -> Self {
+        Self::with_cost(64 * 1024, 3, 1)
+    }
+
+    /// Fresh salt with the given cost.
+    pub fn with_cost(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        KdfParams {
+            algorithm: "argon2id".to_string(),
+            memory_kib,
+            iterations,
+            parallelism,
+            salt: BASE64.encode(salt),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<[u8; KEY_LENGTH]> {
+        let invalid = |detail: &str| LockboxError::InvalidImportFormat { detail: detail.to_string() };
+        if self.algorithm != "argon2id"
+            || self.memory_kib > MAX_MEMORY_KIB
+            || self.iterations > MAX_ITERATIONS
+            || self.parallelism > MAX_PARALLELISM
+        {
+            return Err(invalid("unsupported key derivation parameters"));
+        }
+        let salt = BASE64.decode(&self.salt).map_err(|_| invalid("invalid salt"))?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(KEY_LENGTH))
+            .map_err(|e| invalid(&e.to_string()))?;
+        let mut key = [0u8; KEY_LENGTH];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| invalid(&e.to_string()))?;
+        Ok(key)
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableFile {
+    pub format: String,
+    pub version: i64,
+    pub kdf: KdfParams,
+    /// The export file, AES-256-GCM encrypted under the derived key.
+    pub payload: String,
+}
+
+/// True when `data` is a portable file rather than a plain export.
+pub fn is_portable(data: &str) -> bool {
+    serde_json::from_str::<Value>(data)
+        .is_ok_and(|value| value.get("format").and_then(Value::as_str) == Some(PORTABLE_FORMAT))
+}
+
+/// Refuses a passphrase shorter than `MIN_PASSPHRASE_LENGTH`.
+pub fn check_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(LockboxError::PassphraseTooShort { min_length: MIN_PASSPHRASE_LENGTH });
+    }
+    Ok(())
+}
+
+/// Encrypts an export file under `passphrase`.
+pub fn seal(export: &str, passphrase: &str, kdf: KdfParams) -> Result<PortableFile> {
+    check_passphrase(passphrase)?;
+    let payload = crypto::encrypt_with_key(export, &kdf.derive_key(passphrase)?)?;
+    Ok(PortableFile { format: PORTABLE_FORMAT.to_string(), version: PORTABLE_VERSION, kdf, payload })
+}
+
+/// Decrypts a portable file back to the export file it carries.
+pub fn open(data: &str, passphrase: &str) -> Result<String> {
+    let file: PortableFile = serde_json::from_str(data)
+        .map_err(|e| LockboxError::InvalidImportFormat { detail: e.to_string() })?;
+    if file.format != PORTABLE_FORMAT || file.version != PORTABLE_VERSION {
+        return Err(LockboxError::InvalidImportFormat {
+            detail: format!("unsupported portable file version {}", file.version),
+        });
+    }
+    crypto::decrypt_with_key(&file.payload, &file.kdf.derive_key(passphrase)?)
+        .map_err(|_| LockboxError::PassphraseIncorrect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cheap_kdf() -> KdfParams {
+        KdfParams::with_cost(64, 1, 1)
+    }
+
+    #[test]
+    fn test_open_needs_the_passphrase() {
+        let file = seal("{\"lockboxes\":[]}", "correct horse", cheap_kdf()).unwrap();
+        let data = serde_json::to_string(&file).unwrap();
+        assert!(is_portable(&data));
+        assert!(!is_portable("{\"version\":\"4.0.0\"}"));
+        assert_eq!(open(&data, "correct horse").unwrap(), "{\"lockboxes\":[]}");
+        assert!(matches!(open(&data, "wrong horse"), Err(LockboxError::PassphraseIncorrect)));
+        assert!(matches!(
+            seal("{}", "short", cheap_kdf()),
+            Err(LockboxError::PassphraseTooShort { min_length: MIN_PASSPHRASE_LENGTH })
+        ));
+    }
+
+    #[test]
+    fn test_crafted_cost_is_refused() {
+        let mut file = seal("{}", "correct horse", cheap_kdf()).unwrap();
+        file.kdf.memory_kib = MAX_MEMORY_KIB * 4;
+        let data = serde_json::to_string(&file).unwrap();
+        assert!(matches!(open(&data, "correct horse"), Err(LockboxError::InvalidImportFormat { .. })));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::db::{AccessLogEntry, Database};
+use crate::error::{LockboxError, Result};
+use crate::validation::{FieldError, FieldErrorKind};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Settings key holding the JSON-encoded `RetentionPolicy`.
+pub const RETENTION_SETTING_KEY: &str = "access_log_retention";
+
+/// Raw events are always kept at least this long, so that the current month
+/// and recent streaks are computed from exact rows.
+pub const MIN_RETENTION_DAYS: i64 = 90;
+
+/// Width of the `hour_buckets` kept in monthly aggregates.
+pub const BUCKET_MS: i64 = 3600 * 1000;
+const DAY_MS: i64 = 24 * BUCKET_MS;
+
+/// Version of the JSON written, encrypted, to access log archive files.
+pub const ARCHIVE_VERSION: i64 = 1;
+
+/// How long raw access log rows are kept before being rolled up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Days of raw events to keep; `None` keeps everything.
+    pub retention_days: Option<i64>,
+    /// Write compacted rows to an encrypted archive file before removing them.
+    pub archive: bool,
+}
+
+impl RetentionPolicy {
+    /// Policy stored in settings, keeping everything by default.
+    pub fn load(db: &Database) -> Result<Self> {
+        match db.get_setting(RETENTION_SETTING_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+            None => Ok(RetentionPolicy::default()),
+        }
+    }
+
+    pub fn save(&self, db: &Database) -> Result<()> {
+        if matches!(self.retention_days, Some(days) if days < MIN_RETENTION_DAYS) {
+            return Err(LockboxError::Validation {
+                errors: vec![FieldError {
+                    field: "retention_days".to_string(),
+                    kind: FieldErrorKind::TooSmall,
+                    limit: Some(MIN_RETENTION_DAYS),
+                }],
+            });
+        }
+        db.set_setting(RETENTION_SETTING_KEY, &serde_json::to_string(self)?)
+    }
+
+    /// Rows logged before this timestamp are due for compaction.
+    pub fn cutoff(&self, now_ms: i64) -> Option<i64> {
+        self.retention_days
+            .map(|days| now_ms - days.max(MIN_RETENTION_DAYS) * DAY_MS)
+    }
+}
+
+/// Monthly roll-up of one event type for one lockbox.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyAggregate {
+    pub lockbox_id: i64,
+    /// UTC month, "YYYY-MM".
+    pub month: String,
+    pub event_type: String,
+    pub count: i64,
+    /// Event counts keyed by hours since the Unix epoch, so that days, hours
+    /// and weekdays can still be derived in any timezone.
+    pub hour_buckets: BTreeMap<i64, i64>,
+    /// Sum and number of known waits before a cancellation.
+    pub wait_seconds_total: i64,
+    pub wait_count: i64,
+}
+
+impl MonthlyAggregate {
+    pub fn merge(&mut self, other: &MonthlyAggregate) {
+        self.count += other.count;
+        for (hour, count) in &other.hour_buckets {
+            *self.hour_buckets.entry(*hour).or_default() += count;
+        }
+        self.wait_seconds_total += other.wait_seconds_total;
+        self.wait_count += other.wait_count;
+    }
+}
+
+/// Adds `aggregate` to the matching roll-up in `month`, or appends it.
+pub fn merge_into(month: &mut Vec<MonthlyAggregate>, aggregate: &MonthlyAggregate) {
+    match month.iter_mut().find(|a| {
+        a.lockbox_id == aggregate.lockbox_id && a.event_type == aggregate.event_type
+    }) {
+        Some(existing) => existing.merge(aggregate),
+        None => month.push(aggregate.clone()),
+    }
+}
+
+/// Rolls `entries` (oldest first) up per lockbox, UTC month and event type.
+pub fn aggregate(entries: &[AccessLogEntry]) -> Vec<MonthlyAggregate> {
+    let mut aggregates: BTreeMap<(i64, String, String), MonthlyAggregate> = BTreeMap::new();
+    let mut pending_requests: HashMap<i64, i64> = HashMap::new();
+
+    for entry in entries {
+        let month = DateTime::from_timestamp_millis(entry.timestamp)
+            .unwrap_or_default()
+            .format("%Y-%m")
+            .to_string();
+        let key = (entry.lockbox_id, month.clone(), entry.event_type.clone());
+        let agg = aggregates.entry(key).or_insert_with(|| MonthlyAggregate {
+            lockbox_id: entry.lockbox_id,
+            month,
+            event_type: entry.event_type.clone(),
+            ..Default::default()
+        });
+
+        agg.count += 1;
+        *agg.hour_buckets.entry(entry.timestamp.div_euclid(BUCKET_MS)).or_default() += 1;
+
+        if let Some(waited) = cancelled_wait(entry, &mut pending_requests) {
+            agg.wait_seconds_total += waited;
+            agg.wait_count += 1;
+        }
+    }
+
+    aggregates.into_values().collect()
+}
+
+/// Seconds waited before `entry` cancelled an unlock request, None for other
+/// events. `pending_requests` holds the request time per lockbox, used for
+/// rows logged without details; fed in log order, it is kept up to date here.
+pub fn cancelled_wait(entry: &AccessLogEntry, pending_requests: &mut HashMap<i64, i64>) -> Option<i64> {
+    match entry.event_type.as_str() {
+        "unlock_requested" => {
+            pending_requests.insert(entry.lockbox_id, entry.timestamp);
+            None
+        }
+        "unlock_cancelled" => {
+            let requested_at = pending_requests.remove(&entry.lockbox_id);
+            entry
+                .details
+                .as_ref()
+                .and_then(|d| d.get("waited_seconds"))
+                .and_then(|w| w.as_i64())
+                .or_else(|| requested_at.map(|ts| (entry.timestamp - ts) / 1000))
+        }
+        _ => None,
+    }
+}
+
+/// Raw row as written to an archive file, with its chain hashes so the
+/// archive can be checked against the chain it was cut from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEntry {
+    #[serde(flatten)]
+    pub entry: AccessLogEntry,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+}
+
+/// Plaintext of an archive file, encrypted under the vault key on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessLogArchive {
+    pub version: i64,
+    pub created_at: i64,
+    pub cutoff: i64,
+    pub entries: Vec<ArchivedEntry>,
+}
+
+/// Outcome of a compaction run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CompactionReport {
+    pub cutoff: Option<i64>,
+    pub removed_entries: i64,
+    pub aggregates_updated: i64,
+    /// Path of the encrypted archive written for the removed rows.
+    pub archive_file: Option<String>,
+}
+
+/// Applies the stored retention policy.
+pub fn run(db: &Database, now_ms: i64) -> Result<CompactionReport> {
+    let policy = RetentionPolicy::load(db)?;
+    match policy.cutoff(now_ms) {
+        Some(cutoff) => db.compact_access_log(cutoff, policy.archive),
+        None => Ok(CompactionReport::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(lockbox_id: i64, event_type: &str, timestamp: i64) -> AccessLogEntry {
+        AccessLogEntry {
+            id: 0,
+            lockbox_id,
+            event_type: event_type.to_string(),
+            timestamp,
+            details: None,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_groups_by_box_month_and_type() {
+        // 2024-01-31T23:00Z and 2024-02-01T01:00Z fall in different UTC months.
+        let jan = 1_706_742_000_000;
+        let feb = jan + 2 * BUCKET_MS;
+        let entries = [
+            entry(1, "unlock_requested", jan),
+            entry(1, "unlock_cancelled", jan + 90_000),
+            entry(1, "unlock_requested", feb),
+            entry(2, "unlock_requested", feb),
+        ];
+        let aggregates = aggregate(&entries);
+
+        assert_eq!(aggregates.len(), 4);
+        let cancelled = aggregates.iter().find(|a| a.event_type == "unlock_cancelled").unwrap();
+        assert_eq!(cancelled.month, "2024-01");
+        assert_eq!((cancelled.wait_seconds_total, cancelled.wait_count), (90, 1));
+        let feb_requests = aggregates
+            .iter()
+            .find(|a| a.lockbox_id == 1 && a.month == "2024-02")
+            .unwrap();
+        assert_eq!(feb_requests.hour_buckets.get(&(feb / BUCKET_MS)), Some(&1));
+    }
+
+    #[test]
+    fn test_cancelled_wait_prefers_the_logged_seconds() {
+        let mut pending = HashMap::new();
+        assert_eq!(cancelled_wait(&entry(1, "unlock_requested", 1_000), &mut pending), None);
+        let cancelled = AccessLogEntry {
+            details: Some(serde_json::json!({ "waited_seconds": 42 })),
+            ..entry(1, "unlock_cancelled", 100_000)
+        };
+        assert_eq!(cancelled_wait(&cancelled, &mut pending), Some(42));
+        assert!(pending.is_empty());
+        assert_eq!(cancelled_wait(&entry(1, "unlock_cancelled", 200_000), &mut pending), None);
+    }
+
+    #[test]
+    fn test_cutoff_never_goes_below_minimum_retention() {
+        let policy = RetentionPolicy { retention_days: Some(1), archive: false };
+        assert_eq!(policy.cutoff(100 * DAY_MS), Some((100 - MIN_RETENTION_DAYS) * DAY_MS));
+        assert_eq!(RetentionPolicy::default().cutoff(100 * DAY_MS), None);
+    }
+}
@@ -0,0 +1,43 @@
+/// Most results a search returns when the caller gives no limit.
+pub const DEFAULT_SEARCH_LIMIT: u32 = 50;
+
+/// Words of a query beyond this many are ignored.
+const MAX_QUERY_TERMS: usize = 16;
+
+/// bm25 weights of the `lockbox_search` columns: name, category, tags and
+/// reflection text. A hit in the name counts most.
+pub const COLUMN_WEIGHTS: [f64; 4] = [10.0, 2.0, 5.0, 1.0];
+
+/// Turns free text into an FTS5 match expression where every word must
+/// match, as a prefix, in one of the indexed columns. Words are split on
+/// anything that is not a letter or digit, like the `unicode61` tokenizer,
+/// so user input can never carry FTS5 operators. `None` when no word is left.
+pub fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_QUERY_TERMS)
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+    if terms.is_empty() { None } else { Some(terms.join(" AND ")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_expression_prefixes_every_word() {
+        assert_eq!(match_expression("bank pin"), Some("\"bank\"* AND \"pin\"*".to_string()));
+        assert_eq!(match_expression("  Épargne "), Some("\"Épargne\"*".to_string()));
+    }
+
+    #[test]
+    fn test_match_expression_drops_operators() {
+        assert_eq!(
+            match_expression("name:\"x\" OR -y*"),
+            Some("\"name\"* AND \"x\"* AND \"OR\"* AND \"y\"*".to_string())
+        );
+        assert_eq!(match_expression(" *\"() "), None);
+    }
+}
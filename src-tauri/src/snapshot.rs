@@ -0,0 +1,306 @@
+//! Automatic backups: snapshots of the database file taken by the backend
+//! with the SQLite online backup API, encrypted under a key derived from the
+//! vault secret, and rotated by a daily/weekly/monthly retention policy.
+//!
+//! Snapshots restore into this installation only, since they are keyed to
+//! its vault secret; the signed full backup (`backup`) moves a vault between
+//! machines.
+
+use crate::crypto::{self, KEY_LENGTH};
+use crate::db::Database;
+use crate::error::{LockboxError, Result};
+use crate::validation::{FieldError, FieldErrorKind};
+use chrono::{DateTime, Datelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Settings key holding the JSON-encoded `SnapshotPolicy`.
+pub const SNAPSHOT_POLICY_SETTING_KEY: &str = "snapshot_policy";
+
+/// Purpose of the vault subkey encrypting snapshots.
+pub const SNAPSHOT_KEY_PURPOSE: &str = "snapshot";
+
+/// Extension of snapshot files; other files in the directory are ignored.
+pub const SNAPSHOT_EXTENSION: &str = "lbsnap";
+
+/// How often the backend checks whether a snapshot is due.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// File header: magic and creation time, authenticated with the payload.
+const MAGIC: &[u8; 8] = b"LBSNAP01";
+const HEADER_LENGTH: usize = 16;
+
+/// When snapshots are taken and which are kept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotPolicy {
+    pub enabled: bool,
+    /// Directory the snapshots are written to; `None` for the default one
+    /// next to the database.
+    pub directory: Option<String>,
+    /// Hours between two snapshots.
+    pub interval_hours: i64,
+    /// Newest snapshot of each of this many days, weeks and months is kept.
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+    pub keep_monthly: u32,
+}
+
+impl Default for SnapshotPolicy {
+    fn default() -> Self {
+        SnapshotPolicy {
+            enabled: true,
+            directory: None,
+            interval_hours: 24,
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_monthly: 12,
+        }
+    }
+}
+
+impl SnapshotPolicy {
+    /// Policy stored in settings, daily snapshots by default.
+    pub fn load(db: &Database) -> Result<Self> {
+        match db.get_setting(SNAPSHOT_POLICY_SETTING_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+            None => Ok(SnapshotPolicy::default()),
+        }
+    }
+
+    pub fn save(&self, db: &Database) -> Result<()> {
+        if self.interval_hours < 1 {
+            return Err(LockboxError::Validation {
+                errors: vec![FieldError {
+                    field: "interval_hours".to_string(),
+                    kind: FieldErrorKind::TooSmall,
+                    limit: Some(1),
+                }],
+            });
+        }
+        db.set_setting(SNAPSHOT_POLICY_SETTING_KEY, &serde_json::to_string(self)?)
+    }
+
+    pub fn directory(&self) -> PathBuf {
+        match self.directory.as_deref().map(str::trim).filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => default_directory(),
+        }
+    }
+
+    /// True when the newest snapshot, taken at `last`, is old enough.
+    pub fn is_due(&self, last: Option<i64>, now_ms: i64) -> bool {
+        self.enabled && last.is_none_or(|last| now_ms - last >= self.interval_hours * 3600 * 1000)
+    }
+}
+
+pub fn default_directory() -> PathBuf {
+    if let Some(data_dir) = dirs::data_local_dir() {
+        data_dir.join("com.lockbox.local").join("backups")
+    } else {
+        PathBuf::from("lockbox_backups")
+    }
+}
+
+/// A snapshot file, as listed from its header without decrypting it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotInfo {
+    pub file_name: String,
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+/// File name of a snapshot taken at `created_at`, sorting by date.
+pub fn file_name(created_at: i64) -> String {
+    let at = DateTime::from_timestamp_millis(created_at).unwrap_or_default();
+    format!("lockbox-{}.{}", at.format("%Y%m%d-%H%M%S%.3f"), SNAPSHOT_EXTENSION)
+}
+
+fn header(created_at: i64) -> [u8; HEADER_LENGTH] {
+    let mut header = [0u8; HEADER_LENGTH];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()..].copy_from_slice(&created_at.to_be_bytes());
+    header
+}
+
+/// Creation time recorded in a snapshot header, if `data` starts with one.
+fn parse_header(data: &[u8]) -> Option<i64> {
+    let header = data.get(..HEADER_LENGTH)?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return None;
+    }
+    Some(i64::from_be_bytes(header[MAGIC.len()..].try_into().ok()?))
+}
+
+/// Encrypts a database file taken at `created_at` into a snapshot.
+pub fn seal(database: &[u8], created_at: i64, key: &[u8; KEY_LENGTH]) -> Result<Vec<u8>> {
+    let header = header(created_at);
+    let mut data = header.to_vec();
+    data.extend(crypto::encrypt_bytes_with_key(database, &header, key)?);
+    Ok(data)
+}
+
+/// Decrypts a snapshot back to its creation time and database file,
+/// refusing any file that was not sealed under `key` or was altered since,
+/// header included.
+pub fn open(data: &[u8], name: &str, key: &[u8; KEY_LENGTH]) -> Result<(i64, Vec<u8>)> {
+    let invalid = || LockboxError::SnapshotInvalid { name: name.to_string() };
+    let created_at = parse_header(data).ok_or_else(invalid)?;
+    let (header, payload) = data.split_at(HEADER_LENGTH);
+    let database = crypto::decrypt_bytes_with_key(payload, header, key).map_err(|_| invalid())?;
+    Ok((created_at, database))
+}
+
+fn failed(e: std::io::Error) -> LockboxError {
+    LockboxError::SnapshotFailed { detail: e.to_string() }
+}
+
+/// Snapshots in `dir`, newest first. A missing directory has none.
+pub fn list(dir: &Path) -> Result<Vec<SnapshotInfo>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(failed(e)),
+    };
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION))
+        .filter_map(|entry| {
+            let mut header = [0u8; HEADER_LENGTH];
+            std::fs::File::open(entry.path()).ok()?.read_exact(&mut header).ok()?;
+            Some(SnapshotInfo {
+                file_name: entry.file_name().to_string_lossy().into_owned(),
+                created_at: parse_header(&header)?,
+                size_bytes: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
+
+/// Reads the snapshot `name` from `dir`. Only plain snapshot file names are
+/// accepted, so a restore cannot be pointed outside the directory.
+pub fn read(dir: &Path, name: &str) -> Result<Vec<u8>> {
+    let path = Path::new(name);
+    let plain = path.file_name().is_some_and(|file| file == name)
+        && path.extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION);
+    if !plain {
+        return Err(LockboxError::SnapshotInvalid { name: name.to_string() });
+    }
+    std::fs::read(dir.join(name)).map_err(failed)
+}
+
+/// Snapshots the retention policy lets go, from a newest-first list: all but
+/// the newest one of each of the last `keep_daily` days, `keep_weekly` ISO
+/// weeks and `keep_monthly` months (UTC). The newest snapshot is always kept.
+pub fn expired<'a>(snapshots: &'a [SnapshotInfo], policy: &SnapshotPolicy) -> Vec<&'a SnapshotInfo> {
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut months = HashSet::new();
+    snapshots
+        .iter()
+        .enumerate()
+        .filter(|(index, snapshot)| {
+            let at = DateTime::from_timestamp_millis(snapshot.created_at).unwrap_or_default();
+            let week = at.iso_week();
+            let mut keep = *index == 0;
+            if days.len() < policy.keep_daily as usize && days.insert(at.date_naive()) {
+                keep = true;
+            }
+            if weeks.len() < policy.keep_weekly as usize && weeks.insert((week.year(), week.week())) {
+                keep = true;
+            }
+            if months.len() < policy.keep_monthly as usize && months.insert((at.year(), at.month())) {
+                keep = true;
+            }
+            !keep
+        })
+        .map(|(_, snapshot)| snapshot)
+        .collect()
+}
+
+/// Takes a snapshot at `now_ms` into the policy directory, then removes the
+/// snapshots the policy no longer keeps.
+pub fn take(db: &Database, policy: &SnapshotPolicy, now_ms: i64) -> Result<SnapshotInfo> {
+    let dir = policy.directory();
+    std::fs::create_dir_all(&dir).map_err(failed)?;
+    let name = file_name(now_ms);
+    db.write_snapshot(&dir.join(&name), now_ms)?;
+
+    let snapshots = list(&dir)?;
+    for snapshot in expired(&snapshots, policy) {
+        let _ = std::fs::remove_file(dir.join(&snapshot.file_name));
+    }
+    snapshots
+        .into_iter()
+        .find(|snapshot| snapshot.file_name == name)
+        .ok_or_else(|| LockboxError::SnapshotFailed { detail: name })
+}
+
+/// Takes a snapshot if the policy says one is due.
+pub fn run(db: &Database, now_ms: i64) -> Result<Option<SnapshotInfo>> {
+    let policy = SnapshotPolicy::load(db)?;
+    let last = list(&policy.directory())?.first().map(|snapshot| snapshot.created_at);
+    if !policy.is_due(last, now_ms) {
+        return Ok(None);
+    }
+    take(db, &policy, now_ms).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 3600 * 1000;
+
+    fn snapshot(created_at: i64) -> SnapshotInfo {
+        SnapshotInfo { file_name: file_name(created_at), created_at, size_bytes: 0 }
+    }
+
+    #[test]
+    fn test_open_rejects_other_keys_and_edited_headers() {
+        let key = [3u8; KEY_LENGTH];
+        let data = seal(b"SQLite format 3", 1_000, &key).unwrap();
+        assert_eq!(parse_header(&data), Some(1_000));
+        assert_eq!(open(&data, "a", &key).unwrap(), (1_000, b"SQLite format 3".to_vec()));
+        assert!(matches!(open(&data, "a", &[4u8; KEY_LENGTH]), Err(LockboxError::SnapshotInvalid { .. })));
+
+        // Passing an old snapshot off as a newer one
+        let mut redated = data.clone();
+        redated[..HEADER_LENGTH].copy_from_slice(&header(2_000));
+        assert!(matches!(open(&redated, "a", &key), Err(LockboxError::SnapshotInvalid { .. })));
+        assert!(matches!(open(b"not a snapshot", "a", &key), Err(LockboxError::SnapshotInvalid { .. })));
+    }
+
+    #[test]
+    fn test_expired_keeps_newest_per_day_week_and_month() {
+        // 2024-03-31T12:00Z (a Sunday), then one snapshot every 12 hours back 60 days
+        let newest = 1_711_886_400_000;
+        let snapshots: Vec<SnapshotInfo> = (0..120).map(|i| snapshot(newest - i * DAY_MS / 2)).collect();
+        let policy = SnapshotPolicy { keep_daily: 3, keep_weekly: 2, keep_monthly: 2, ..Default::default() };
+        let gone: HashSet<i64> = expired(&snapshots, &policy).iter().map(|s| s.created_at).collect();
+        let kept: Vec<i64> = snapshots.iter().map(|s| s.created_at).filter(|at| !gone.contains(at)).collect();
+
+        // Three days, the previous week's Sunday, and the newest of February
+        assert_eq!(
+            kept,
+            vec![newest, newest - DAY_MS, newest - 2 * DAY_MS, newest - 7 * DAY_MS, newest - 31 * DAY_MS]
+        );
+
+        let none = SnapshotPolicy { keep_daily: 0, keep_weekly: 0, keep_monthly: 0, ..Default::default() };
+        assert_eq!(expired(&snapshots, &none).len(), snapshots.len() - 1);
+    }
+
+    #[test]
+    fn test_is_due() {
+        let policy = SnapshotPolicy::default();
+        assert!(policy.is_due(None, 0));
+        assert!(!policy.is_due(Some(0), 23 * 3600 * 1000));
+        assert!(policy.is_due(Some(0), 24 * 3600 * 1000));
+        assert!(!SnapshotPolicy { enabled: false, ..policy }.is_due(None, 0));
+    }
+}
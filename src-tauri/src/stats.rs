@@ -0,0 +1,300 @@
+use crate::db::{AccessLogEntry, Database};
+use crate::error::{LockboxError, Result};
+use crate::retention::{self, MonthlyAggregate};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Events that give access to a lockbox's content and so end a streak.
+const ACCESS_EVENTS: [&str; 3] = ["unlock_completed", "scheduled_unlock_completed", "panic_used"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EventCounts {
+    pub requests: i64,
+    pub completions: i64,
+    pub cancellations: i64,
+    pub panic_uses: i64,
+    pub extensions: i64,
+}
+
+impl EventCounts {
+    fn record(&mut self, event_type: &str, count: i64) {
+        match event_type {
+            "unlock_requested" => self.requests += count,
+            "unlock_completed" | "scheduled_unlock_completed" => self.completions += count,
+            "unlock_cancelled" => self.cancellations += count,
+            "panic_used" => self.panic_uses += count,
+            "extend_delay" => self.extensions += count,
+            _ => {}
+        }
+    }
+}
+
+/// Self-control metrics over a set of access log events. Days, hours and
+/// weekdays are taken in the timezone the statistics were requested for.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metrics {
+    pub all_time: EventCounts,
+    pub this_month: EventCounts,
+    /// Cancellations per unlock request, `None` without requests.
+    pub cancellation_ratio: Option<f64>,
+    /// Whole days since the last access, `None` if never accessed.
+    pub current_streak_days: Option<i64>,
+    /// Longest run of whole days without access, current run included.
+    pub longest_streak_days: i64,
+    pub last_access_at: Option<i64>,
+    /// Unlock requests per local hour, 0 to 23.
+    pub requests_by_hour: [i64; 24],
+    /// Unlock requests per local weekday, Monday first.
+    pub requests_by_weekday: [i64; 7],
+    /// Average wait between an unlock request and its cancellation.
+    pub average_seconds_to_cancel: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LockboxStats {
+    pub lockbox_id: i64,
+    #[serde(flatten)]
+    pub metrics: Metrics,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsReport {
+    pub timezone: String,
+    pub generated_at: i64,
+    pub global: Metrics,
+    pub lockboxes: Vec<LockboxStats>,
+}
+
+/// Builds the report for every lockbox and overall, as seen from `tz`.
+/// Compacted history is read from the monthly aggregates, to the hour.
+pub fn compute(db: &Database, tz: Tz, now_ms: i64) -> Result<StatsReport> {
+    let now = local_time(tz, now_ms);
+    let month_start = tz
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .earliest()
+        .map_or(now_ms, |start| start.timestamp_millis());
+
+    let mut global = Accumulator::default();
+    let mut per_lockbox: BTreeMap<i64, Accumulator> = BTreeMap::new();
+
+    // Aggregates only hold rows older than every remaining raw row.
+    let aggregates = db.get_access_log_aggregates()?;
+    for (bucket, lockbox_id, event_type, count) in hourly_buckets(&aggregates) {
+        let timestamp = bucket * retention::BUCKET_MS;
+        let local = local_time(tz, timestamp);
+        global.record(event_type, timestamp, &local, month_start, count);
+        per_lockbox
+            .entry(lockbox_id)
+            .or_default()
+            .record(event_type, timestamp, &local, month_start, count);
+    }
+    for aggregate in &aggregates {
+        global.add_waits(aggregate);
+        per_lockbox.entry(aggregate.lockbox_id).or_default().add_waits(aggregate);
+    }
+
+    db.for_each_access_log_entry(|entry| {
+        let local = local_time(tz, entry.timestamp);
+        global.push(&entry, &local, month_start);
+        per_lockbox
+            .entry(entry.lockbox_id)
+            .or_default()
+            .push(&entry, &local, month_start);
+    })?;
+
+    let today = now.date_naive();
+    Ok(StatsReport {
+        timezone: tz.name().to_string(),
+        generated_at: now_ms,
+        global: global.finish(today),
+        lockboxes: per_lockbox
+            .into_iter()
+            .map(|(lockbox_id, acc)| LockboxStats { lockbox_id, metrics: acc.finish(today) })
+            .collect(),
+    })
+}
+
+/// Parses an IANA timezone name such as "Europe/Paris".
+pub fn parse_timezone(name: &str) -> Result<Tz> {
+    name.parse::<Tz>()
+        .map_err(|_| LockboxError::InvalidTimezone { timezone: name.to_string() })
+}
+
+/// Aggregated counts as `(bucket, lockbox_id, event_type, count)`, oldest first.
+fn hourly_buckets(aggregates: &[MonthlyAggregate]) -> Vec<(i64, i64, &str, i64)> {
+    let mut buckets: Vec<_> = aggregates
+        .iter()
+        .flat_map(|a| {
+            a.hour_buckets
+                .iter()
+                .map(move |(bucket, count)| (*bucket, a.lockbox_id, a.event_type.as_str(), *count))
+        })
+        .collect();
+    buckets.sort();
+    buckets
+}
+
+fn local_time(tz: Tz, timestamp_ms: i64) -> DateTime<Tz> {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .with_timezone(&tz)
+}
+
+/// Running totals, fed events in chronological order.
+#[derive(Default)]
+struct Accumulator {
+    all_time: EventCounts,
+    this_month: EventCounts,
+    requests_by_hour: [i64; 24],
+    requests_by_weekday: [i64; 7],
+    first_day: Option<NaiveDate>,
+    last_access_day: Option<NaiveDate>,
+    last_access_at: Option<i64>,
+    longest_gap_days: i64,
+    /// Pending unlock request per lockbox, for rows logged without details.
+    pending_requests: HashMap<i64, i64>,
+    cancel_wait_seconds: i64,
+    cancel_count: i64,
+}
+
+impl Accumulator {
+    fn push(&mut self, entry: &AccessLogEntry, local: &DateTime<Tz>, month_start: i64) {
+        self.record(&entry.event_type, entry.timestamp, local, month_start, 1);
+
+        if let Some(waited) = retention::cancelled_wait(entry, &mut self.pending_requests) {
+            self.cancel_wait_seconds += waited;
+            self.cancel_count += 1;
+        }
+
+        if ACCESS_EVENTS.contains(&entry.event_type.as_str()) {
+            self.pending_requests.remove(&entry.lockbox_id);
+        }
+    }
+
+    /// Counts `count` events of one type at `timestamp`.
+    fn record(&mut self, event_type: &str, timestamp: i64, local: &DateTime<Tz>, month_start: i64, count: i64) {
+        let day = local.date_naive();
+        self.first_day.get_or_insert(day);
+
+        self.all_time.record(event_type, count);
+        if timestamp >= month_start {
+            self.this_month.record(event_type, count);
+        }
+
+        if event_type == "unlock_requested" {
+            self.requests_by_hour[local.hour() as usize] += count;
+            self.requests_by_weekday[local.weekday().num_days_from_monday() as usize] += count;
+        }
+
+        if ACCESS_EVENTS.contains(&event_type) {
+            let gap = match self.last_access_day {
+                Some(previous) => (day - previous).num_days() - 1,
+                None => self.first_day.map_or(0, |first| (day - first).num_days()),
+            };
+            self.longest_gap_days = self.longest_gap_days.max(gap);
+            self.last_access_day = Some(day);
+            self.last_access_at = Some(timestamp);
+        }
+    }
+
+    fn add_waits(&mut self, aggregate: &MonthlyAggregate) {
+        self.cancel_wait_seconds += aggregate.wait_seconds_total;
+        self.cancel_count += aggregate.wait_count;
+    }
+
+    fn finish(self, today: NaiveDate) -> Metrics {
+        let current_streak_days = self.last_access_day.map(|day| (today - day).num_days().max(0));
+        let open_run = current_streak_days
+            .or_else(|| self.first_day.map(|first| (today - first).num_days().max(0)))
+            .unwrap_or(0);
+
+        Metrics {
+            cancellation_ratio: (self.all_time.requests > 0)
+                .then(|| self.all_time.cancellations as f64 / self.all_time.requests as f64),
+            current_streak_days,
+            longest_streak_days: self.longest_gap_days.max(open_run),
+            last_access_at: self.last_access_at,
+            requests_by_hour: self.requests_by_hour,
+            requests_by_weekday: self.requests_by_weekday,
+            average_seconds_to_cancel: (self.cancel_count > 0)
+                .then(|| self.cancel_wait_seconds as f64 / self.cancel_count as f64),
+            all_time: self.all_time,
+            this_month: self.this_month,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 3600 * 1000;
+
+    fn entry(lockbox_id: i64, event_type: &str, timestamp: i64) -> AccessLogEntry {
+        AccessLogEntry {
+            id: 0,
+            lockbox_id,
+            event_type: event_type.to_string(),
+            timestamp,
+            details: None,
+        }
+    }
+
+    fn run(tz: Tz, entries: &[AccessLogEntry], now_ms: i64) -> Metrics {
+        let mut acc = Accumulator::default();
+        for e in entries {
+            acc.push(e, &local_time(tz, e.timestamp), 0);
+        }
+        acc.finish(local_time(tz, now_ms).date_naive())
+    }
+
+    #[test]
+    fn test_streaks_count_whole_days_without_access() {
+        // 2024-01-01T12:00Z, accesses on day 0 and day 5, now is day 7.
+        let start = 1_704_110_400_000;
+        let entries = [
+            entry(1, "unlock_completed", start),
+            entry(1, "panic_used", start + 5 * DAY_MS),
+        ];
+        let metrics = run(Tz::UTC, &entries, start + 7 * DAY_MS);
+
+        assert_eq!(metrics.current_streak_days, Some(2));
+        assert_eq!(metrics.longest_streak_days, 4);
+        assert_eq!(metrics.all_time.completions, 1);
+        assert_eq!(metrics.all_time.panic_uses, 1);
+    }
+
+    #[test]
+    fn test_heatmaps_follow_the_requested_timezone() {
+        // 2024-01-01T23:30Z is Tuesday 08:30 in Tokyo.
+        let entries = [entry(1, "unlock_requested", 1_704_151_800_000)];
+
+        let utc = run(Tz::UTC, &entries, 1_704_151_800_000);
+        assert_eq!(utc.requests_by_hour[23], 1);
+        assert_eq!(utc.requests_by_weekday[0], 1);
+
+        let tokyo = run(chrono_tz::Asia::Tokyo, &entries, 1_704_151_800_000);
+        assert_eq!(tokyo.requests_by_hour[8], 1);
+        assert_eq!(tokyo.requests_by_weekday[1], 1);
+    }
+
+    #[test]
+    fn test_cancellation_ratio_and_time_to_cancel() {
+        let mut cancelled = entry(1, "unlock_cancelled", 100_000);
+        cancelled.details = Some(serde_json::json!({ "v": 1, "waited_seconds": 30 }));
+        let entries = [
+            entry(1, "unlock_requested", 0),
+            cancelled,
+            // Legacy cancellation without details: paired with its request.
+            entry(2, "unlock_requested", 200_000),
+            entry(2, "unlock_cancelled", 290_000),
+        ];
+        let metrics = run(Tz::UTC, &entries, 300_000);
+
+        assert_eq!(metrics.cancellation_ratio, Some(1.0));
+        assert_eq!(metrics.average_seconds_to_cancel, Some(60.0));
+        assert_eq!(metrics.current_streak_days, None);
+    }
+}
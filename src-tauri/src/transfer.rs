@@ -0,0 +1,1014 @@
+use crate::crypto;
+use crate::db::{CategoryPolicies, Lockbox, LockboxFilter, UpdateLockboxRequest};
+use crate::foreign::ForeignFormat;
+use crate::error::{LockboxError, Result};
+use crate::identity::{self, DeviceKey};
+use crate::validation::{self, FieldError, FieldErrorKind, LockboxInput, ValidationLimits};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Version of the export layout written by this build.
+pub const EXPORT_VERSION: &str = "4.0.0";
+
+/// First major version whose files all carry a manifest.
+const MANIFEST_MAJOR_VERSION: u32 = 3;
+
+/// Settings key of the export counter, bumped by every export.
+pub const EXPORT_COUNTER_SETTING_KEY: &str = "export_counter";
+
+/// Settings key of the random id telling this vault's exports apart.
+pub const VAULT_ID_SETTING_KEY: &str = "vault_id";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportData {
+    /// Human-readable header in the exporter's language; ignored on import.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+    pub version: String,
+    pub exported_at: i64,
+    pub lockboxes: Vec<ExportLockbox>,
+    /// Added in v3; older files have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<ExportManifest>,
+    /// Set by `parse_export` once the manifest was verified against a
+    /// trusted device key; never read from the file.
+    #[serde(skip)]
+    pub device_verified: bool,
+    /// Set when the file was read from another password manager's format.
+    #[serde(skip)]
+    pub source: Option<ForeignFormat>,
+}
+
+/// Signature over the whole file, so that no field can be edited and no
+/// lockbox dropped, and its place in the exporting vault's history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub vault_id: String,
+    /// Value of the export counter for this file.
+    pub counter: i64,
+    pub lockbox_count: usize,
+    /// Filter the lockboxes were chosen with, for a partial export. The
+    /// file then says nothing about the lockboxes it leaves out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<LockboxFilter>,
+    /// Public key of the exporting device (v4). Without it the manifest is
+    /// signed with HMAC-SHA256 under the master password hash (v3).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_key: Option<String>,
+    /// Signature of the file without this field, see `manifest_data`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportLockbox {
+    pub name: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub unlock_delay_seconds: i64,
+    pub relock_delay_seconds: i64,
+    // Advanced fields (added in v2)
+    #[serde(default)]
+    pub reflection_enabled: bool,
+    #[serde(default)]
+    pub reflection_message: Option<String>,
+    #[serde(default)]
+    pub reflection_checklist: Option<String>,
+    #[serde(default)]
+    pub penalty_enabled: bool,
+    #[serde(default)]
+    pub penalty_seconds: i64,
+    #[serde(default)]
+    pub tags: Option<String>,
+    #[serde(default)]
+    pub max_views_per_unlock: Option<i64>,
+    /// HMAC-SHA256 signature of security-critical fields, in files from
+    /// before v4; the manifest signature covers them since.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl ExportLockbox {
+    fn sign_data(&self) -> String {
+        lockbox_sign_data(
+            &self.name,
+            &self.content,
+            self.unlock_delay_seconds,
+            self.relock_delay_seconds,
+            self.penalty_enabled,
+            self.penalty_seconds,
+        )
+    }
+}
+
+impl From<Lockbox> for ExportLockbox {
+    fn from(lb: Lockbox) -> Self {
+        ExportLockbox {
+            name: lb.name,
+            content: lb.content,
+            category: lb.category,
+            unlock_delay_seconds: lb.unlock_delay_seconds,
+            relock_delay_seconds: lb.relock_delay_seconds,
+            reflection_enabled: lb.reflection_enabled,
+            reflection_message: lb.reflection_message,
+            reflection_checklist: lb.reflection_checklist,
+            penalty_enabled: lb.penalty_enabled,
+            penalty_seconds: lb.penalty_seconds,
+            tags: lb.tags,
+            max_views_per_unlock: lb.max_views_per_unlock,
+            signature: None,
+        }
+    }
+}
+
+/// Builds the canonical string that is HMAC-signed for a lockbox.
+/// Only security-critical fields are included.
+fn lockbox_sign_data(
+    name: &str,
+    content: &str,
+    unlock_delay_seconds: i64,
+    relock_delay_seconds: i64,
+    penalty_enabled: bool,
+    penalty_seconds: i64,
+) -> String {
+    format!(
+        "{name}|{content}|{unlock}|{relock}|{penalty}|{penalty_sec}",
+        name = name,
+        content = content,
+        unlock = unlock_delay_seconds,
+        relock = relock_delay_seconds,
+        penalty = if penalty_enabled { "1" } else { "0" },
+        penalty_sec = penalty_seconds,
+    )
+}
+
+/// Adds the manifest to `data`, signed by this device. `selection` is the
+/// filter of a partial export; an empty one means every lockbox.
+pub fn seal(
+    data: &mut ExportData,
+    selection: &LockboxFilter,
+    vault_id: String,
+    counter: i64,
+    device: &DeviceKey,
+) -> Result<()> {
+    data.manifest = Some(ExportManifest {
+        vault_id,
+        counter,
+        lockbox_count: data.lockboxes.len(),
+        selection: Some(selection.clone()).filter(|selection| !selection.is_empty()),
+        device_key: Some(device.public_key()),
+        signature: None,
+    });
+    let signature = device.sign(&manifest_data(serde_json::to_value(&*data)?));
+    if let Some(manifest) = &mut data.manifest {
+        manifest.signature = Some(signature);
+    }
+    Ok(())
+}
+
+/// What the manifest of an import file is checked against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManifestKeys<'a> {
+    /// Public keys of this device and of the trusted devices.
+    pub trusted: &'a [String],
+    /// Master password hash the file was exported under, for v3 manifests.
+    pub hmac: Option<&'a str>,
+    /// Accept a device that is not trusted, leaving the file unverified.
+    /// Portable files are vouched for by their passphrase instead.
+    pub allow_untrusted: bool,
+}
+
+/// Parses an export file and checks its manifest: any edit, or a lockbox
+/// count that does not match, rejects the whole file. A device-signed file
+/// must also come from a trusted device, unless `keys.allow_untrusted`; a
+/// v3 manifest needs `keys.hmac`. A file without a manifest is only taken
+/// from before v3, with every lockbox signed under `keys.hmac`.
+pub fn parse_export(data: &str, keys: ManifestKeys) -> Result<ExportData> {
+    let invalid = |detail: String| LockboxError::InvalidImportFormat { detail };
+    let value: Value = serde_json::from_str(data).map_err(|e| invalid(e.to_string()))?;
+    let mut export: ExportData = serde_json::from_value(value.clone()).map_err(|e| invalid(e.to_string()))?;
+    let Some(manifest) = &export.manifest else {
+        let major: u32 = export.version.split('.').next().and_then(|major| major.parse().ok()).unwrap_or(0);
+        let signed = keys.hmac.is_some_and(|key| {
+            export.lockboxes.iter().all(|lb| {
+                lb.signature.as_deref().is_some_and(|signature| crypto::hmac_verify(&lb.sign_data(), key, signature))
+            })
+        });
+        // A later file without one had it stripped, and its lockboxes carry
+        // no signature of their own.
+        if major >= MANIFEST_MAJOR_VERSION || !signed {
+            return Err(LockboxError::ManifestInvalid);
+        }
+        return Ok(export);
+    };
+
+    let signature = manifest.signature.as_deref().unwrap_or_default();
+    let authentic = match (&manifest.device_key, keys.hmac) {
+        (Some(device_key), _) => identity::verify(device_key, &manifest_data(value), signature),
+        (None, Some(key)) => crypto::hmac_verify(&manifest_data(value), key, signature),
+        (None, None) => false,
+    };
+    if !authentic || manifest.lockbox_count != export.lockboxes.len() {
+        return Err(LockboxError::ManifestInvalid);
+    }
+    if let Some(device_key) = &manifest.device_key {
+        let public_key = identity::parse_public_key(device_key)?;
+        export.device_verified = keys.trusted.contains(&public_key);
+        if !export.device_verified && !keys.allow_untrusted {
+            return Err(LockboxError::UntrustedDevice {
+                fingerprint: identity::fingerprint(&public_key),
+                public_key,
+            });
+        }
+    }
+    Ok(export)
+}
+
+/// Canonical text of an export without the manifest signature.
+fn manifest_data(mut value: Value) -> String {
+    if let Some(Value::Object(manifest)) = value.get_mut("manifest") {
+        manifest.remove("signature");
+    }
+    crypto::canonical_json(&value)
+}
+
+/// Delay raises an import file from this vault must not predate: lockbox
+/// name to the export counter when its delay was last raised.
+#[derive(Debug, Clone, Default)]
+pub struct RollbackGuard {
+    /// Export counter of the file; 0 for files without a manifest.
+    pub counter: i64,
+    pub raised: HashMap<String, i64>,
+}
+
+impl RollbackGuard {
+    /// Guard for `data`, given this vault's id and delay raises. Only a file
+    /// signed by a trusted device of another vault escapes it. Any other file
+    /// is held to every raise: a file without a manifest, one signed under a
+    /// password (v3) or by a device not trusted, whose vault id and counter
+    /// anyone could have written, counts as older than all of them.
+    pub fn for_file(data: &ExportData, vault_id: &str, raised: HashMap<String, i64>) -> Option<Self> {
+        let counter = match &data.manifest {
+            Some(manifest) if data.device_verified && manifest.vault_id != vault_id => return None,
+            Some(manifest) if data.device_verified => manifest.counter,
+            _ => 0,
+        };
+        Some(RollbackGuard { counter, raised })
+    }
+
+    /// True when the file was exported before the delays of `name` were raised.
+    pub fn predates_raise(&self, name: &str) -> bool {
+        self.raised.get(name).is_some_and(|raised| self.counter <= *raised)
+    }
+}
+
+/// What to do with an imported lockbox whose name is already taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Leave the existing lockbox alone.
+    #[default]
+    Skip,
+    /// Import under a free name, e.g. "Bank (2)".
+    Rename,
+    /// Overwrite the existing lockbox, only when no policy gets looser.
+    Replace,
+    /// Keep the existing lockbox and content, with the stricter value of
+    /// each policy; tags are combined.
+    Merge,
+}
+
+/// Conflict strategies of an import: one for every conflict, with
+/// per-lockbox overrides keyed by position in the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ImportStrategies {
+    pub default: ConflictStrategy,
+    pub overrides: HashMap<usize, ConflictStrategy>,
+}
+
+impl ImportStrategies {
+    fn for_index(&self, index: usize) -> ConflictStrategy {
+        self.overrides.get(&index).copied().unwrap_or(self.default)
+    }
+}
+
+/// What importing one lockbox of a file does, or would do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// No lockbox has this name.
+    New,
+    /// A lockbox with the same name exists, or the name was taken earlier in
+    /// the file, by a lockbox or a renamed copy.
+    Conflict,
+    /// The signature does not match: the file was altered after export, or
+    /// signed under another master password.
+    BadSignature,
+    /// The content cannot be decrypted with the source password.
+    WrongPassword,
+    /// Fields outside the validation limits.
+    Invalid,
+    /// Exported before the lockbox's delays were last raised, or from a
+    /// file that cannot prove otherwise; importing it would roll them back.
+    Outdated,
+}
+
+/// Write an import performs for one lockbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Create,
+    /// Create under `renamed_to`.
+    Rename,
+    Replace,
+    Merge,
+    Skip,
+}
+
+/// Outcome for one lockbox of an import file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportItem {
+    /// Position in the file.
+    pub index: usize,
+    pub name: String,
+    pub category: Option<String>,
+    pub unlock_delay_seconds: i64,
+    pub status: ImportStatus,
+    /// Set when a trusted device signed the file. The password signatures of
+    /// older exports only show the file was not edited without the password.
+    pub signed: bool,
+    /// Existing lockbox with the same name, for `conflict`.
+    pub existing_id: Option<i64>,
+    /// Rejected fields, for `invalid`.
+    pub errors: Vec<FieldError>,
+    /// Strategy applied to a `conflict`.
+    pub strategy: Option<ConflictStrategy>,
+    pub action: ImportAction,
+    pub renamed_to: Option<String>,
+    /// Fields a replace would loosen, or `content` while the existing
+    /// lockbox is locked; the replace is refused if any.
+    pub looser_fields: Vec<String>,
+    /// Set once `action` was carried out.
+    pub imported: bool,
+}
+
+/// Per-lockbox report of `preview_import` and `import_lockboxes`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub version: String,
+    pub exported_at: i64,
+    /// The file holds a selection of lockboxes, not a whole vault.
+    pub partial: bool,
+    /// Format of another password manager the file was read from; its
+    /// entries carry no signature.
+    pub source: Option<ForeignFormat>,
+    pub items: Vec<ImportItem>,
+}
+
+/// Password hashes an import works with.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportKeys<'a> {
+    /// Hash of the password the file was exported under, when it differs
+    /// from the current one.
+    pub source: Option<&'a str>,
+    /// Hash of the current master password.
+    pub current: Option<&'a str>,
+    /// Contents are in clear, inside a portable file already opened with
+    /// its passphrase or a file from another password manager; they are
+    /// encrypted under the current password.
+    pub portable: bool,
+}
+
+impl ImportKeys<'_> {
+    /// Signatures and content of the file are under the source password if
+    /// given, else under the current one.
+    fn file_key(&self) -> Option<&str> {
+        self.source.or(self.current)
+    }
+}
+
+/// The vault an import is checked against.
+#[derive(Debug, Clone, Copy)]
+pub struct ImportTarget<'a> {
+    pub lockboxes: &'a [Lockbox],
+    /// Policies of the existing categories by name, for their delay floors.
+    pub categories: &'a HashMap<String, CategoryPolicies>,
+    pub limits: &'a ValidationLimits,
+}
+
+/// Checks every lockbox of `data` without writing anything: signature,
+/// decryption, field limits, then name conflicts against the lockboxes of
+/// `target` and earlier lockboxes of the file, resolved by `strategies`.
+/// With a `rollback` guard, lockboxes whose delays were raised since the
+/// file was exported are refused. A lockbox that would end up below its
+/// category's delay floor is `invalid`. Returns the report and, for each
+/// lockbox to create or replace, its content ready to store under the
+/// current password.
+pub fn check_import(
+    data: &ExportData,
+    keys: ImportKeys,
+    target: ImportTarget,
+    strategies: &ImportStrategies,
+    rollback: Option<&RollbackGuard>,
+    now_ms: i64,
+) -> Result<(ImportReport, Vec<Option<String>>)> {
+    let existing: HashMap<&str, &Lockbox> = target.lockboxes.iter().map(|lb| (lb.name.as_str(), lb)).collect();
+    // Names taken once the import is done: existing, earlier in the file or
+    // given to a renamed copy
+    let mut taken: HashSet<String> = existing.keys().map(|name| name.to_string()).collect();
+    let mut items = Vec::with_capacity(data.lockboxes.len());
+    let mut contents = Vec::with_capacity(data.lockboxes.len());
+
+    for (index, lb) in data.lockboxes.iter().enumerate() {
+        let current = existing.get(lb.name.as_str()).copied();
+        let mut item = ImportItem {
+            index,
+            name: lb.name.clone(),
+            category: lb.category.clone(),
+            unlock_delay_seconds: lb.unlock_delay_seconds,
+            status: ImportStatus::New,
+            signed: data.device_verified,
+            existing_id: current.map(|lb| lb.id),
+            errors: Vec::new(),
+            strategy: None,
+            action: ImportAction::Skip,
+            renamed_to: None,
+            looser_fields: Vec::new(),
+            imported: false,
+        };
+        let (status, content) = check_lockbox(lb, keys, target.limits, now_ms, &mut item.errors)?;
+        let conflict = taken.contains(&lb.name);
+        let outdated = rollback.is_some_and(|guard| guard.predates_raise(&lb.name));
+        item.status = match status {
+            ImportStatus::New if outdated => ImportStatus::Outdated,
+            ImportStatus::New if conflict => ImportStatus::Conflict,
+            status => status,
+        };
+
+        item.action = match item.status {
+            ImportStatus::New => ImportAction::Create,
+            ImportStatus::Conflict => {
+                let strategy = strategies.for_index(index);
+                item.strategy = Some(strategy);
+                match (strategy, current) {
+                    (ConflictStrategy::Rename, _) => {
+                        let name = free_name(&lb.name, &taken);
+                        taken.insert(name.clone());
+                        item.renamed_to = Some(name);
+                        ImportAction::Rename
+                    }
+                    (ConflictStrategy::Replace, Some(current)) => {
+                        item.looser_fields = looser_fields(lb, current);
+                        if item.looser_fields.is_empty() { ImportAction::Replace } else { ImportAction::Skip }
+                    }
+                    (ConflictStrategy::Merge, Some(_)) => ImportAction::Merge,
+                    // Replace and merge only apply to lockboxes already stored.
+                    _ => ImportAction::Skip,
+                }
+            }
+            _ => ImportAction::Skip,
+        };
+        if let Some(floor) = below_floor(lb, current, item.action, target.categories) {
+            item.status = ImportStatus::Invalid;
+            item.action = ImportAction::Skip;
+            item.errors.push(floor);
+        }
+        if item.status == ImportStatus::New {
+            taken.insert(lb.name.clone());
+        }
+        let writes_content = matches!(item.action, ImportAction::Create | ImportAction::Rename | ImportAction::Replace);
+        contents.push(content.filter(|_| writes_content));
+        items.push(item);
+    }
+
+    Ok((
+        ImportReport {
+            version: data.version.clone(),
+            exported_at: data.exported_at,
+            partial: data.manifest.as_ref().is_some_and(|manifest| manifest.selection.is_some()),
+            source: data.source,
+            items,
+        },
+        contents,
+    ))
+}
+
+/// Error for a lockbox that `action` would leave below the unlock delay
+/// floor of its category, which create and update refuse.
+fn below_floor(
+    lb: &ExportLockbox,
+    current: Option<&Lockbox>,
+    action: ImportAction,
+    categories: &HashMap<String, CategoryPolicies>,
+) -> Option<FieldError> {
+    let (category, unlock_delay_seconds) = match (action, current) {
+        (ImportAction::Create | ImportAction::Rename | ImportAction::Replace, _) => {
+            (lb.category.as_deref(), lb.unlock_delay_seconds)
+        }
+        (ImportAction::Merge, Some(current)) => (
+            current.category.as_deref().or(lb.category.as_deref()),
+            current.unlock_delay_seconds.max(lb.unlock_delay_seconds),
+        ),
+        _ => return None,
+    };
+    let minimum = categories.get(category?)?.min_unlock_delay_seconds?;
+    (unlock_delay_seconds < minimum).then(|| FieldError {
+        field: "unlock_delay_seconds".to_string(),
+        kind: FieldErrorKind::TooSmall,
+        limit: Some(minimum),
+    })
+}
+
+/// First "name (n)" not in `taken`, counting from 2.
+fn free_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Fields of `current` that `imported` would loosen if it replaced it. The
+/// content of a locked lockbox cannot be edited, so it cannot be replaced.
+fn looser_fields(imported: &ExportLockbox, current: &Lockbox) -> Vec<String> {
+    let current_checklist = string_items(current.reflection_checklist.as_deref());
+    let imported_checklist = string_items(imported.reflection_checklist.as_deref());
+    let imported_penalty = if imported.penalty_enabled { imported.penalty_seconds } else { 0 };
+    let current_penalty = if current.penalty_enabled { current.penalty_seconds } else { 0 };
+
+    [
+        ("content", current.is_locked || current.unlock_timestamp.is_some()),
+        ("unlock_delay_seconds", imported.unlock_delay_seconds < current.unlock_delay_seconds),
+        ("relock_delay_seconds", imported.relock_delay_seconds < current.relock_delay_seconds),
+        ("penalty_enabled", current.penalty_enabled && !imported.penalty_enabled),
+        ("penalty_seconds", imported_penalty < current_penalty),
+        ("reflection_enabled", current.reflection_enabled && !imported.reflection_enabled),
+        (
+            "reflection_checklist",
+            !current_checklist.iter().all(|item| imported_checklist.contains(item)),
+        ),
+        (
+            "max_views_per_unlock",
+            current.max_views_per_unlock.is_some_and(|current| {
+                imported.max_views_per_unlock.is_none_or(|imported| imported > current)
+            }),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, looser)| *looser)
+    .map(|(field, _)| field.to_string())
+    .collect()
+}
+
+/// Update overwriting `current` with every field of `imported`.
+pub fn replacement(imported: ExportLockbox, current: &Lockbox, content: String) -> UpdateLockboxRequest {
+    UpdateLockboxRequest {
+        id: current.id,
+        name: Some(imported.name),
+        content: Some(content),
+        clear_category: imported.category.is_none(),
+        category: imported.category,
+        unlock_delay_seconds: Some(imported.unlock_delay_seconds),
+        relock_delay_seconds: Some(imported.relock_delay_seconds),
+        reflection_enabled: Some(imported.reflection_enabled),
+        clear_reflection_message: imported.reflection_message.is_none(),
+        reflection_message: imported.reflection_message,
+        clear_reflection_checklist: imported.reflection_checklist.is_none(),
+        reflection_checklist: imported.reflection_checklist,
+        penalty_enabled: Some(imported.penalty_enabled),
+        penalty_seconds: Some(imported.penalty_seconds),
+        clear_tags: imported.tags.is_none(),
+        tags: imported.tags,
+        clear_max_views_per_unlock: imported.max_views_per_unlock.is_none(),
+        max_views_per_unlock: imported.max_views_per_unlock,
+        ..Default::default()
+    }
+}
+
+/// Update keeping `current` and its content, with the stricter value of each
+/// policy of the two and their tags and checklist items combined.
+pub fn merge(imported: &ExportLockbox, current: &Lockbox) -> UpdateLockboxRequest {
+    let penalty = |enabled: bool, seconds: i64| if enabled { seconds } else { 0 };
+    let penalty_enabled = current.penalty_enabled || imported.penalty_enabled;
+    let checklist = union(
+        string_items(current.reflection_checklist.as_deref()),
+        string_items(imported.reflection_checklist.as_deref()),
+    );
+    let tags = union(
+        string_items(current.tags.as_deref()),
+        string_items(imported.tags.as_deref()),
+    );
+    let max_views_per_unlock = match (current.max_views_per_unlock, imported.max_views_per_unlock) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    UpdateLockboxRequest {
+        id: current.id,
+        category: current.category.clone().or_else(|| imported.category.clone()),
+        unlock_delay_seconds: Some(current.unlock_delay_seconds.max(imported.unlock_delay_seconds)),
+        relock_delay_seconds: Some(current.relock_delay_seconds.max(imported.relock_delay_seconds)),
+        reflection_enabled: Some(current.reflection_enabled || imported.reflection_enabled),
+        reflection_message: current.reflection_message.clone().or_else(|| imported.reflection_message.clone()),
+        reflection_checklist: (!checklist.is_empty()).then(|| json_array(&checklist)),
+        penalty_enabled: Some(penalty_enabled),
+        penalty_seconds: Some(
+            penalty(current.penalty_enabled, current.penalty_seconds)
+                .max(penalty(imported.penalty_enabled, imported.penalty_seconds)),
+        ),
+        tags: (!tags.is_empty()).then(|| json_array(&tags)),
+        max_views_per_unlock,
+        ..Default::default()
+    }
+}
+
+/// Items of a JSON string array (checklist or tags); anything else is empty.
+fn string_items(json: Option<&str>) -> Vec<String> {
+    json.and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default()
+}
+
+fn union(mut a: Vec<String>, b: Vec<String>) -> Vec<String> {
+    for item in b {
+        if !a.contains(&item) {
+            a.push(item);
+        }
+    }
+    a
+}
+
+fn json_array(items: &[String]) -> String {
+    serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Status of a lockbox on its own, with its content to store when valid.
+fn check_lockbox(
+    lb: &ExportLockbox,
+    keys: ImportKeys,
+    limits: &ValidationLimits,
+    now_ms: i64,
+    errors: &mut Vec<FieldError>,
+) -> Result<(ImportStatus, Option<String>)> {
+    let file_key = keys.file_key();
+    if let (Some(signature), Some(key)) = (&lb.signature, file_key) {
+        if !crypto::hmac_verify(&lb.sign_data(), key, signature) {
+            return Ok((ImportStatus::BadSignature, None));
+        }
+    }
+
+    // Without any password the content cannot be read; it is kept as is.
+    let plaintext = match file_key {
+        _ if keys.portable => Some(lb.content.clone()),
+        Some(key) => match crypto::decrypt(&lb.content, key) {
+            Ok(plaintext) => Some(plaintext),
+            Err(_) => return Ok((ImportStatus::WrongPassword, None)),
+        },
+        None => None,
+    };
+
+    let input = LockboxInput {
+        name: Some(&lb.name),
+        content_len: Some(match &plaintext {
+            Some(plaintext) => plaintext.len(),
+            None => validation::encrypted_plaintext_len(&lb.content),
+        }),
+        category: lb.category.as_deref(),
+        unlock_delay_seconds: Some(lb.unlock_delay_seconds),
+        relock_delay_seconds: Some(lb.relock_delay_seconds),
+        penalty_seconds: Some(lb.penalty_seconds),
+        reflection_message: lb.reflection_message.as_deref(),
+        reflection_checklist: lb.reflection_checklist.as_deref(),
+        tags: lb.tags.as_deref(),
+        scheduled_unlock_at: None,
+        max_views_per_unlock: lb.max_views_per_unlock,
+    };
+    match validation::validate(&input, limits, now_ms) {
+        Ok(()) => {}
+        Err(LockboxError::Validation { errors: rejected }) => {
+            *errors = rejected;
+            return Ok((ImportStatus::Invalid, None));
+        }
+        Err(other) => return Err(other),
+    }
+
+    // Content moves to the current password only when it differs, and
+    // clear content of a portable file always does.
+    let content = match (keys.source, keys.current, plaintext) {
+        (_, Some(current), Some(plaintext)) if keys.portable => crypto::encrypt(&plaintext, current)?,
+        (Some(source), Some(current), Some(plaintext)) if source != current => {
+            crypto::encrypt(&plaintext, current)?
+        }
+        (_, None, _) if keys.portable => return Err(LockboxError::MasterPasswordRequired),
+        _ => lb.content.clone(),
+    };
+    Ok((ImportStatus::New, Some(content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export_lockbox(name: &str, key: &str) -> ExportLockbox {
+        let mut lb = ExportLockbox {
+            name: name.to_string(),
+            content: crypto::encrypt("secret", key).unwrap(),
+            category: None,
+            unlock_delay_seconds: 3600,
+            relock_delay_seconds: 60,
+            reflection_enabled: false,
+            reflection_message: None,
+            reflection_checklist: None,
+            penalty_enabled: false,
+            penalty_seconds: 0,
+            tags: None,
+            max_views_per_unlock: None,
+            signature: None,
+        };
+        lb.signature = Some(crypto::hmac_sign(&lb.sign_data(), key));
+        lb
+    }
+
+    fn existing_lockbox(name: &str) -> Lockbox {
+        Lockbox {
+            id: 7,
+            name: name.to_string(),
+            unlock_delay_seconds: 3600,
+            relock_delay_seconds: 60,
+            is_locked: false,
+            ..Default::default()
+        }
+    }
+
+    fn export_data(lockboxes: Vec<ExportLockbox>) -> ExportData {
+        ExportData {
+            notice: None,
+            version: EXPORT_VERSION.to_string(),
+            exported_at: 0,
+            lockboxes,
+            manifest: None,
+            device_verified: false,
+            source: None,
+        }
+    }
+
+    fn check_with(
+        lockboxes: Vec<ExportLockbox>,
+        existing: &[Lockbox],
+        strategies: &ImportStrategies,
+    ) -> (ImportReport, Vec<Option<String>>) {
+        let data = export_data(lockboxes);
+        let keys = ImportKeys { source: None, current: Some("key"), ..Default::default() };
+        let target = ImportTarget { lockboxes: existing, categories: &HashMap::new(), limits: &ValidationLimits::default() };
+        check_import(&data, keys, target, strategies, None, 0).unwrap()
+    }
+
+    fn check(lockboxes: Vec<ExportLockbox>, keys: ImportKeys) -> (Vec<ImportStatus>, Vec<Option<String>>) {
+        let data = export_data(lockboxes);
+        let existing = [existing_lockbox("taken")];
+        let strategies = ImportStrategies::default();
+        let target = ImportTarget { lockboxes: &existing, categories: &HashMap::new(), limits: &ValidationLimits::default() };
+        let (report, contents) = check_import(&data, keys, target, &strategies, None, 0).unwrap();
+        (report.items.iter().map(|item| item.status).collect(), contents)
+    }
+
+    #[test]
+    fn test_check_import_reports_each_lockbox() {
+        let keys = ImportKeys { source: None, current: Some("key"), ..Default::default() };
+        let mut tampered = export_lockbox("tampered", "key");
+        tampered.unlock_delay_seconds = 1;
+        let mut invalid = export_lockbox("", "key");
+        invalid.signature = None;
+
+        let (statuses, contents) = check(
+            vec![
+                export_lockbox("fresh", "key"),
+                export_lockbox("taken", "key"),
+                export_lockbox("fresh", "key"),
+                tampered,
+                invalid,
+            ],
+            keys,
+        );
+        assert_eq!(
+            statuses,
+            vec![
+                ImportStatus::New,
+                ImportStatus::Conflict,
+                ImportStatus::Conflict,
+                ImportStatus::BadSignature,
+                ImportStatus::Invalid,
+            ]
+        );
+        assert!(contents[0].is_some());
+        assert!(contents[1..].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_check_import_reencrypts_under_current_password() {
+        let wrong = ImportKeys { source: Some("wrong"), current: Some("key"), ..Default::default() };
+        let (statuses, _) = check(vec![export_lockbox("a", "source")], wrong);
+        assert_eq!(statuses, vec![ImportStatus::BadSignature]);
+
+        let mut unsigned = export_lockbox("a", "source");
+        unsigned.signature = None;
+        let (statuses, _) = check(vec![unsigned], wrong);
+        assert_eq!(statuses, vec![ImportStatus::WrongPassword]);
+
+        let source = ImportKeys { source: Some("source"), current: Some("key"), ..Default::default() };
+        let (statuses, contents) = check(vec![export_lockbox("a", "source")], source);
+        assert_eq!(statuses, vec![ImportStatus::New]);
+        assert_eq!(crypto::decrypt(contents[0].as_ref().unwrap(), "key").unwrap(), "secret");
+
+        // Clear content of a portable file
+        let mut clear = export_lockbox("a", "key");
+        clear.content = "secret".to_string();
+        clear.signature = None;
+        let portable = ImportKeys { current: Some("key"), portable: true, ..Default::default() };
+        let (statuses, contents) = check(vec![clear], portable);
+        assert_eq!(statuses, vec![ImportStatus::New]);
+        assert_eq!(crypto::decrypt(contents[0].as_ref().unwrap(), "key").unwrap(), "secret");
+    }
+
+    #[test]
+    fn test_check_import_holds_lockboxes_to_category_floors() {
+        let mut short = export_lockbox("short", "key");
+        short.category = Some("Bank".to_string());
+        let mut merged = export_lockbox("bank", "key");
+        merged.category = Some("Bank".to_string());
+        let data = export_data(vec![short, merged]);
+        let keys = ImportKeys { current: Some("key"), ..Default::default() };
+        let categories = HashMap::from([(
+            "Bank".to_string(),
+            CategoryPolicies { min_unlock_delay_seconds: Some(7200), ..Default::default() },
+        )]);
+        let existing = [existing_lockbox("bank")];
+        let target = ImportTarget { lockboxes: &existing, categories: &categories, limits: &ValidationLimits::default() };
+        let merge = ImportStrategies { default: ConflictStrategy::Merge, ..Default::default() };
+
+        let (report, contents) = check_import(&data, keys, target, &merge, None, 0).unwrap();
+        for item in &report.items {
+            assert_eq!(item.status, ImportStatus::Invalid);
+            assert_eq!(item.action, ImportAction::Skip);
+            assert_eq!(item.errors[0].limit, Some(7200));
+        }
+        assert!(contents.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_conflict_strategies() {
+        let mut looser = export_lockbox("bank", "key");
+        looser.unlock_delay_seconds = 60;
+        looser.signature = Some(crypto::hmac_sign(&looser.sign_data(), "key"));
+        let mut current = existing_lockbox("bank");
+        current.penalty_enabled = true;
+        current.penalty_seconds = 600;
+        current.tags = Some(r#"["urgent"]"#.to_string());
+        let existing = [current, existing_lockbox("bank (2)")];
+
+        let rename = ImportStrategies { default: ConflictStrategy::Rename, ..Default::default() };
+        let (report, contents) = check_with(vec![export_lockbox("bank", "key")], &existing, &rename);
+        assert_eq!(report.items[0].action, ImportAction::Rename);
+        assert_eq!(report.items[0].renamed_to.as_deref(), Some("bank (3)"));
+        assert!(contents[0].is_some());
+
+        // Shorter unlock delay and no penalty: refused
+        let replace = ImportStrategies {
+            default: ConflictStrategy::Skip,
+            overrides: HashMap::from([(0, ConflictStrategy::Replace)]),
+        };
+        let (report, contents) = check_with(vec![looser], &existing, &replace);
+        assert_eq!(report.items[0].action, ImportAction::Skip);
+        assert_eq!(report.items[0].looser_fields, ["unlock_delay_seconds", "penalty_enabled", "penalty_seconds"]);
+        assert!(contents[0].is_none());
+
+        let mut imported = export_lockbox("bank", "key");
+        imported.unlock_delay_seconds = 60;
+        imported.relock_delay_seconds = 120;
+        imported.tags = Some(r#"["work"]"#.to_string());
+        let merged = merge(&imported, &existing[0]);
+        assert_eq!(merged.unlock_delay_seconds, Some(3600));
+        assert_eq!(merged.relock_delay_seconds, Some(120));
+        assert_eq!((merged.penalty_enabled, merged.penalty_seconds), (Some(true), Some(600)));
+        assert_eq!(merged.tags.as_deref(), Some(r#"["urgent","work"]"#));
+        assert!(merged.content.is_none());
+    }
+
+    #[test]
+    fn test_rename_treats_names_of_renamed_copies_as_taken() {
+        let existing = [existing_lockbox("bank")];
+        let rename = ImportStrategies { default: ConflictStrategy::Rename, ..Default::default() };
+        let file = vec![export_lockbox("bank", "key"), export_lockbox("bank (2)", "key")];
+        let (report, _) = check_with(file, &existing, &rename);
+        assert_eq!(report.items[0].renamed_to.as_deref(), Some("bank (2)"));
+        assert_eq!(report.items[1].status, ImportStatus::Conflict);
+        assert_eq!(report.items[1].action, ImportAction::Rename);
+        assert_eq!(report.items[1].renamed_to.as_deref(), Some("bank (2) (2)"));
+    }
+
+    #[test]
+    fn test_manifest_covers_the_whole_file() {
+        let device = DeviceKey::generate();
+        let trusted = [device.public_key()];
+        let keys = ManifestKeys { trusted: &trusted, ..Default::default() };
+        let mut data = export_data(vec![export_lockbox("bank", "key"), export_lockbox("mail", "key")]);
+        data.lockboxes[0].tags = Some(r#"["work"]"#.to_string());
+        seal(&mut data, &LockboxFilter::default(), "vault".to_string(), 4, &device).unwrap();
+        let json = serde_json::to_string(&data).unwrap();
+        assert_eq!(parse_export(&json, keys).unwrap().manifest.unwrap().counter, 4);
+
+        // Unsigned fields of a lockbox, and dropping one
+        let retagged = json.replace(r#"[\"work\"]"#, r#"[\"home\"]"#);
+        assert_ne!(retagged, json);
+        assert!(matches!(parse_export(&retagged, keys), Err(LockboxError::ManifestInvalid)));
+        let mut dropped: Value = serde_json::from_str(&json).unwrap();
+        dropped["lockboxes"].as_array_mut().unwrap().pop();
+        let dropped = dropped.to_string();
+        assert!(matches!(parse_export(&dropped, keys), Err(LockboxError::ManifestInvalid)));
+
+        // Stripping the manifest to edit a delay
+        let mut stripped: Value = serde_json::from_str(&json).unwrap();
+        stripped.as_object_mut().unwrap().remove("manifest");
+        stripped["lockboxes"][0]["unlock_delay_seconds"] = 0.into();
+        let stripped = stripped.to_string();
+        let with_hmac = ManifestKeys { hmac: Some("key"), ..keys };
+        assert!(matches!(parse_export(&stripped, with_hmac), Err(LockboxError::ManifestInvalid)));
+
+        // Files from before manifests stand on their lockbox signatures
+        let mut legacy = export_data(vec![export_lockbox("bank", "key")]);
+        legacy.version = "2.0.0".to_string();
+        let legacy_json = serde_json::to_string(&legacy).unwrap();
+        assert!(parse_export(&legacy_json, with_hmac).is_ok());
+        assert!(matches!(parse_export(&legacy_json, keys), Err(LockboxError::ManifestInvalid)));
+        legacy.lockboxes[0].unlock_delay_seconds = 0;
+        let edited = serde_json::to_string(&legacy).unwrap();
+        assert!(matches!(parse_export(&edited, with_hmac), Err(LockboxError::ManifestInvalid)));
+
+        // Authentic, but from a device nobody trusted
+        let untrusted = ManifestKeys { trusted: &[], hmac: Some("key"), allow_untrusted: false };
+        match parse_export(&json, untrusted) {
+            Err(LockboxError::UntrustedDevice { public_key, .. }) => assert_eq!(public_key, trusted[0]),
+            other => panic!("expected an untrusted device, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_partial_export_is_signed_and_reported() {
+        let device = DeviceKey::generate();
+        let trusted = [device.public_key()];
+        let keys = ManifestKeys { trusted: &trusted, ..Default::default() };
+        let selection = LockboxFilter { category: Some("Work".to_string()), ..Default::default() };
+        let mut data = export_data(vec![export_lockbox("bank", "key")]);
+        seal(&mut data, &selection, "vault".to_string(), 1, &device).unwrap();
+        let json = serde_json::to_string(&data).unwrap();
+
+        let parsed = parse_export(&json, keys).unwrap();
+        assert_eq!(parsed.manifest.as_ref().unwrap().selection.as_ref(), Some(&selection));
+        let import_keys = ImportKeys { current: Some("key"), ..Default::default() };
+        let target = ImportTarget { lockboxes: &[], categories: &HashMap::new(), limits: &ValidationLimits::default() };
+        let (report, _) = check_import(&parsed, import_keys, target, &ImportStrategies::default(), None, 0).unwrap();
+        assert!(report.partial);
+
+        // Passing a partial file off as a whole one, or the reverse
+        let widened = json.replace(r#""selection":{"category":"Work","tag_match":"any"},"#, "");
+        assert_ne!(widened, json);
+        assert!(matches!(parse_export(&widened, keys), Err(LockboxError::ManifestInvalid)));
+    }
+
+    #[test]
+    fn test_rollback_guard_refuses_files_older_than_a_raise() {
+        let data = export_data(vec![export_lockbox("bank", "key"), export_lockbox("mail", "key")]);
+        let keys = ImportKeys { source: None, current: Some("key"), ..Default::default() };
+        let guard = RollbackGuard { counter: 3, raised: HashMap::from([("bank".to_string(), 3)]) };
+        let target = ImportTarget { lockboxes: &[], categories: &HashMap::new(), limits: &ValidationLimits::default() };
+        let (report, contents) =
+            check_import(&data, keys, target, &ImportStrategies::default(), Some(&guard), 0).unwrap();
+        assert_eq!(report.items[0].status, ImportStatus::Outdated);
+        assert!(contents[0].is_none());
+        assert_eq!(report.items[1].status, ImportStatus::New);
+
+        let newer = RollbackGuard { counter: 4, ..guard };
+        let (report, _) = check_import(&data, keys, target, &ImportStrategies::default(), Some(&newer), 0).unwrap();
+        assert_eq!(report.items[0].status, ImportStatus::New);
+        assert!(!report.items[0].signed);
+    }
+
+    #[test]
+    fn test_rollback_guard_trusts_only_device_verified_counters() {
+        let raised = HashMap::from([("bank".to_string(), 3)]);
+        let device = DeviceKey::generate();
+        let mut data = export_data(vec![export_lockbox("bank", "key")]);
+        seal(&mut data, &LockboxFilter::default(), "vault".to_string(), 5, &device).unwrap();
+
+        // Signed by a device nobody trusts: its vault id proves nothing
+        let guard = RollbackGuard::for_file(&data, "vault", raised.clone()).unwrap();
+        assert_eq!(guard.counter, 0);
+        assert!(RollbackGuard::for_file(&data, "other", raised.clone()).is_some_and(|guard| guard.counter == 0));
+
+        data.device_verified = true;
+        assert_eq!(RollbackGuard::for_file(&data, "vault", raised.clone()).unwrap().counter, 5);
+        assert!(RollbackGuard::for_file(&data, "other", raised.clone()).is_none());
+
+        data.manifest = None;
+        assert_eq!(RollbackGuard::for_file(&data, "vault", raised).unwrap().counter, 0);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::db::{CategoryRequest, Database};
+use crate::error::{LockboxError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Settings key holding the JSON-encoded `ValidationLimits` overrides.
+pub const LIMITS_SETTING_KEY: &str = "validation_limits";
+
+/// AES-GCM output is base64(salt || nonce || ciphertext || tag).
+const ENCRYPTION_OVERHEAD_BYTES: usize = 16 + 12 + 16;
+
+/// Upper bounds enforced on lockbox input. Defaults can be overridden in settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationLimits {
+    pub max_name_chars: usize,
+    pub max_content_bytes: usize,
+    pub max_category_chars: usize,
+    pub max_delay_seconds: i64,
+    pub max_penalty_seconds: i64,
+    pub max_reflection_message_chars: usize,
+    pub max_checklist_items: usize,
+    pub max_checklist_item_chars: usize,
+    pub max_tags: usize,
+    pub max_tag_chars: usize,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        ValidationLimits {
+            max_name_chars: 200,
+            max_content_bytes: 1024 * 1024,
+            max_category_chars: 100,
+            // One year: long enough for any self-control scheme, short enough to catch typos.
+            max_delay_seconds: 365 * 24 * 3600,
+            max_penalty_seconds: 30 * 24 * 3600,
+            max_reflection_message_chars: 2000,
+            max_checklist_items: 20,
+            max_checklist_item_chars: 200,
+            max_tags: 20,
+            max_tag_chars: 50,
+        }
+    }
+}
+
+impl ValidationLimits {
+    /// Limits stored in settings, falling back to the defaults.
+    pub fn load(db: &Database) -> Result<Self> {
+        match db.get_setting(LIMITS_SETTING_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+            None => Ok(ValidationLimits::default()),
+        }
+    }
+
+    pub fn save(&self, db: &Database) -> Result<()> {
+        let limits_by_field = [
+            ("max_name_chars", self.max_name_chars as i64),
+            ("max_content_bytes", self.max_content_bytes as i64),
+            ("max_category_chars", self.max_category_chars as i64),
+            ("max_delay_seconds", self.max_delay_seconds),
+            ("max_penalty_seconds", self.max_penalty_seconds),
+            ("max_reflection_message_chars", self.max_reflection_message_chars as i64),
+            ("max_checklist_items", self.max_checklist_items as i64),
+            ("max_checklist_item_chars", self.max_checklist_item_chars as i64),
+            ("max_tags", self.max_tags as i64),
+            ("max_tag_chars", self.max_tag_chars as i64),
+        ];
+        let errors: Vec<FieldError> = limits_by_field
+            .iter()
+            .filter(|(_, value)| *value <= 0)
+            .map(|(field, _)| FieldError::new(field, FieldErrorKind::NotPositive, None))
+            .collect();
+        if !errors.is_empty() {
+            return Err(LockboxError::Validation { errors });
+        }
+        db.set_setting(LIMITS_SETTING_KEY, &serde_json::to_string(self)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldErrorKind {
+    Required,
+    Negative,
+    NotPositive,
+    TooSmall,
+    TooLarge,
+    TooLong,
+    TooMany,
+    InvalidJson,
+    InvalidItem,
+    InPast,
+}
+
+/// A single rejected field, e.g. `{ field: "unlock_delay_seconds", kind: "negative" }`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub kind: FieldErrorKind,
+    pub limit: Option<i64>,
+}
+
+impl FieldError {
+    fn new(field: &str, kind: FieldErrorKind, limit: Option<i64>) -> Self {
+        FieldError { field: field.to_string(), kind, limit }
+    }
+}
+
+/// Fields shared by create, update and import. `None` means "not provided"
+/// and is skipped, so updates only validate what they change.
+#[derive(Debug, Default)]
+pub struct LockboxInput<'a> {
+    pub name: Option<&'a str>,
+    /// Plaintext length in bytes.
+    pub content_len: Option<usize>,
+    pub category: Option<&'a str>,
+    pub unlock_delay_seconds: Option<i64>,
+    pub relock_delay_seconds: Option<i64>,
+    pub penalty_seconds: Option<i64>,
+    pub reflection_message: Option<&'a str>,
+    pub reflection_checklist: Option<&'a str>,
+    pub tags: Option<&'a str>,
+    pub scheduled_unlock_at: Option<i64>,
+    pub max_views_per_unlock: Option<i64>,
+}
+
+/// Upper bound of the plaintext length behind an `crypto::encrypt` output,
+/// used when validating imported content that cannot be decrypted here.
+pub fn encrypted_plaintext_len(encrypted: &str) -> usize {
+    (encrypted.len() / 4 * 3).saturating_sub(ENCRYPTION_OVERHEAD_BYTES)
+}
+
+/// Validates `input` against `limits`, collecting every field error.
+pub fn validate(input: &LockboxInput, limits: &ValidationLimits, now_ms: i64) -> Result<()> {
+    let mut errors = Vec::new();
+
+    if let Some(name) = input.name {
+        if name.trim().is_empty() {
+            errors.push(FieldError::new("name", FieldErrorKind::Required, None));
+        }
+        check_chars(&mut errors, "name", name, limits.max_name_chars);
+    }
+    if let Some(len) = input.content_len {
+        if len > limits.max_content_bytes {
+            errors.push(FieldError::new(
+                "content",
+                FieldErrorKind::TooLong,
+                Some(limits.max_content_bytes as i64),
+            ));
+        }
+    }
+    if let Some(category) = input.category {
+        check_chars(&mut errors, "category", category, limits.max_category_chars);
+    }
+
+    check_duration(&mut errors, "unlock_delay_seconds", input.unlock_delay_seconds, limits.max_delay_seconds);
+    check_duration(&mut errors, "relock_delay_seconds", input.relock_delay_seconds, limits.max_delay_seconds);
+    check_duration(&mut errors, "penalty_seconds", input.penalty_seconds, limits.max_penalty_seconds);
+
+    if let Some(message) = input.reflection_message {
+        check_chars(&mut errors, "reflection_message", message, limits.max_reflection_message_chars);
+    }
+    if let Some(checklist) = input.reflection_checklist {
+        check_string_array(
+            &mut errors,
+            "reflection_checklist",
+            checklist,
+            limits.max_checklist_items,
+            limits.max_checklist_item_chars,
+        );
+    }
+    if let Some(tags) = input.tags {
+        check_string_array(&mut errors, "tags", tags, limits.max_tags, limits.max_tag_chars);
+    }
+    if matches!(input.max_views_per_unlock, Some(max) if max < 1) {
+        errors.push(FieldError::new("max_views_per_unlock", FieldErrorKind::NotPositive, None));
+    }
+    if let Some(ts) = input.scheduled_unlock_at {
+        if ts <= now_ms {
+            errors.push(FieldError::new("scheduled_unlock_at", FieldErrorKind::InPast, None));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(LockboxError::Validation { errors })
+    }
+}
+
+fn check_chars(errors: &mut Vec<FieldError>, field: &str, value: &str, max: usize) {
+    if value.chars().count() > max {
+        errors.push(FieldError::new(field, FieldErrorKind::TooLong, Some(max as i64)));
+    }
+}
+
+/// Validates a single tag name given to the tag commands, reported under `field`.
+pub fn validate_tag_name(field: &str, name: &str, limits: &ValidationLimits) -> Result<()> {
+    check_name(field, name, limits.max_tag_chars)
+}
+
+/// Validates a folder name, bounded like category names.
+pub fn validate_folder_name(field: &str, name: &str, limits: &ValidationLimits) -> Result<()> {
+    check_name(field, name, limits.max_category_chars)
+}
+
+fn check_name(field: &str, name: &str, max_chars: usize) -> Result<()> {
+    let kind = if name.trim().is_empty() {
+        Some((FieldErrorKind::Required, None))
+    } else if name.chars().count() > max_chars {
+        Some((FieldErrorKind::TooLong, Some(max_chars as i64)))
+    } else {
+        None
+    };
+    match kind {
+        Some((kind, limit)) => Err(LockboxError::Validation { errors: vec![FieldError::new(field, kind, limit)] }),
+        None => Ok(()),
+    }
+}
+
+/// Validates a category's name, look and default policies.
+pub fn validate_category(category: &CategoryRequest, limits: &ValidationLimits) -> Result<()> {
+    let mut errors = Vec::new();
+
+    if category.name.trim().is_empty() {
+        errors.push(FieldError::new("name", FieldErrorKind::Required, None));
+    }
+    check_chars(&mut errors, "name", &category.name, limits.max_category_chars);
+    for (field, value) in [("icon", &category.icon), ("color", &category.color)] {
+        if let Some(value) = value {
+            check_chars(&mut errors, field, value, limits.max_category_chars);
+        }
+    }
+
+    let policies = &category.policies;
+    check_duration(&mut errors, "unlock_delay_seconds", policies.unlock_delay_seconds, limits.max_delay_seconds);
+    check_duration(&mut errors, "relock_delay_seconds", policies.relock_delay_seconds, limits.max_delay_seconds);
+    check_duration(&mut errors, "penalty_seconds", policies.penalty_seconds, limits.max_penalty_seconds);
+    check_duration(
+        &mut errors,
+        "min_unlock_delay_seconds",
+        policies.min_unlock_delay_seconds,
+        limits.max_delay_seconds,
+    );
+    if let Some(checklist) = &policies.reflection_checklist {
+        check_string_array(
+            &mut errors,
+            "reflection_checklist",
+            checklist,
+            limits.max_checklist_items,
+            limits.max_checklist_item_chars,
+        );
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(LockboxError::Validation { errors })
+    }
+}
+
+fn check_duration(errors: &mut Vec<FieldError>, field: &str, value: Option<i64>, max: i64) {
+    match value {
+        Some(v) if v < 0 => errors.push(FieldError::new(field, FieldErrorKind::Negative, None)),
+        Some(v) if v > max => errors.push(FieldError::new(field, FieldErrorKind::TooLarge, Some(max))),
+        _ => {}
+    }
+}
+
+/// Checks a JSON array of non-empty strings, as stored for tags and checklists.
+fn check_string_array(
+    errors: &mut Vec<FieldError>,
+    field: &str,
+    json: &str,
+    max_items: usize,
+    max_item_chars: usize,
+) {
+    let Ok(items) = serde_json::from_str::<Vec<String>>(json) else {
+        errors.push(FieldError::new(field, FieldErrorKind::InvalidJson, None));
+        return;
+    };
+    if items.len() > max_items {
+        errors.push(FieldError::new(field, FieldErrorKind::TooMany, Some(max_items as i64)));
+    }
+    if items
+        .iter()
+        .any(|item| item.trim().is_empty() || item.chars().count() > max_item_chars)
+    {
+        errors.push(FieldError::new(field, FieldErrorKind::InvalidItem, Some(max_item_chars as i64)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_kinds(result: Result<()>) -> Vec<(String, FieldErrorKind)> {
+        match result {
+            Err(LockboxError::Validation { errors }) => {
+                errors.into_iter().map(|e| (e.field, e.kind)).collect()
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rejects_negative_and_oversized_delays() {
+        let limits = ValidationLimits::default();
+        let input = LockboxInput {
+            unlock_delay_seconds: Some(-1),
+            relock_delay_seconds: Some(limits.max_delay_seconds + 1),
+            penalty_seconds: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            field_kinds(validate(&input, &limits, 0)),
+            vec![
+                ("unlock_delay_seconds".to_string(), FieldErrorKind::Negative),
+                ("relock_delay_seconds".to_string(), FieldErrorKind::TooLarge),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_tags_and_past_schedule() {
+        let limits = ValidationLimits::default();
+        let input = LockboxInput {
+            name: Some("Steam"),
+            tags: Some("not json"),
+            reflection_checklist: Some(r#"["ok", ""]"#),
+            scheduled_unlock_at: Some(1_000),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            field_kinds(validate(&input, &limits, 2_000)),
+            vec![
+                ("reflection_checklist".to_string(), FieldErrorKind::InvalidItem),
+                ("tags".to_string(), FieldErrorKind::InvalidJson),
+                ("scheduled_unlock_at".to_string(), FieldErrorKind::InPast),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_category_policies() {
+        let limits = ValidationLimits::default();
+        let category: CategoryRequest = serde_json::from_value(serde_json::json!({
+            "name": " ",
+            "min_unlock_delay_seconds": -5,
+            "penalty_seconds": limits.max_penalty_seconds + 1,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            field_kinds(validate_category(&category, &limits)),
+            vec![
+                ("name".to_string(), FieldErrorKind::Required),
+                ("penalty_seconds".to_string(), FieldErrorKind::TooLarge),
+                ("min_unlock_delay_seconds".to_string(), FieldErrorKind::Negative),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accepts_valid_input() {
+        let input = LockboxInput {
+            name: Some("Steam"),
+            content_len: Some(12),
+            unlock_delay_seconds: Some(3600),
+            relock_delay_seconds: Some(600),
+            penalty_seconds: Some(60),
+            tags: Some(r#"["games"]"#),
+            ..Default::default()
+        };
+        assert!(validate(&input, &ValidationLimits::default(), 0).is_ok());
+    }
+}
@@ -0,0 +1,181 @@
+import React, { useEffect, useState } from 'react';
+import { toast } from 'react-toastify';
+import { clsx } from 'clsx';
+import { ArchiveRestore, HardDrive, RefreshCw } from 'lucide-react';
+import { Button } from './ui/Button';
+import { Input } from './ui/Input';
+import { Select } from './ui/Select';
+import { ConfirmModal } from './ui/Modal';
+import { useLockboxStore, describeError } from '../store';
+import { useTranslation } from '../i18n';
+import type { BackupInfo, BackupPolicy } from '../types';
+
+/** Automatic encrypted backups taken by the backend, with their rotation and restore */
+export const AutoBackupSection: React.FC = () => {
+  const { getBackupPolicy, setBackupPolicy, listBackups, runBackupNow, restoreAutoBackup } = useLockboxStore();
+  const { t } = useTranslation();
+  const [policy, setPolicy] = useState<BackupPolicy | null>(null);
+  const [backups, setBackups] = useState<BackupInfo[]>([]);
+  const [isWorking, setIsWorking] = useState(false);
+  const [restoring, setRestoring] = useState<BackupInfo | null>(null);
+
+  const refresh = () => listBackups().then(setBackups).catch((err) => toast.error(describeError(err)));
+
+  useEffect(() => {
+    getBackupPolicy().then(setPolicy).catch((err) => toast.error(describeError(err)));
+    refresh();
+  // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [getBackupPolicy, listBackups]);
+
+  const updatePolicy = async (next: BackupPolicy) => {
+    const previous = policy;
+    setPolicy(next);
+    try {
+      await setBackupPolicy(next);
+      refresh();
+    } catch (err) {
+      setPolicy(previous);
+      toast.error(describeError(err));
+    }
+  };
+
+  const handleBackupNow = async () => {
+    setIsWorking(true);
+    try {
+      await runBackupNow();
+      toast.success(t('autoBackup.created'));
+      refresh();
+    } catch (err) {
+      toast.error(describeError(err));
+    } finally {
+      setIsWorking(false);
+    }
+  };
+
+  const handleRestore = async () => {
+    if (!restoring) return;
+    setIsWorking(true);
+    try {
+      const count = await restoreAutoBackup(restoring.file_name);
+      toast.success(t('autoBackup.restored', { count }));
+      refresh();
+    } catch (err) {
+      toast.error(describeError(err));
+    } finally {
+      setIsWorking(false);
+      setRestoring(null);
+    }
+  };
+
+  const intervalOptions = [6, 12, 24, 72, 168].map((hours) => ({
+    value: String(hours),
+    label: t(`autoBackup.every.${hours}`),
+  }));
+
+  const keepField = (field: 'keep_daily' | 'keep_weekly' | 'keep_monthly') =>
+    policy && (
+      <Input
+        type="number"
+        min={0}
+        label={t(`autoBackup.${field}`)}
+        value={policy[field]}
+        onChange={(e) => updatePolicy({ ...policy, [field]: Math.max(0, Number(e.target.value) || 0) })}
+      />
+    );
+
+  return (
+    <div className="space-y-3">
+      <h3 className="text-sm font-medium text-gray-900 dark:text-white flex items-center gap-2">
+        <HardDrive className="w-4 h-4 text-gray-500" />
+        {t('autoBackup.title')}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400">{t('autoBackup.description')}</p>
+
+      {policy && (
+        <>
+          <label className="flex items-center justify-between gap-4 cursor-pointer">
+            <span className="text-sm text-gray-900 dark:text-white">{t('autoBackup.enabled')}</span>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={policy.enabled}
+              onClick={() => updatePolicy({ ...policy, enabled: !policy.enabled })}
+              className={clsx(
+                'relative inline-flex h-6 w-11 shrink-0 items-center rounded-full transition-colors focus:outline-none focus:ring-2 focus:ring-primary-500 focus:ring-offset-2 dark:focus:ring-offset-gray-800',
+                policy.enabled ? 'bg-primary-600' : 'bg-gray-300 dark:bg-gray-600'
+              )}
+            >
+              <span
+                className={clsx(
+                  'inline-block h-4 w-4 transform rounded-full bg-white transition-transform',
+                  policy.enabled ? 'translate-x-6' : 'translate-x-1'
+                )}
+              />
+            </button>
+          </label>
+          <Input
+            label={t('autoBackup.directory')}
+            placeholder={t('autoBackup.defaultDirectory')}
+            value={policy.directory ?? ''}
+            onChange={(e) => setPolicy({ ...policy, directory: e.target.value || null })}
+            onBlur={() => updatePolicy(policy)}
+          />
+          <Select
+            label={t('autoBackup.interval')}
+            options={intervalOptions}
+            value={String(policy.interval_hours)}
+            onChange={(e) => updatePolicy({ ...policy, interval_hours: Number(e.target.value) })}
+          />
+          <div className="grid grid-cols-3 gap-2">
+            {keepField('keep_daily')}
+            {keepField('keep_weekly')}
+            {keepField('keep_monthly')}
+          </div>
+        </>
+      )}
+
+      <Button onClick={handleBackupNow} disabled={isWorking} variant="secondary" className="w-full justify-center">
+        <RefreshCw className="w-4 h-4 mr-2" />
+        {t('autoBackup.backUpNow')}
+      </Button>
+
+      {backups.length === 0 ? (
+        <p className="text-xs text-gray-500 dark:text-gray-400">{t('autoBackup.empty')}</p>
+      ) : (
+        <ul className="max-h-48 overflow-y-auto divide-y divide-gray-100 dark:divide-gray-800">
+          {backups.map((backup) => (
+            <li key={backup.file_name} className="flex items-center gap-3 py-2">
+              <div className="flex-1 min-w-0">
+                <p className="text-sm text-gray-900 dark:text-white">{new Date(backup.created_at).toLocaleString()}</p>
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  {t('autoBackup.size', { size: (backup.size_bytes / 1024).toFixed(0) })}
+                </p>
+              </div>
+              <button
+                onClick={() => setRestoring(backup)}
+                disabled={isWorking}
+                className="p-1 rounded text-gray-400 hover:text-primary-600 dark:hover:text-primary-400"
+                title={t('autoBackup.restore')}
+              >
+                <ArchiveRestore className="h-4 w-4" />
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <ConfirmModal
+        isOpen={restoring !== null}
+        onClose={() => setRestoring(null)}
+        onConfirm={handleRestore}
+        title={t('autoBackup.restoreTitle')}
+        message={t('autoBackup.restoreBody', {
+          date: restoring ? new Date(restoring.created_at).toLocaleString() : '',
+        })}
+        confirmText={t('autoBackup.restore')}
+        variant="warning"
+        isLoading={isWorking}
+      />
+    </div>
+  );
+};
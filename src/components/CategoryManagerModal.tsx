@@ -0,0 +1,319 @@
+import React, { useEffect, useState } from 'react';
+import { toast } from 'react-toastify';
+import { ChevronDown, ChevronUp, Pencil, Plus, Trash2 } from 'lucide-react';
+import { clsx } from 'clsx';
+import { Modal } from './ui/Modal';
+import { Button } from './ui/Button';
+import { Input, TextArea } from './ui/Input';
+import { CategoryIcon, CATEGORY_ICONS } from './ui/CategoryIcon';
+import { useLockboxStore, describeError } from '../store';
+import { useTranslation } from '../i18n';
+import { CATEGORY_COLORS, parseTags, type Category, type CategoryInput } from '../types';
+
+interface CategoryManagerModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+/** Form state; durations are typed in minutes, '' meaning "no default" */
+interface CategoryForm {
+  name: string;
+  icon: string;
+  color: string;
+  unlockMinutes: string;
+  relockMinutes: string;
+  penaltyMinutes: string;
+  minUnlockMinutes: string;
+  checklist: string;
+}
+
+const EMPTY_FORM: CategoryForm = {
+  name: '',
+  icon: 'tag',
+  color: CATEGORY_COLORS[0],
+  unlockMinutes: '',
+  relockMinutes: '',
+  penaltyMinutes: '',
+  minUnlockMinutes: '',
+  checklist: '',
+};
+
+const toMinutes = (seconds: number | null): string => (seconds == null ? '' : String(seconds / 60));
+const toSeconds = (minutes: string): number | null =>
+  minutes.trim() === '' ? null : Math.round(Number(minutes) * 60);
+
+const formFromCategory = (category: Category): CategoryForm => ({
+  name: category.name,
+  icon: category.icon ?? 'tag',
+  color: category.color ?? CATEGORY_COLORS[0],
+  unlockMinutes: toMinutes(category.unlock_delay_seconds),
+  relockMinutes: toMinutes(category.relock_delay_seconds),
+  penaltyMinutes: toMinutes(category.penalty_seconds),
+  minUnlockMinutes: toMinutes(category.min_unlock_delay_seconds),
+  checklist: parseTags(category.reflection_checklist).join('\n'),
+});
+
+const inputFromForm = (form: CategoryForm): CategoryInput => {
+  const items = form.checklist.split('\n').map((l) => l.trim()).filter(Boolean);
+  return {
+    name: form.name.trim(),
+    icon: form.icon,
+    color: form.color,
+    unlock_delay_seconds: toSeconds(form.unlockMinutes),
+    relock_delay_seconds: toSeconds(form.relockMinutes),
+    penalty_seconds: toSeconds(form.penaltyMinutes),
+    min_unlock_delay_seconds: toSeconds(form.minUnlockMinutes),
+    reflection_checklist: items.length > 0 ? JSON.stringify(items) : null,
+  };
+};
+
+export const CategoryManagerModal: React.FC<CategoryManagerModalProps> = ({ isOpen, onClose }) => {
+  const { categories, fetchCategories, createCategory, updateCategory, reorderCategories, deleteCategory } =
+    useLockboxStore();
+  const { t, categoryLabel, formatDelay } = useTranslation();
+  // null: list only; 'new': creating; a number: editing that category
+  const [editing, setEditing] = useState<number | 'new' | null>(null);
+  const [form, setForm] = useState<CategoryForm>(EMPTY_FORM);
+  const [pendingDelete, setPendingDelete] = useState<Category | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+
+  useEffect(() => {
+    if (isOpen) {
+      fetchCategories();
+      setEditing(null);
+      setPendingDelete(null);
+    }
+  }, [isOpen, fetchCategories]);
+
+  const startEdit = (category: Category | null) => {
+    setPendingDelete(null);
+    setEditing(category ? category.id : 'new');
+    setForm(category ? formFromCategory(category) : EMPTY_FORM);
+  };
+
+  const update = (changes: Partial<CategoryForm>) => setForm((f) => ({ ...f, ...changes }));
+
+  const handleSave = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (editing === null) return;
+    setIsSaving(true);
+    try {
+      if (editing === 'new') {
+        await createCategory(inputFromForm(form));
+      } else {
+        await updateCategory(editing, inputFromForm(form));
+      }
+      setEditing(null);
+    } catch (err) {
+      toast.error(describeError(err));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleMove = async (index: number, offset: number) => {
+    const ids = categories.map((c) => c.id);
+    [ids[index], ids[index + offset]] = [ids[index + offset], ids[index]];
+    try {
+      await reorderCategories(ids);
+    } catch (err) {
+      toast.error(describeError(err));
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!pendingDelete) return;
+    try {
+      await deleteCategory(pendingDelete.id);
+      setPendingDelete(null);
+    } catch (err) {
+      toast.error(describeError(err));
+    }
+  };
+
+  const durationField = (label: string, key: keyof CategoryForm, hint?: string) => (
+    <Input
+      type="number"
+      min={0}
+      label={label}
+      value={form[key]}
+      onChange={(e) => update({ [key]: e.target.value })}
+      placeholder={t('categories.noDefault')}
+      helperText={hint}
+    />
+  );
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title={t('categories.manageTitle')} size="lg">
+      {editing === null ? (
+        <div className="space-y-4">
+          <p className="text-sm text-gray-600 dark:text-gray-400">{t('categories.manageDesc')}</p>
+
+          <ul className="space-y-1 max-h-96 overflow-y-auto">
+            {categories.map((category, index) => (
+              <li
+                key={category.id}
+                className="flex items-center gap-2 px-2 py-1.5 rounded-lg hover:bg-gray-50 dark:hover:bg-gray-800"
+              >
+                <CategoryIcon icon={category.icon} color={category.color} className="h-4 w-4 shrink-0" />
+                <span className="flex-1 text-sm text-gray-800 dark:text-gray-200 truncate">
+                  {categoryLabel(category.name)}
+                </span>
+                {category.min_unlock_delay_seconds != null && (
+                  <span className="text-xs text-amber-600 dark:text-amber-400 whitespace-nowrap">
+                    ≥ {formatDelay(category.min_unlock_delay_seconds)}
+                  </span>
+                )}
+                <span className="text-xs bg-gray-200 dark:bg-gray-700 px-2 py-0.5 rounded-full">
+                  {category.lockbox_count}
+                </span>
+                <button
+                  type="button"
+                  disabled={index === 0}
+                  onClick={() => handleMove(index, -1)}
+                  className="p-1 rounded text-gray-400 hover:text-gray-600 disabled:opacity-30"
+                  title={t('categories.moveUp')}
+                >
+                  <ChevronUp className="h-4 w-4" />
+                </button>
+                <button
+                  type="button"
+                  disabled={index === categories.length - 1}
+                  onClick={() => handleMove(index, 1)}
+                  className="p-1 rounded text-gray-400 hover:text-gray-600 disabled:opacity-30"
+                  title={t('categories.moveDown')}
+                >
+                  <ChevronDown className="h-4 w-4" />
+                </button>
+                <button
+                  type="button"
+                  onClick={() => startEdit(category)}
+                  className="p-1 rounded text-gray-400 hover:text-primary-600"
+                  title={t('categories.edit')}
+                >
+                  <Pencil className="h-4 w-4" />
+                </button>
+                <button
+                  type="button"
+                  onClick={() => setPendingDelete(category)}
+                  className="p-1 rounded text-gray-400 hover:text-red-600"
+                  title={t('categories.delete')}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </button>
+              </li>
+            ))}
+          </ul>
+
+          {pendingDelete && (
+            <div className="p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg space-y-3">
+              <p className="text-sm text-amber-800 dark:text-amber-300">
+                {t('categories.deleteConfirm', {
+                  name: categoryLabel(pendingDelete.name),
+                  count: pendingDelete.lockbox_count,
+                })}
+              </p>
+              <div className="flex justify-end gap-2">
+                <Button variant="secondary" size="sm" onClick={() => setPendingDelete(null)}>
+                  {t('common.cancel')}
+                </Button>
+                <Button variant="danger" size="sm" onClick={handleDelete}>
+                  {t('categories.delete')}
+                </Button>
+              </div>
+            </div>
+          )}
+
+          <div className="flex justify-end">
+            <Button size="sm" onClick={() => startEdit(null)}>
+              <Plus className="h-4 w-4 mr-1" />
+              {t('categories.new')}
+            </Button>
+          </div>
+        </div>
+      ) : (
+        <form onSubmit={handleSave} className="space-y-4 max-h-[70vh] overflow-y-auto pr-1">
+          <Input
+            label={t('categories.name')}
+            value={form.name}
+            onChange={(e) => update({ name: e.target.value })}
+            required
+          />
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+              {t('categories.icon')}
+            </label>
+            <div className="flex flex-wrap gap-1">
+              {Object.keys(CATEGORY_ICONS).map((icon) => (
+                <button
+                  key={icon}
+                  type="button"
+                  onClick={() => update({ icon })}
+                  className={clsx(
+                    'p-2 rounded-lg border transition-colors',
+                    form.icon === icon
+                      ? 'border-primary-500 bg-primary-50 dark:bg-primary-900/30'
+                      : 'border-transparent hover:bg-gray-100 dark:hover:bg-gray-800'
+                  )}
+                >
+                  <CategoryIcon icon={icon} color={form.color} />
+                </button>
+              ))}
+            </div>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+              {t('categories.color')}
+            </label>
+            <div className="flex gap-2">
+              {CATEGORY_COLORS.map((color) => (
+                <button
+                  key={color}
+                  type="button"
+                  onClick={() => update({ color })}
+                  className={clsx(
+                    'h-6 w-6 rounded-full border-2',
+                    form.color === color ? 'border-gray-900 dark:border-white' : 'border-transparent'
+                  )}
+                  style={{ backgroundColor: color }}
+                  aria-label={color}
+                />
+              ))}
+            </div>
+          </div>
+
+          <div className="space-y-3">
+            <p className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider">
+              {t('categories.defaults')}
+            </p>
+            <p className="text-xs text-gray-500 dark:text-gray-400">{t('categories.defaultsHint')}</p>
+            <div className="grid grid-cols-2 gap-4">
+              {durationField(t('categories.unlockDelay'), 'unlockMinutes')}
+              {durationField(t('categories.relockDelay'), 'relockMinutes')}
+              {durationField(t('categories.penalty'), 'penaltyMinutes')}
+              {durationField(t('categories.minUnlockDelay'), 'minUnlockMinutes', t('categories.minUnlockDelayHint'))}
+            </div>
+            <TextArea
+              label={t('categories.checklist')}
+              value={form.checklist}
+              onChange={(e) => update({ checklist: e.target.value })}
+              placeholder={t('createLockbox.reflectionChecklistPlaceholder')}
+              rows={3}
+            />
+          </div>
+
+          <div className="flex justify-end gap-3 pt-2">
+            <Button type="button" variant="secondary" onClick={() => setEditing(null)}>
+              {t('common.cancel')}
+            </Button>
+            <Button type="submit" isLoading={isSaving}>
+              {t('categories.save')}
+            </Button>
+          </div>
+        </form>
+      )}
+    </Modal>
+  );
+};
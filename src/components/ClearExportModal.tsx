@@ -0,0 +1,49 @@
+import React, { useState } from 'react';
+import { AlertTriangle, FileOutput } from 'lucide-react';
+import { Modal } from './ui/Modal';
+import { Button } from './ui/Button';
+import { Select } from './ui/Select';
+import { useTranslation } from '../i18n';
+import type { ClearFormat } from '../types';
+
+interface ClearExportModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  /** Called with the chosen format; the file is picked next */
+  onSubmit: (format: ClearFormat) => void;
+}
+
+const FORMATS: ClearFormat[] = ['keepass_xml', 'csv'];
+
+/** Format of an export in clear of the unlocked lockboxes, with what it costs */
+export const ClearExportModal: React.FC<ClearExportModalProps> = ({ isOpen, onClose, onSubmit }) => {
+  const { t } = useTranslation();
+  const [format, setFormat] = useState<ClearFormat>('keepass_xml');
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title={t('clearExport.title')} size="sm">
+      <div className="space-y-4">
+        <p className="text-sm text-gray-600 dark:text-gray-300">{t('clearExport.description')}</p>
+        <Select
+          label={t('clearExport.format')}
+          value={format}
+          onChange={(e) => setFormat(e.target.value as ClearFormat)}
+          options={FORMATS.map((value) => ({ value, label: t(`clearExport.formats.${value}`) }))}
+        />
+        <div className="flex items-start gap-2 p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg border border-amber-200 dark:border-amber-800">
+          <AlertTriangle className="h-4 w-4 text-amber-600 dark:text-amber-400 shrink-0 mt-0.5" />
+          <p className="text-xs text-amber-700 dark:text-amber-300">{t('clearExport.warning')}</p>
+        </div>
+        <div className="flex gap-2 justify-end">
+          <Button variant="secondary" onClick={onClose}>
+            {t('common.cancel')}
+          </Button>
+          <Button onClick={() => onSubmit(format)}>
+            <FileOutput className="w-4 h-4 mr-2" />
+            {t('clearExport.chooseFile')}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+};
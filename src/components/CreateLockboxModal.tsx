@@ -9,9 +9,9 @@ import { Tooltip } from './ui/Tooltip';
 import { TagInput } from './ui/TagInput';
 import { DateTimePicker } from './ui/DateTimePicker';
 import { PasswordGeneratorModal } from './PasswordGeneratorModal';
-import { useLockboxStore } from '../store';
+import { useLockboxStore, describeError } from '../store';
 import { useTranslation } from '../i18n';
-import { CATEGORIES, serializeTags } from '../types';
+import { parseTags, serializeTags, isLockboxError } from '../types';
 
 interface CreateLockboxModalProps {
   isOpen: boolean;
@@ -29,6 +29,13 @@ const convertToSeconds = (value: number, unit: TimeUnit): number => {
   }
 };
 
+const secondsToUnit = (totalSeconds: number): { value: number; unit: TimeUnit } => {
+  if (totalSeconds % 86400 === 0 && totalSeconds >= 86400) return { value: totalSeconds / 86400, unit: 'days' };
+  if (totalSeconds % 3600 === 0 && totalSeconds >= 3600) return { value: totalSeconds / 3600, unit: 'hours' };
+  if (totalSeconds % 60 === 0 && totalSeconds >= 60) return { value: totalSeconds / 60, unit: 'minutes' };
+  return { value: totalSeconds, unit: 'seconds' };
+};
+
 interface CollapsibleProps {
   title: string;
   hint?: string;
@@ -77,8 +84,9 @@ const FieldWithTooltip: React.FC<FieldWithTooltipProps> = ({ label, tooltip, chi
 );
 
 export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen, onClose }) => {
-  const { t } = useTranslation();
+  const { t, categoryLabel, formatDelay } = useTranslation();
   const createLockbox = useLockboxStore((state) => state.createLockbox);
+  const categories = useLockboxStore((state) => state.categories);
 
   // Basic fields
   const [name, setName] = useState('');
@@ -99,6 +107,10 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
   const [penaltyDelay, setPenaltyDelay] = useState(30);
   const [penaltyUnit, setPenaltyUnit] = useState<TimeUnit>('minutes');
 
+  // View limit
+  const [viewLimitEnabled, setViewLimitEnabled] = useState(false);
+  const [viewLimit, setViewLimit] = useState(3);
+
   // Tags
   const [tags, setTags] = useState<string[]>([]);
 
@@ -120,6 +132,7 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
     setRelockDelay(1); setRelockUnit('hours');
     setReflectionEnabled(false); setReflectionMessage(''); setReflectionChecklistRaw('');
     setPenaltyEnabled(false); setPenaltyDelay(30); setPenaltyUnit('minutes');
+    setViewLimitEnabled(false); setViewLimit(3);
     setTags([]);
     setPanicCode('');
     setScheduledEnabled(false); setScheduledDateTime('');
@@ -129,6 +142,33 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
 
   const handleClose = () => { resetForm(); onClose(); };
 
+  const selectedCategory = categories.find((c) => c.name === category);
+
+  // Pre-fill the form with the category's defaults; the user can still change them
+  const handleCategoryChange = (name: string) => {
+    setCategory(name);
+    const defaults = categories.find((c) => c.name === name);
+    if (!defaults) return;
+    const floor = defaults.min_unlock_delay_seconds ?? 0;
+    const unlockSeconds = defaults.unlock_delay_seconds ?? (floor > convertToSeconds(unlockDelay, unlockUnit) ? floor : null);
+    if (unlockSeconds != null) {
+      const ul = secondsToUnit(unlockSeconds);
+      setUnlockDelay(ul.value); setUnlockUnit(ul.unit);
+    }
+    if (defaults.relock_delay_seconds != null) {
+      const rl = secondsToUnit(defaults.relock_delay_seconds);
+      setRelockDelay(rl.value); setRelockUnit(rl.unit);
+    }
+    if (defaults.penalty_seconds != null) {
+      const pl = secondsToUnit(defaults.penalty_seconds);
+      setPenaltyEnabled(true); setPenaltyDelay(pl.value); setPenaltyUnit(pl.unit);
+    }
+    const checklist = parseTags(defaults.reflection_checklist);
+    if (checklist.length > 0) {
+      setReflectionEnabled(true); setReflectionChecklistRaw(checklist.join('\n'));
+    }
+  };
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setValidationError('');
@@ -165,12 +205,17 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
         panic_code: panicCode.trim() || undefined,
         scheduled_unlock_at: scheduledUnlockAt,
         tags: serializeTags(tags),
+        max_views_per_unlock: viewLimitEnabled ? viewLimit : undefined,
       });
       toast.success(t('createLockbox.createSuccess'));
       resetForm();
       onClose();
     } catch (err) {
-      toast.error(t('createLockbox.createError'));
+      if (isLockboxError(err) && err.code === 'below_category_minimum') {
+        setValidationError(describeError(err));
+      } else {
+        toast.error(t('createLockbox.createError'));
+      }
       console.error(err);
     } finally {
       setIsSubmitting(false);
@@ -186,7 +231,7 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
 
   const categoryOptions = [
     { value: '', label: t('createLockbox.noCategory') },
-    ...CATEGORIES.map((cat) => ({ value: cat, label: t(`category.${cat}` as 'category.Passwords') })),
+    ...categories.map((cat) => ({ value: cat.name, label: categoryLabel(cat.name) })),
   ];
 
   return (
@@ -233,8 +278,13 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
         <Select
           label={t('createLockbox.category')}
           value={category}
-          onChange={(e) => setCategory(e.target.value)}
+          onChange={(e) => handleCategoryChange(e.target.value)}
           options={categoryOptions}
+          helperText={
+            selectedCategory?.min_unlock_delay_seconds != null
+              ? t('categories.minimumApplies', { delay: formatDelay(selectedCategory.min_unlock_delay_seconds) })
+              : undefined
+          }
         />
 
         <TagInput
@@ -328,6 +378,28 @@ export const CreateLockboxModal: React.FC<CreateLockboxModalProps> = ({ isOpen,
             )}
           </CollapsibleSection>
 
+          {/* View limit */}
+          <CollapsibleSection
+            title={t('createLockbox.viewLimitSection')}
+            hint={t('createLockbox.viewLimitSectionHint')}
+          >
+            <label className="flex items-center gap-3 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={viewLimitEnabled}
+                onChange={(e) => setViewLimitEnabled(e.target.checked)}
+                className="h-4 w-4 rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+              />
+              <span className="text-sm text-gray-700 dark:text-gray-300">{t('createLockbox.viewLimitEnabled')}</span>
+            </label>
+
+            {viewLimitEnabled && (
+              <FieldWithTooltip label={t('createLockbox.viewLimitCount')} tooltip={t('createLockbox.viewLimitCountHint')}>
+                <Input type="number" min={1} value={viewLimit} onChange={(e) => setViewLimit(Number(e.target.value))} className="w-20" />
+              </FieldWithTooltip>
+            )}
+          </CollapsibleSection>
+
           {/* Scheduled unlock */}
           <CollapsibleSection
             title={t('createLockbox.scheduledSection')}
@@ -9,9 +9,9 @@ import { Tooltip } from './ui/Tooltip';
 import { TagInput } from './ui/TagInput';
 import { DateTimePicker } from './ui/DateTimePicker';
 import { PasswordGeneratorModal } from './PasswordGeneratorModal';
-import { useLockboxStore } from '../store';
+import { useLockboxStore, describeError } from '../store';
 import { useTranslation } from '../i18n';
-import { CATEGORIES, parseTags, serializeTags } from '../types';
+import { parseTags, serializeTags, isLockboxError } from '../types';
 import { useEditPermissions } from '../hooks/useEditPermissions';
 import type { Lockbox } from '../types';
 
@@ -104,8 +104,9 @@ const FieldWithTooltip: React.FC<FieldWithTooltipProps> = ({ label, tooltip, chi
 );
 
 export const EditLockboxModal: React.FC<EditLockboxModalProps> = ({ isOpen, onClose, lockbox }) => {
-  const { t } = useTranslation();
+  const { t, categoryLabel } = useTranslation();
   const updateLockbox = useLockboxStore((state) => state.updateLockbox);
+  const categories = useLockboxStore((state) => state.categories);
   const perms = useEditPermissions(lockbox);
 
   // Basic fields
@@ -259,7 +260,7 @@ export const EditLockboxModal: React.FC<EditLockboxModalProps> = ({ isOpen, onCl
       updates.content = content.trim();
     }
 
-    if (perms.canEditPanicCode && (changePanic || !lockbox.panic_code_hash)) {
+    if (perms.canEditPanicCode && (changePanic || !lockbox.has_panic_code)) {
       updates.panic_code = panicCode.trim() || undefined;
     }
 
@@ -271,7 +272,9 @@ export const EditLockboxModal: React.FC<EditLockboxModalProps> = ({ isOpen, onCl
       onClose();
     } catch (err) {
       const msg = String(err);
-      if (msg.includes('while locked')) {
+      if (isLockboxError(err) && err.code === 'below_category_minimum') {
+        setValidationError(describeError(err));
+      } else if (msg.includes('while locked')) {
         setValidationError(t('editLockbox.blockedByLockState'));
       } else {
         toast.error(t('editLockbox.saveError'));
@@ -291,7 +294,7 @@ export const EditLockboxModal: React.FC<EditLockboxModalProps> = ({ isOpen, onCl
 
   const categoryOptions = [
     { value: '', label: t('createLockbox.noCategory') },
-    ...CATEGORIES.map((cat) => ({ value: cat, label: t(`category.${cat}` as 'category.Passwords') })),
+    ...categories.map((cat) => ({ value: cat.name, label: categoryLabel(cat.name) })),
   ];
 
   const lockedFieldMessage = t('editLockbox.availableAfterUnlock');
@@ -522,7 +525,7 @@ export const EditLockboxModal: React.FC<EditLockboxModalProps> = ({ isOpen, onCl
             locked={!perms.canEditPanicCode}
             lockedMessage={lockedFieldMessage}
           >
-            {lockbox.panic_code_hash && (
+            {lockbox.has_panic_code && (
               <label className="flex items-center gap-3 cursor-pointer mb-2">
                 <input
                   type="checkbox"
@@ -533,7 +536,7 @@ export const EditLockboxModal: React.FC<EditLockboxModalProps> = ({ isOpen, onCl
                 <span className="text-sm text-gray-700 dark:text-gray-300">{t('editLockbox.changePanicCode')}</span>
               </label>
             )}
-            {(changePanic || !lockbox.panic_code_hash) && (
+            {(changePanic || !lockbox.has_panic_code) && (
               <FieldWithTooltip label={t('createLockbox.panicCode')} tooltip={t('createLockbox.panicCodeHint')}>
                 <Input
                   type="password"
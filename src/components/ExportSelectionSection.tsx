@@ -0,0 +1,128 @@
+import React, { useEffect, useState } from 'react';
+import { Select } from './ui/Select';
+import { Input } from './ui/Input';
+import { useLockboxStore } from '../store';
+import { useTranslation } from '../i18n';
+import type { LockboxFilter } from '../types';
+
+type Scope = 'all' | 'lockboxes' | 'category' | 'tags' | 'since';
+
+interface ExportSelectionSectionProps {
+  /** Called with the chosen filter, `null` for every lockbox */
+  onChange: (selection: LockboxFilter | null) => void;
+}
+
+/** Which lockboxes an export carries: all of them, or a chosen subset */
+export const ExportSelectionSection: React.FC<ExportSelectionSectionProps> = ({ onChange }) => {
+  const { lockboxes, categories, tags, fetchCategories, fetchTags } = useLockboxStore();
+  const { t } = useTranslation();
+  const [scope, setScope] = useState<Scope>('all');
+  const [ids, setIds] = useState<number[]>([]);
+  const [category, setCategory] = useState('');
+  const [chosenTags, setChosenTags] = useState<string[]>([]);
+  const [tagMatch, setTagMatch] = useState<'any' | 'all'>('any');
+  const [since, setSince] = useState('');
+
+  useEffect(() => {
+    fetchCategories();
+    fetchTags();
+  }, [fetchCategories, fetchTags]);
+
+  useEffect(() => {
+    switch (scope) {
+      case 'all':
+        return onChange(null);
+      case 'lockboxes':
+        return onChange({ ids });
+      case 'category':
+        return onChange({ category });
+      case 'tags':
+        return onChange({ tags: chosenTags, tag_match: tagMatch });
+      case 'since':
+        return onChange({ updated_since: since ? new Date(`${since}T00:00`).getTime() : 0 });
+    }
+  // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [scope, ids, category, chosenTags, tagMatch, since]);
+
+  const toggle = <T,>(list: T[], item: T) =>
+    list.includes(item) ? list.filter((other) => other !== item) : [...list, item];
+
+  const scopeOptions = (['all', 'lockboxes', 'category', 'tags', 'since'] as const).map((value) => ({
+    value,
+    label: t(`exportSelection.scope.${value}`),
+  }));
+
+  return (
+    <div className="space-y-2">
+      <Select
+        label={t('exportSelection.label')}
+        value={scope}
+        onChange={(e) => setScope(e.target.value as Scope)}
+        options={scopeOptions}
+      />
+
+      {scope === 'lockboxes' && (
+        <div className="max-h-40 overflow-y-auto space-y-1 p-2 border border-gray-200 dark:border-gray-700 rounded-lg">
+          {lockboxes.map((lockbox) => (
+            <label key={lockbox.id} className="flex items-center gap-2 text-sm text-gray-900 dark:text-white">
+              <input
+                type="checkbox"
+                checked={ids.includes(lockbox.id)}
+                onChange={() => setIds(toggle(ids, lockbox.id))}
+              />
+              <span className="truncate">{lockbox.name}</span>
+            </label>
+          ))}
+        </div>
+      )}
+
+      {scope === 'category' && (
+        <Select
+          value={category}
+          onChange={(e) => setCategory(e.target.value)}
+          options={[
+            { value: '', label: t('exportSelection.chooseCategory') },
+            ...categories.map((c) => ({ value: c.name, label: c.name })),
+          ]}
+        />
+      )}
+
+      {scope === 'tags' && (
+        <>
+          <div className="flex flex-wrap gap-2">
+            {tags.map((tag) => (
+              <button
+                key={tag.id}
+                type="button"
+                onClick={() => setChosenTags(toggle(chosenTags, tag.name))}
+                className={
+                  chosenTags.includes(tag.name)
+                    ? 'px-2 py-1 rounded-full text-xs bg-primary-600 text-white'
+                    : 'px-2 py-1 rounded-full text-xs bg-gray-100 dark:bg-gray-800 text-gray-700 dark:text-gray-300'
+                }
+              >
+                {tag.name}
+              </button>
+            ))}
+          </div>
+          <Select
+            value={tagMatch}
+            onChange={(e) => setTagMatch(e.target.value as 'any' | 'all')}
+            options={[
+              { value: 'any', label: t('exportSelection.tagMatchAny') },
+              { value: 'all', label: t('exportSelection.tagMatchAll') },
+            ]}
+          />
+        </>
+      )}
+
+      {scope === 'since' && (
+        <Input type="date" value={since} onChange={(e) => setSince(e.target.value)} />
+      )}
+
+      {scope !== 'all' && (
+        <p className="text-xs text-gray-500 dark:text-gray-400">{t('exportSelection.note')}</p>
+      )}
+    </div>
+  );
+};
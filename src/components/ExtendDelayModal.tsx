@@ -10,7 +10,9 @@ interface ExtendDelayModalProps {
   isOpen: boolean;
   onClose: () => void;
   onConfirm: (additionalSeconds: number) => Promise<void>;
-  currentDelaySeconds: number;
+  /** Left out when extending several lockboxes at once */
+  currentDelaySeconds?: number;
+  description?: string;
 }
 
 type TimeUnit = 'minutes' | 'hours' | 'days';
@@ -20,6 +22,7 @@ export const ExtendDelayModal: React.FC<ExtendDelayModalProps> = ({
   onClose,
   onConfirm,
   currentDelaySeconds,
+  description,
 }) => {
   const { t, formatDelay } = useTranslation();
   const [value, setValue] = useState(30);
@@ -57,14 +60,16 @@ export const ExtendDelayModal: React.FC<ExtendDelayModalProps> = ({
     <Modal isOpen={isOpen} onClose={onClose} title={t('extendDelay.title')} size="sm">
       <div className="space-y-4">
         <p className="text-sm text-gray-600 dark:text-gray-400">
-          {t('extendDelay.description')}
+          {description ?? t('extendDelay.description')}
         </p>
 
-        <div className="p-3 bg-gray-50 dark:bg-gray-700/50 rounded-lg">
-          <p className="text-sm text-gray-500 dark:text-gray-400">
-            {t('extendDelay.currentDelay', { delay: formatDelay(currentDelaySeconds) })}
-          </p>
-        </div>
+        {currentDelaySeconds !== undefined && (
+          <div className="p-3 bg-gray-50 dark:bg-gray-700/50 rounded-lg">
+            <p className="text-sm text-gray-500 dark:text-gray-400">
+              {t('extendDelay.currentDelay', { delay: formatDelay(currentDelaySeconds) })}
+            </p>
+          </div>
+        )}
 
         <div>
           <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
@@ -86,7 +91,9 @@ export const ExtendDelayModal: React.FC<ExtendDelayModalProps> = ({
           </div>
           {additionalSeconds > 0 && (
             <p className="mt-1 text-xs text-gray-500">
-              +{formatDelay(additionalSeconds)} → {formatDelay(currentDelaySeconds + additionalSeconds)}
+              +{formatDelay(additionalSeconds)}
+              {currentDelaySeconds !== undefined &&
+                ` → ${formatDelay(currentDelaySeconds + additionalSeconds)}`}
             </p>
           )}
         </div>
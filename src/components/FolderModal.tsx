@@ -0,0 +1,233 @@
+import React, { useEffect, useMemo, useState } from 'react';
+import { toast } from 'react-toastify';
+import { Clock, FolderPlus, Lock, Trash2 } from 'lucide-react';
+import { Modal } from './ui/Modal';
+import { Button } from './ui/Button';
+import { Input } from './ui/Input';
+import { Select } from './ui/Select';
+import { ExtendDelayModal } from './ExtendDelayModal';
+import { useLockboxStore, describeError } from '../store';
+import { useTranslation } from '../i18n';
+import { flattenFolders, folderSubtree } from '../utils/folders';
+
+interface FolderModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  /** Folder to edit; null creates a new one under `parentId` */
+  folderId: number | null;
+  parentId?: number | null;
+}
+
+const ROOT = '';
+
+export const FolderModal: React.FC<FolderModalProps> = ({ isOpen, onClose, folderId, parentId = null }) => {
+  const {
+    folders,
+    lockboxes,
+    createFolder,
+    renameFolder,
+    moveFolder,
+    deleteFolder,
+    relockFolder,
+    extendFolderDelays,
+  } = useLockboxStore();
+  const { t } = useTranslation();
+  const folder = folders.find((f) => f.id === folderId) ?? null;
+  const [name, setName] = useState('');
+  const [parent, setParent] = useState(ROOT);
+  const [subfolderName, setSubfolderName] = useState('');
+  const [confirmDelete, setConfirmDelete] = useState(false);
+  const [showExtend, setShowExtend] = useState(false);
+  const [isSaving, setIsSaving] = useState(false);
+
+  useEffect(() => {
+    if (isOpen) {
+      setName(folder?.name ?? '');
+      setParent(String(folder ? (folder.parent_id ?? ROOT) : (parentId ?? ROOT)));
+      setSubfolderName('');
+      setConfirmDelete(false);
+    }
+    // Reset only when opening, not when the folder list refreshes
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen, folderId]);
+
+  const subtree = useMemo(
+    () => (folder ? folderSubtree(folders, folder.id) : new Set<number>()),
+    [folders, folder]
+  );
+  const treeLockboxes = lockboxes.filter((lb) => lb.folder_id !== null && subtree.has(lb.folder_id));
+  const unlockedCount = treeLockboxes.filter((lb) => !lb.is_locked).length;
+
+  // A folder cannot move below itself
+  const parentOptions = [
+    { value: ROOT, label: t('folders.root') },
+    ...flattenFolders(folders)
+      .filter(({ folder: f }) => !subtree.has(f.id))
+      .map(({ folder: f, path }) => ({ value: String(f.id), label: path })),
+  ];
+  const parentValue = parent === ROOT ? null : Number(parent);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setIsSaving(true);
+    try {
+      await action();
+      return true;
+    } catch (err) {
+      toast.error(describeError(err));
+      return false;
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleSave = async (e: React.FormEvent) => {
+    e.preventDefault();
+    const trimmed = name.trim();
+    if (!trimmed) return;
+    const saved = await run(async () => {
+      if (!folder) {
+        await createFolder(trimmed, parentValue);
+        return;
+      }
+      if (trimmed !== folder.name) await renameFolder(folder.id, trimmed);
+      if (parentValue !== folder.parent_id) await moveFolder(folder.id, parentValue);
+    });
+    if (saved) onClose();
+  };
+
+  const handleCreateSubfolder = async () => {
+    const trimmed = subfolderName.trim();
+    if (!folder || !trimmed) return;
+    if (await run(() => createFolder(trimmed, folder.id))) setSubfolderName('');
+  };
+
+  const handleRelock = async () => {
+    if (!folder) return;
+    await run(async () => {
+      const relocked = await relockFolder(folder.id);
+      toast.success(t('folders.relocked', { count: relocked.length }));
+    });
+  };
+
+  const handleExtend = async (additionalSeconds: number) => {
+    if (!folder) return;
+    try {
+      const extended = await extendFolderDelays(folder.id, additionalSeconds);
+      toast.success(t('folders.extended', { count: extended.length }));
+    } catch (err) {
+      toast.error(describeError(err));
+      throw err;
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!folder) return;
+    if (await run(() => deleteFolder(folder.id))) onClose();
+  };
+
+  return (
+    <Modal
+      isOpen={isOpen}
+      onClose={onClose}
+      title={folder ? t('folders.editTitle') : t('folders.newTitle')}
+      size="md"
+    >
+      <div className="space-y-5">
+        <form onSubmit={handleSave} className="space-y-4">
+          <Input label={t('folders.name')} value={name} onChange={(e) => setName(e.target.value)} required />
+          <Select
+            label={t('folders.parent')}
+            value={parent}
+            onChange={(e) => setParent(e.target.value)}
+            options={parentOptions}
+          />
+          <div className="flex justify-end">
+            <Button type="submit" isLoading={isSaving}>
+              {folder ? t('folders.save') : t('folders.create')}
+            </Button>
+          </div>
+        </form>
+
+        {folder && (
+          <>
+            <div className="pt-4 border-t border-gray-200 dark:border-gray-700 flex items-end gap-2">
+              <Input
+                label={t('folders.newSubfolder')}
+                value={subfolderName}
+                onChange={(e) => setSubfolderName(e.target.value)}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter') {
+                    e.preventDefault();
+                    handleCreateSubfolder();
+                  }
+                }}
+              />
+              <Button variant="secondary" onClick={handleCreateSubfolder} disabled={!subfolderName.trim()}>
+                <FolderPlus className="h-4 w-4" />
+              </Button>
+            </div>
+
+            <div className="pt-4 border-t border-gray-200 dark:border-gray-700 space-y-3">
+              <p className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider">
+                {t('folders.bulkTitle')}
+              </p>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t('folders.bulkDesc', { count: treeLockboxes.length })}
+              </p>
+              <div className="flex gap-2">
+                <Button
+                  variant="secondary"
+                  className="flex-1"
+                  onClick={handleRelock}
+                  disabled={unlockedCount === 0 || isSaving}
+                >
+                  <Lock className="h-4 w-4 mr-2" />
+                  {t('folders.relockAll', { count: unlockedCount })}
+                </Button>
+                <Button
+                  variant="secondary"
+                  className="flex-1"
+                  onClick={() => setShowExtend(true)}
+                  disabled={treeLockboxes.length === 0 || isSaving}
+                >
+                  <Clock className="h-4 w-4 mr-2" />
+                  {t('folders.extendAll')}
+                </Button>
+              </div>
+            </div>
+
+            <div className="pt-4 border-t border-gray-200 dark:border-gray-700">
+              {confirmDelete ? (
+                <div className="p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg space-y-3">
+                  <p className="text-sm text-amber-800 dark:text-amber-300">
+                    {t('folders.deleteConfirm', { name: folder.name })}
+                  </p>
+                  <div className="flex justify-end gap-2">
+                    <Button variant="secondary" size="sm" onClick={() => setConfirmDelete(false)}>
+                      {t('common.cancel')}
+                    </Button>
+                    <Button variant="danger" size="sm" onClick={handleDelete}>
+                      {t('folders.delete')}
+                    </Button>
+                  </div>
+                </div>
+              ) : (
+                <Button variant="ghost" size="sm" onClick={() => setConfirmDelete(true)}>
+                  <Trash2 className="h-4 w-4 mr-2" />
+                  {t('folders.delete')}
+                </Button>
+              )}
+            </div>
+          </>
+        )}
+      </div>
+
+      <ExtendDelayModal
+        isOpen={showExtend}
+        onClose={() => setShowExtend(false)}
+        onConfirm={handleExtend}
+        description={folder ? t('folders.extendDesc', { name: folder.name, count: treeLockboxes.length }) : undefined}
+      />
+    </Modal>
+  );
+};
@@ -0,0 +1,115 @@
+import React, { useEffect, useState } from 'react';
+import { ChevronDown, ChevronRight, FolderClosed, FolderOpen, Plus, Settings2 } from 'lucide-react';
+import { clsx } from 'clsx';
+import { useLockboxStore } from '../store';
+import { useTranslation } from '../i18n';
+import { folderSubtree } from '../utils/folders';
+import { FolderModal } from './FolderModal';
+import type { Folder } from '../types';
+
+/** Sidebar folder tree: selecting a folder shows it and its subfolders */
+export const FolderTree: React.FC = () => {
+  const { folders, fetchFolders, lockboxes, selectedFolder, setSelectedFolder } = useLockboxStore();
+  const { t } = useTranslation();
+  const [collapsed, setCollapsed] = useState<Set<number>>(new Set());
+  // undefined: closed; null: creating a root folder; a number: editing that folder
+  const [editing, setEditing] = useState<number | null | undefined>(undefined);
+
+  useEffect(() => {
+    fetchFolders();
+  }, [lockboxes, fetchFolders]);
+
+  const treeCount = (folder: Folder) => {
+    const subtree = folderSubtree(folders, folder.id);
+    return lockboxes.filter((lb) => lb.folder_id !== null && subtree.has(lb.folder_id)).length;
+  };
+
+  const toggle = (id: number) =>
+    setCollapsed((c) => {
+      const next = new Set(c);
+      if (next.has(id)) next.delete(id);
+      else next.add(id);
+      return next;
+    });
+
+  const renderLevel = (parentId: number | null, depth: number): React.ReactNode =>
+    folders
+      .filter((f) => f.parent_id === parentId)
+      .map((folder) => {
+        const hasChildren = folders.some((f) => f.parent_id === folder.id);
+        const isOpen = !collapsed.has(folder.id);
+        const count = treeCount(folder);
+        return (
+          <React.Fragment key={folder.id}>
+            <div
+              className={clsx(
+                'group flex items-center rounded-lg text-sm transition-colors',
+                selectedFolder === folder.id
+                  ? 'bg-primary-100 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+                  : 'text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-800'
+              )}
+              style={{ paddingLeft: `${depth * 12}px` }}
+            >
+              <button
+                onClick={() => toggle(folder.id)}
+                className={clsx('p-1 text-gray-400', !hasChildren && 'invisible')}
+                aria-label={isOpen ? t('folders.collapse') : t('folders.expand')}
+              >
+                {isOpen ? <ChevronDown className="h-3.5 w-3.5" /> : <ChevronRight className="h-3.5 w-3.5" />}
+              </button>
+              <button
+                onClick={() => setSelectedFolder(selectedFolder === folder.id ? null : folder.id)}
+                className="flex-1 flex items-center gap-2 min-w-0 py-2"
+              >
+                {selectedFolder === folder.id ? (
+                  <FolderOpen className="h-4 w-4 shrink-0" />
+                ) : (
+                  <FolderClosed className="h-4 w-4 shrink-0" />
+                )}
+                <span className="truncate">{folder.name}</span>
+              </button>
+              <button
+                onClick={() => setEditing(folder.id)}
+                className="p-1 rounded text-gray-400 hover:text-gray-600 dark:hover:text-gray-300 opacity-0 group-hover:opacity-100"
+                title={t('folders.editTitle')}
+              >
+                <Settings2 className="h-3.5 w-3.5" />
+              </button>
+              {count > 0 && (
+                <span className="text-xs bg-gray-200 dark:bg-gray-700 px-2 py-0.5 mr-3 rounded-full">{count}</span>
+              )}
+            </div>
+            {isOpen && renderLevel(folder.id, depth + 1)}
+          </React.Fragment>
+        );
+      });
+
+  return (
+    <div className="mt-6">
+      <div className="flex items-center justify-between mb-3">
+        <h2 className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider">
+          {t('folders.title')}
+        </h2>
+        <button
+          onClick={() => setEditing(null)}
+          className="p-1 rounded text-gray-400 hover:text-gray-600 dark:hover:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-800 transition-colors"
+          title={t('folders.newTitle')}
+        >
+          <Plus className="h-3.5 w-3.5" />
+        </button>
+      </div>
+      {folders.length === 0 ? (
+        <p className="px-3 text-xs text-gray-500 dark:text-gray-400">{t('folders.empty')}</p>
+      ) : (
+        <nav className="space-y-1">{renderLevel(null, 0)}</nav>
+      )}
+
+      <FolderModal
+        isOpen={editing !== undefined}
+        onClose={() => setEditing(undefined)}
+        folderId={editing ?? null}
+        parentId={selectedFolder}
+      />
+    </div>
+  );
+};
@@ -0,0 +1,81 @@
+import React, { useState } from 'react';
+import { AlertTriangle, Upload } from 'lucide-react';
+import { Modal } from './ui/Modal';
+import { Button } from './ui/Button';
+import { Select } from './ui/Select';
+import { useTranslation } from '../i18n';
+import type { FolderMapping, ForeignFormat, ForeignImport } from '../types';
+
+interface ForeignImportModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  /** Called with the chosen options; the file is picked next */
+  onSubmit: (options: ForeignImport) => void;
+}
+
+const FORMATS: ForeignFormat[] = ['kdbx', 'keepass_xml', 'bitwarden', 'csv'];
+const FOLDER_MAPPINGS: FolderMapping[] = ['category', 'tags', 'ignore'];
+const UNLOCK_DELAYS = [3600, 86400, 3 * 86400, 7 * 86400];
+const RELOCK_DELAYS = [300, 3600, 86400];
+
+/** Options of an import from another password manager: format, delays given to every entry, folders */
+export const ForeignImportModal: React.FC<ForeignImportModalProps> = ({ isOpen, onClose, onSubmit }) => {
+  const { t, formatDelay } = useTranslation();
+  const [format, setFormat] = useState<ForeignFormat>('kdbx');
+  const [unlockDelay, setUnlockDelay] = useState(86400);
+  const [relockDelay, setRelockDelay] = useState(3600);
+  const [folders, setFolders] = useState<FolderMapping>('category');
+
+  const delayOptions = (delays: number[]) => delays.map((seconds) => ({ value: String(seconds), label: formatDelay(seconds) }));
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title={t('foreignImport.title')} size="sm">
+      <div className="space-y-4">
+        <p className="text-sm text-gray-600 dark:text-gray-300">{t('foreignImport.description')}</p>
+        <Select
+          label={t('foreignImport.format')}
+          value={format}
+          onChange={(e) => setFormat(e.target.value as ForeignFormat)}
+          options={FORMATS.map((value) => ({ value, label: t(`foreignImport.formats.${value}`) }))}
+        />
+        <div className="grid grid-cols-2 gap-3">
+          <Select
+            label={t('createLockbox.unlockDelay')}
+            value={String(unlockDelay)}
+            onChange={(e) => setUnlockDelay(Number(e.target.value))}
+            options={delayOptions(UNLOCK_DELAYS)}
+          />
+          <Select
+            label={t('createLockbox.relockDelay')}
+            value={String(relockDelay)}
+            onChange={(e) => setRelockDelay(Number(e.target.value))}
+            options={delayOptions(RELOCK_DELAYS)}
+          />
+        </div>
+        <Select
+          label={t('foreignImport.folders')}
+          value={folders}
+          onChange={(e) => setFolders(e.target.value as FolderMapping)}
+          options={FOLDER_MAPPINGS.map((value) => ({ value, label: t(`foreignImport.folderMapping.${value}`) }))}
+        />
+        <div className="flex items-start gap-2 p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg border border-amber-200 dark:border-amber-800">
+          <AlertTriangle className="h-4 w-4 text-amber-600 dark:text-amber-400 shrink-0 mt-0.5" />
+          <p className="text-xs text-amber-700 dark:text-amber-300">{t('foreignImport.clearFileWarning')}</p>
+        </div>
+        <div className="flex gap-2 justify-end">
+          <Button variant="secondary" onClick={onClose}>
+            {t('common.cancel')}
+          </Button>
+          <Button
+            onClick={() =>
+              onSubmit({ format, unlock_delay_seconds: unlockDelay, relock_delay_seconds: relockDelay, folders })
+            }
+          >
+            <Upload className="w-4 h-4 mr-2" />
+            {t('foreignImport.chooseFile')}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+};
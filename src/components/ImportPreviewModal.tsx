@@ -0,0 +1,200 @@
+import React, { useEffect, useState } from 'react';
+import { AlertTriangle, CheckCircle2, Copy, History, Info, KeyRound, ShieldX, XCircle } from 'lucide-react';
+import { clsx } from 'clsx';
+import { Modal } from './ui/Modal';
+import { Button } from './ui/Button';
+import { Select } from './ui/Select';
+import { useTranslation } from '../i18n';
+import type { ConflictStrategy, ImportItem, ImportReport, ImportStatus, ImportStrategies } from '../types';
+
+interface ImportPreviewModalProps {
+  report: ImportReport | null;
+  onClose: () => void;
+  onConfirm: () => Promise<void>;
+  /** Checks the file again with other conflict strategies */
+  onStrategiesChange: (strategies: ImportStrategies) => Promise<void>;
+  isImporting: boolean;
+}
+
+const DEFAULT_STRATEGIES: ImportStrategies = { default: 'skip', overrides: {} };
+
+const STRATEGIES: ConflictStrategy[] = ['skip', 'rename', 'replace', 'merge'];
+
+const STATUS_STYLES: Record<ImportStatus, { icon: typeof CheckCircle2; className: string }> = {
+  new: { icon: CheckCircle2, className: 'text-green-600 dark:text-green-400' },
+  conflict: { icon: Copy, className: 'text-gray-500 dark:text-gray-400' },
+  bad_signature: { icon: ShieldX, className: 'text-red-600 dark:text-red-400' },
+  wrong_password: { icon: KeyRound, className: 'text-amber-600 dark:text-amber-400' },
+  invalid: { icon: XCircle, className: 'text-red-600 dark:text-red-400' },
+  outdated: { icon: History, className: 'text-amber-600 dark:text-amber-400' },
+};
+
+const STATUS_ORDER: ImportStatus[] = ['new', 'conflict', 'bad_signature', 'wrong_password', 'invalid', 'outdated'];
+
+/** Dry-run report of an import file, confirmed before anything is written */
+export const ImportPreviewModal: React.FC<ImportPreviewModalProps> = ({
+  report,
+  onClose,
+  onConfirm,
+  onStrategiesChange,
+  isImporting,
+}) => {
+  const { t, formatDelay, categoryLabel } = useTranslation();
+  const [strategies, setStrategies] = useState<ImportStrategies>(DEFAULT_STRATEGIES);
+  const items = report?.items ?? [];
+  const writeCount = items.filter((item) => item.action !== 'skip').length;
+  const unsignedCount = items.filter((item) => !item.signed).length;
+  const hasConflicts = items.some((item) => item.status === 'conflict');
+
+  // Each new file starts from skipping every conflict
+  const isOpen = report !== null;
+  useEffect(() => {
+    if (!isOpen) setStrategies(DEFAULT_STRATEGIES);
+  }, [isOpen]);
+
+  const changeStrategies = (next: ImportStrategies) => {
+    setStrategies(next);
+    onStrategiesChange(next);
+  };
+
+  // Replace and merge need a stored lockbox; duplicates within the file can only be renamed or skipped
+  const strategyOptions = (withExisting: boolean) =>
+    STRATEGIES.filter((s) => withExisting || s === 'skip' || s === 'rename').map((s) => ({
+      value: s,
+      label: t(`importPreview.strategy.${s}`),
+    }));
+
+  const detail = (item: ImportItem): string | null => {
+    switch (item.status) {
+      case 'invalid':
+        return t('importPreview.invalidFields', {
+          fields: item.errors.map((e) => e.field).join(', '),
+        });
+      case 'outdated':
+        return t('importPreview.outdated');
+      case 'conflict':
+        if (item.action === 'rename') return t('importPreview.renamedTo', { name: item.renamed_to ?? '' });
+        if (item.action === 'replace') return t('importPreview.replaces');
+        if (item.action === 'merge') return t('importPreview.merges');
+        if (item.looser_fields.length > 0) {
+          return t('importPreview.replaceRefused', { fields: item.looser_fields.join(', ') });
+        }
+        return item.existing_id !== null ? t('importPreview.conflictExisting') : t('importPreview.conflictFile');
+      default:
+        return null;
+    }
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title={t('importPreview.title')} size="lg">
+      <div className="space-y-4">
+        <p className="text-sm text-gray-600 dark:text-gray-400">{t('importPreview.description')}</p>
+
+        <div className="flex flex-wrap gap-2">
+          {STATUS_ORDER.map((status) => {
+            const count = items.filter((item) => item.status === status).length;
+            if (count === 0) return null;
+            const { icon: Icon, className } = STATUS_STYLES[status];
+            return (
+              <span
+                key={status}
+                className="inline-flex items-center gap-1 px-2 py-1 rounded-full bg-gray-100 dark:bg-gray-800 text-xs"
+              >
+                <Icon className={clsx('h-3.5 w-3.5', className)} />
+                {t(`importPreview.status.${status}`)}: {count}
+              </span>
+            );
+          })}
+        </div>
+
+        {report?.partial && (
+          <div className="flex items-start gap-2 p-3 bg-gray-50 dark:bg-gray-800 rounded-lg">
+            <Info className="h-4 w-4 text-gray-500 shrink-0 mt-0.5" />
+            <p className="text-xs text-gray-600 dark:text-gray-400">{t('importPreview.partial')}</p>
+          </div>
+        )}
+
+        {report?.source && (
+          <div className="flex items-start gap-2 p-3 bg-gray-50 dark:bg-gray-800 rounded-lg">
+            <Info className="h-4 w-4 text-gray-500 shrink-0 mt-0.5" />
+            <p className="text-xs text-gray-600 dark:text-gray-400">
+              {t('importPreview.foreign', { format: t(`foreignImport.formats.${report.source}`) })}
+            </p>
+          </div>
+        )}
+
+        {unsignedCount > 0 && !report?.source && (
+          <div className="flex items-start gap-2 p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg border border-amber-200 dark:border-amber-800">
+            <AlertTriangle className="h-4 w-4 text-amber-600 dark:text-amber-400 shrink-0 mt-0.5" />
+            <p className="text-xs text-amber-700 dark:text-amber-300">
+              {t('importPreview.unsigned', { count: unsignedCount })}
+            </p>
+          </div>
+        )}
+
+        {hasConflicts && (
+          <Select
+            label={t('importPreview.strategyLabel')}
+            value={strategies.default}
+            onChange={(e) =>
+              changeStrategies({ ...strategies, default: e.target.value as ConflictStrategy })
+            }
+            options={strategyOptions(true)}
+            helperText={t('importPreview.strategyHelp')}
+          />
+        )}
+
+        <ul className="divide-y divide-gray-100 dark:divide-gray-800 max-h-80 overflow-y-auto">
+          {items.map((item) => {
+            const { icon: Icon, className } = STATUS_STYLES[item.status];
+            const note = detail(item);
+            const options = strategyOptions(item.existing_id !== null);
+            return (
+              <li key={item.index} className="flex items-start gap-3 py-2">
+                <Icon className={clsx('h-4 w-4 mt-0.5 shrink-0', className)} />
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm font-medium text-gray-900 dark:text-white truncate">
+                    {item.name || t('importPreview.unnamed')}
+                  </p>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    {[item.category && categoryLabel(item.category), formatDelay(item.unlock_delay_seconds)]
+                      .filter(Boolean)
+                      .join(' · ')}
+                  </p>
+                  {note && <p className="text-xs text-gray-500 dark:text-gray-400">{note}</p>}
+                </div>
+                {item.status === 'conflict' && (
+                  <div className="w-36 shrink-0">
+                    <Select
+                      aria-label={t('importPreview.strategyLabel')}
+                      value={options.find((o) => o.value === item.strategy)?.value ?? 'skip'}
+                      onChange={(e) =>
+                        changeStrategies({
+                          ...strategies,
+                          overrides: { ...strategies.overrides, [item.index]: e.target.value as ConflictStrategy },
+                        })
+                      }
+                      options={options}
+                    />
+                  </div>
+                )}
+                <span className={clsx('text-xs whitespace-nowrap', className)}>
+                  {t(`importPreview.status.${item.status}`)}
+                </span>
+              </li>
+            );
+          })}
+        </ul>
+
+        <div className="flex gap-3 justify-end pt-2">
+          <Button variant="secondary" onClick={onClose} disabled={isImporting}>
+            {t('common.cancel')}
+          </Button>
+          <Button onClick={onConfirm} isLoading={isImporting} disabled={writeCount === 0}>
+            {t('importPreview.confirm', { count: writeCount })}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+};
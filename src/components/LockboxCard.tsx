@@ -15,7 +15,7 @@ interface LockboxCardProps {
 
 export const LockboxCard: React.FC<LockboxCardProps> = ({ lockbox, onClick, isSelected = false }) => {
   const status = useLockboxStatus(lockbox);
-  const { t } = useTranslation();
+  const { t, categoryLabel } = useTranslation();
 
   const targetTimestamp =
     status === 'unlocking'
@@ -71,7 +71,7 @@ export const LockboxCard: React.FC<LockboxCardProps> = ({ lockbox, onClick, isSe
               <div className="flex items-center gap-1 mt-1">
                 <Tag className="h-3 w-3 text-gray-400" />
                 <span className="text-xs text-gray-500 dark:text-gray-400">
-                  {t(`category.${lockbox.category}` as 'category.Passwords')}
+                  {categoryLabel(lockbox.category)}
                 </span>
               </div>
             )}
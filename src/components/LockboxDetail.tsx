@@ -26,8 +26,10 @@ import type { Lockbox, AccessLogEntry } from "../types";
 import { parseTags } from "../types";
 import { Button } from "./ui/Button";
 import { ConfirmModal } from "./ui/Modal";
+import { Select } from "./ui/Select";
 import { Tooltip } from "./ui/Tooltip";
-import { useLockboxStore } from "../store";
+import { useLockboxStore, describeError } from "../store";
+import { flattenFolders } from "../utils/folders";
 import { useCountdown, formatTimeRemaining } from "../hooks/useCountdown";
 import {
   useLockboxStatus,
@@ -76,11 +78,14 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
     relockLockbox,
     deleteLockbox,
     fetchLockboxDecrypted,
+    recordContentCopy,
     getAccessLog,
+    folders,
+    moveLockboxes,
   } = useLockboxStore();
 
   const status = useLockboxStatus(lockbox);
-  const { t, formatDelay } = useTranslation();
+  const { t, formatDelay, categoryLabel } = useTranslation();
 
   // Fetch decrypted content when unlocked, or when lockbox is updated while unlocked
   useEffect(() => {
@@ -103,8 +108,6 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
     getAccessLog(lockbox.id).then(setAccessLog);
   }, [showAccessLog, lockbox.id, getAccessLog]);
 
-  const displayContent = decryptedContent || lockbox.content;
-
   const targetTimestamp =
     status === "unlocking"
       ? lockbox.unlock_timestamp
@@ -156,6 +159,14 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
     }
   };
 
+  const handleMove = async (value: string) => {
+    try {
+      await moveLockboxes([lockbox.id], value === "" ? null : Number(value));
+    } catch (error) {
+      toast.error(describeError(error));
+    }
+  };
+
   const handleDelete = async () => {
     try {
       await deleteLockbox(lockbox.id);
@@ -168,7 +179,9 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
   };
 
   const handleCopy = () => {
-    navigator.clipboard.writeText(displayContent);
+    if (!decryptedContent) return;
+    navigator.clipboard.writeText(decryptedContent);
+    recordContentCopy(lockbox.id);
     setCopied(true);
     setTimeout(() => setCopied(false), 2000);
     toast.success(t("lockboxDetail.contentCopied"));
@@ -244,6 +257,32 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
     return t(key) || eventType;
   };
 
+  /** Short summary of the structured event details, if any */
+  const getLogDetail = (entry: AccessLogEntry): string | null => {
+    switch (entry.event_type) {
+      case "unlock_requested":
+        return entry.details && formatDelay(entry.details.unlock_delay_seconds);
+      case "unlock_cancelled":
+        return entry.details && entry.details.penalty_seconds > 0
+          ? `+${formatDelay(entry.details.penalty_seconds)}`
+          : null;
+      case "extend_delay":
+        return entry.details && `+${formatDelay(entry.details.additional_seconds)}`;
+      case "content_viewed":
+        return entry.details?.max_views_per_unlock
+          ? `${entry.details.views_this_unlock}/${entry.details.max_views_per_unlock}`
+          : null;
+      case "content_exported":
+        return entry.details && t(`clearExport.formats.${entry.details.format}`);
+      case "field_updated":
+        return entry.details && entry.details.fields.join(", ");
+      case "snapshot_restored":
+        return entry.details && formatLogDate(entry.details.created_at);
+      default:
+        return null;
+    }
+  };
+
   const getLogColor = (eventType: string) => {
     switch (eventType) {
       case "unlock_completed":
@@ -439,8 +478,26 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
               {t("lockboxDetail.category")}
             </p>
             <p className="font-medium text-gray-900 dark:text-white">
-              {t(`category.${lockbox.category}` as "category.Passwords")}
+              {categoryLabel(lockbox.category)}
+            </p>
+          </div>
+        )}
+        {folders.length > 0 && (
+          <div className="p-3 bg-gray-50 dark:bg-gray-800/50 rounded-lg col-span-2">
+            <p className="text-xs text-gray-500 dark:text-gray-400 mb-2">
+              {t("folders.label")}
             </p>
+            <Select
+              value={lockbox.folder_id === null ? "" : String(lockbox.folder_id)}
+              onChange={(e) => handleMove(e.target.value)}
+              options={[
+                { value: "", label: t("folders.root") },
+                ...flattenFolders(folders).map(({ folder, path }) => ({
+                  value: String(folder.id),
+                  label: path,
+                })),
+              ]}
+            />
           </div>
         )}
         {parseTags(lockbox.tags).length > 0 && (
@@ -464,7 +521,7 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
       </div>
 
       {/* Emergency panic code — use while locked/unlocking/scheduled */}
-      {lockbox.panic_code_hash && !isUnlocked && (
+      {lockbox.has_panic_code && !isUnlocked && (
         <div className="relative z-20 mb-4">
           {!lockbox.panic_code_used ? (
             <div className="border-2 border-orange-400 dark:border-orange-600 rounded-lg shadow-md shadow-orange-100 dark:shadow-orange-900/30">
@@ -530,7 +587,7 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
       )}
 
       {/* Reset panic code — only when unlocked and code was used */}
-      {isUnlocked && lockbox.panic_code_hash && lockbox.panic_code_used && (
+      {isUnlocked && lockbox.has_panic_code && lockbox.panic_code_used && (
         <div className="mb-4 border border-gray-200 dark:border-gray-700 rounded-lg overflow-hidden">
           <button
             onClick={() => setShowResetPanic(!showResetPanic)}
@@ -676,6 +733,11 @@ export const LockboxDetail: React.FC<LockboxDetailProps> = ({
                     )}
                   >
                     {getLogLabel(entry.event_type)}
+                    {getLogDetail(entry) && (
+                      <span className="ml-1 font-normal text-gray-400 dark:text-gray-500">
+                        · {getLogDetail(entry)}
+                      </span>
+                    )}
                   </span>
                   <span className="text-gray-400 dark:text-gray-500">
                     {formatLogDate(entry.timestamp)}
@@ -1,12 +1,22 @@
 import React, { useState, useEffect } from 'react';
 import { toast } from 'react-toastify';
-import { Download, Upload, ShieldAlert, Sun, Moon, Monitor, Globe, Database, Settings as SettingsIcon, Bell } from 'lucide-react';
+import { Download, Upload, ShieldAlert, ShieldQuestion, KeyRound, Sun, Moon, Monitor, Globe, Database, Settings as SettingsIcon, Bell, History, ArchiveRestore, HardDriveDownload, FileInput, FileOutput } from 'lucide-react';
 import { clsx } from 'clsx';
 import { Modal } from './ui/Modal';
 import { Button } from './ui/Button';
-import { useThemeStore, useSettingsStore } from '../store';
+import { Select } from './ui/Select';
+import { Input } from './ui/Input';
+import { useThemeStore, useSettingsStore, useLockboxStore, describeError } from '../store';
 import { useExportImport } from '../hooks';
 import { useTranslation } from '../i18n';
+import { isLockboxError } from '../types';
+import type { ClearFormat, ForeignFormat, ForeignImport, ImportReport, ImportStrategies, LockboxFilter, RetentionPolicy } from '../types';
+import { ImportPreviewModal } from './ImportPreviewModal';
+import { TrustedDevicesSection } from './TrustedDevicesSection';
+import { ExportSelectionSection } from './ExportSelectionSection';
+import { AutoBackupSection } from './AutoBackupSection';
+import { ForeignImportModal } from './ForeignImportModal';
+import { ClearExportModal } from './ClearExportModal';
 
 interface SettingsModalProps {
   isOpen: boolean;
@@ -15,6 +25,9 @@ interface SettingsModalProps {
 
 type Tab = 'general' | 'data';
 
+/** Shortest portable export passphrase, as enforced by src-tauri/src/portable.rs */
+const MIN_PASSPHRASE_LENGTH = 10;
+
 export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose }) => {
   const [activeTab, setActiveTab] = useState<Tab>('general');
   const { theme, setTheme } = useThemeStore();
@@ -26,31 +39,86 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
   } = useSettingsStore();
   const { t, locale, setLocale } = useTranslation();
   
-  const { exportLockboxes, importLockboxes, isExporting, isImporting, error: importError, clearError } = useExportImport();
+  const {
+    exportLockboxes,
+    exportPortable,
+    exportClear,
+    exportBackup,
+    restoreBackup,
+    previewImport,
+    previewForeignImport,
+    retryPreview,
+    changeStrategies,
+    confirmImport,
+    cancelImport,
+    isExporting,
+    isImporting,
+    error: importError,
+    clearError,
+  } = useExportImport();
 
   // Export success warning modal
   const [showExportWarning, setShowExportWarning] = useState(false);
 
-  // Import password modal
+  // Lockboxes the next export carries, `null` for all of them
+  const [exportSelection, setExportSelection] = useState<LockboxFilter | null>(null);
+
+  // Export in clear for another password manager
+  const [showClearExport, setShowClearExport] = useState(false);
+
+  // Import password modal, shared by imports and backup restores
   const [showImportModal, setShowImportModal] = useState(false);
   const [sourcePassword, setSourcePassword] = useState('');
+  const [passwordFor, setPasswordFor] = useState<'import' | 'backup'>('import');
 
-  const humanizeImportError = (raw: string): string => {
-    if (raw.includes('Integrity check failed') || raw.includes('tampered')) {
-      return t('header.importErrorIntegrity');
-    }
-    if (raw.includes('wrong source password') || raw.includes('Failed to decrypt')) {
-      return t('header.importErrorPassword');
-    }
-    if (raw.includes('Invalid file format') || raw.includes('invalid') || raw.includes('parse')) {
-      return t('header.importErrorFormat');
+  // Dry-run report awaiting confirmation
+  const [importPreview, setImportPreview] = useState<ImportReport | null>(null);
+
+  // Import file signed by a device not trusted yet, offered for trust
+  const [untrusted, setUntrusted] = useState<{ publicKey: string; fingerprint: string } | null>(null);
+  const [deviceName, setDeviceName] = useState('');
+  const [devicesRefresh, setDevicesRefresh] = useState(0);
+  const { trustDevice } = useLockboxStore();
+
+  // Import from another password manager: options, then the format being previewed
+  const [showForeignImport, setShowForeignImport] = useState(false);
+  const [foreignFormat, setForeignFormat] = useState<ForeignFormat | null>(null);
+
+  // Passphrase of a portable export, being chosen or asked for an import,
+  // or master password of a KeePass database being imported
+  const [passphraseFor, setPassphraseFor] = useState<'export' | 'import' | 'database' | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [passphraseConfirm, setPassphraseConfirm] = useState('');
+  const passphraseTooShort =
+    passphraseFor === 'database' ? passphrase.length === 0 : passphrase.length < MIN_PASSPHRASE_LENGTH;
+  const passphraseMismatch = passphraseFor === 'export' && passphrase !== passphraseConfirm;
+
+  const humanizeImportError = (err: unknown): string => {
+    if (isLockboxError(err)) {
+      switch (err.code) {
+        case 'integrity_check_failed':
+          return t('header.importErrorIntegrity');
+        case 'source_decryption_failed':
+          return t('header.importErrorPassword');
+        case 'invalid_import_format':
+          return t('header.importErrorFormat');
+        case 'passphrase_incorrect':
+        case 'passphrase_too_short':
+        case 'manifest_invalid':
+        case 'backup_signature_invalid':
+        case 'unsupported_backup_version':
+        case 'vault_not_empty':
+        case 'master_password_required':
+        case 'unsupported_foreign_file':
+          return describeError(err);
+      }
     }
-    return t('header.importErrorGeneric', { detail: raw });
+    return t('header.importErrorGeneric', { detail: describeError(err) });
   };
 
   const handleExport = async () => {
     clearError();
-    const success = await exportLockboxes();
+    const success = await exportLockboxes(exportSelection);
     if (success) {
       setShowExportWarning(true);
     } else {
@@ -58,8 +126,88 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
     }
   };
 
+  const handleClearExport = async (format: ClearFormat) => {
+    setShowClearExport(false);
+    clearError();
+    const result = await exportClear(format, exportSelection);
+    if (!result) {
+      setPostImportCheck(true);
+    } else if (result.exported === 0) {
+      toast.warning(t('clearExport.nothingUnlocked'));
+    } else {
+      toast.success(t('clearExport.exported', { count: result.exported }));
+      if (result.skipped_locked > 0) {
+        toast.info(t('clearExport.skipped', { count: result.skipped_locked }));
+      }
+    }
+  };
+
+  const openPassphrase = (mode: 'export' | 'import' | 'database') => {
+    setPassphrase('');
+    setPassphraseConfirm('');
+    setPassphraseFor(mode);
+  };
+
+  const submitPassphrase = async () => {
+    const mode = passphraseFor;
+    setPassphraseFor(null);
+    clearError();
+    if (mode === 'export') {
+      const result = await exportPortable(passphrase, exportSelection);
+      if (!result) {
+        setPostImportCheck(true);
+      } else if (result.exported === 0) {
+        toast.warning(t('clearExport.nothingUnlocked'));
+      } else {
+        toast.success(t('settings.portableExported'));
+        if (result.skipped_locked > 0) {
+          toast.info(t('clearExport.skipped', { count: result.skipped_locked }));
+        }
+      }
+      return;
+    }
+    const report = await retryPreview(passphrase);
+    if (report) {
+      setImportPreview(report);
+    } else {
+      setPostImportCheck(true);
+    }
+  };
+
+  const handleBackup = async () => {
+    clearError();
+    if (await exportBackup()) {
+      toast.success(t('settings.backupCreated'));
+    } else {
+      setPostImportCheck(true);
+    }
+  };
+
+  const handleRestoreClick = () => {
+    setSourcePassword('');
+    setPasswordFor('backup');
+    clearError();
+    setShowImportModal(true);
+  };
+
+  const doRestore = async (password: string | null) => {
+    setShowImportModal(false);
+    clearError();
+    const report = await restoreBackup(password);
+    if (report !== null) {
+      toast.success(t('settings.backupRestored', { count: report.lockboxes.length }));
+      if (report.outdated.length > 0) {
+        toast.warning(t('settings.backupOutdated', { names: report.outdated.join(', ') }));
+      }
+    } else {
+      setPostImportCheck(true);
+    }
+  };
+
   const handleImportClick = () => {
+    setForeignFormat(null);
     setSourcePassword('');
+    setPasswordFor('import');
     clearError();
     setShowImportModal(true);
   };
@@ -67,22 +215,149 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
   const doImport = async (password: string | null) => {
     setShowImportModal(false);
     clearError();
-    const imported = await importLockboxes(password);
-    if (imported.length > 0) {
-      toast.success(t('header.importedCount', { count: imported.length }));
+    const report = await previewImport(password);
+    if (report) {
+      setImportPreview(report);
+    } else {
+      setPostImportCheck(true);
+    }
+  };
+
+  const handleForeignImport = async (options: ForeignImport) => {
+    setShowForeignImport(false);
+    setForeignFormat(options.format);
+    clearError();
+    const report = await previewForeignImport(options);
+    if (report) {
+      setImportPreview(report);
     } else {
       setPostImportCheck(true);
     }
   };
 
+  const submitSourcePassword = (password: string | null) =>
+    passwordFor === 'backup' ? doRestore(password) : doImport(password);
+
+  const handleConfirmImport = async () => {
+    const report = await confirmImport();
+    setImportPreview(null);
+    if (!report) {
+      setPostImportCheck(true);
+      return;
+    }
+    const imported = report.items.filter((item) => item.imported).length;
+    if (imported > 0) {
+      toast.success(t('header.importedCount', { count: imported }));
+    } else {
+      toast.info(t('header.importNoneNew'));
+    }
+  };
+
+  const handleStrategiesChange = async (strategies: ImportStrategies) => {
+    const report = await changeStrategies(strategies);
+    if (report) {
+      setImportPreview(report);
+    } else {
+      setPostImportCheck(true);
+    }
+  };
+
+  const handleTrustAndRetry = async () => {
+    if (!untrusted) return;
+    try {
+      await trustDevice(untrusted.publicKey, deviceName);
+    } catch (err) {
+      toast.error(describeError(err));
+      return;
+    }
+    setUntrusted(null);
+    setDevicesRefresh((n) => n + 1);
+    const report = await retryPreview();
+    if (report) {
+      setImportPreview(report);
+    } else {
+      setPostImportCheck(true);
+    }
+  };
+
+  const handleCancelImport = () => {
+    cancelImport();
+    setImportPreview(null);
+  };
+
   const [postImportCheck, setPostImportCheck] = useState(false);
+
+  // Access history retention
+  const { getRetentionPolicy, setRetentionPolicy, compactAccessLog } = useLockboxStore();
+  const [retention, setRetention] = useState<RetentionPolicy | null>(null);
+  const [isCompacting, setIsCompacting] = useState(false);
+
+  useEffect(() => {
+    if (isOpen) {
+      getRetentionPolicy().then(setRetention);
+    }
+  }, [isOpen, getRetentionPolicy]);
+
+  const retentionOptions = [
+    { value: '', label: t('settings.retentionForever') },
+    { value: '90', label: t('settings.retention3Months') },
+    { value: '180', label: t('settings.retention6Months') },
+    { value: '365', label: t('settings.retention1Year') },
+    { value: '730', label: t('settings.retention2Years') },
+  ];
+
+  const updateRetention = async (policy: RetentionPolicy) => {
+    const previous = retention;
+    setRetention(policy);
+    try {
+      await setRetentionPolicy(policy);
+    } catch (err) {
+      setRetention(previous);
+      toast.error(describeError(err));
+    }
+  };
+
+  const handleCompactNow = async () => {
+    setIsCompacting(true);
+    try {
+      const report = await compactAccessLog();
+      if (report.removed_entries > 0) {
+        toast.success(t('settings.compactedCount', { count: report.removed_entries }));
+      } else {
+        toast.info(t('settings.compactedNothing'));
+      }
+    } catch (err) {
+      toast.error(describeError(err));
+    } finally {
+      setIsCompacting(false);
+    }
+  };
   useEffect(() => {
     if (!postImportCheck) return;
     setPostImportCheck(false);
+    // An unknown signing device is offered for trust instead of failing
+    if (isLockboxError(importError) && importError.code === 'untrusted_device') {
+      setDeviceName('');
+      setUntrusted({
+        publicKey: String(importError.params.public_key),
+        fingerprint: String(importError.params.fingerprint),
+      });
+      return;
+    }
+    // A portable file or KeePass database asks for its passphrase, again if it was wrong
+    const passphraseMode = foreignFormat === 'kdbx' ? 'database' : 'import';
+    if (isLockboxError(importError) && importError.code === 'passphrase_required') {
+      openPassphrase(passphraseMode);
+      return;
+    }
+    if (isLockboxError(importError) && importError.code === 'passphrase_incorrect') {
+      toast.error(describeError(importError));
+      openPassphrase(passphraseMode);
+      return;
+    }
+    // Nothing to report when the file dialog was simply cancelled
     if (importError) {
       toast.error(humanizeImportError(importError), { autoClose: 7000 });
-    } else {
-      toast.info(t('header.importNoneNew'));
     }
   // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [postImportCheck, importError]);
@@ -247,10 +522,22 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
                   <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
                     {t('settings.exportDesc') || 'Export your lockboxes to a secure, encrypted file.'}
                   </p>
-                  <Button onClick={handleExport} disabled={isExporting} variant="secondary" className="w-full justify-center">
-                    <Download className="w-4 h-4 mr-2" />
-                    {t('header.export')}
-                  </Button>
+                  <ExportSelectionSection onChange={setExportSelection} />
+                  <div className="flex gap-2">
+                    <Button onClick={handleExport} disabled={isExporting} variant="secondary" className="flex-1 justify-center">
+                      <Download className="w-4 h-4 mr-2" />
+                      {t('header.export')}
+                    </Button>
+                    <Button onClick={() => openPassphrase('export')} disabled={isExporting} variant="secondary" className="flex-1 justify-center">
+                      <KeyRound className="w-4 h-4 mr-2" />
+                      {t('settings.portableExport')}
+                    </Button>
+                    <Button onClick={() => setShowClearExport(true)} disabled={isExporting} variant="secondary" className="flex-1 justify-center">
+                      <FileOutput className="w-4 h-4 mr-2" />
+                      {t('clearExport.button')}
+                    </Button>
+                  </div>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">{t('settings.portableExportNote')}</p>
                 </div>
 
                 <div className="w-full h-px bg-gray-200 dark:bg-gray-700" />
@@ -260,10 +547,104 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
                   <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">
                     {t('settings.importDesc') || 'Import lockboxes from a previously exported file.'}
                   </p>
-                  <Button onClick={handleImportClick} disabled={isImporting} variant="secondary" className="w-full justify-center">
-                    <Upload className="w-4 h-4 mr-2" />
-                    {t('header.import')}
-                  </Button>
+                  <div className="flex gap-2">
+                    <Button onClick={handleImportClick} disabled={isImporting} variant="secondary" className="flex-1 justify-center">
+                      <Upload className="w-4 h-4 mr-2" />
+                      {t('header.import')}
+                    </Button>
+                    <Button onClick={() => setShowForeignImport(true)} disabled={isImporting} variant="secondary" className="flex-1 justify-center">
+                      <FileInput className="w-4 h-4 mr-2" />
+                      {t('foreignImport.button')}
+                    </Button>
+                  </div>
+                </div>
+
+                <div className="w-full h-px bg-gray-200 dark:bg-gray-700" />
+
+                <div className="space-y-2">
+                  <h3 className="text-sm font-medium text-gray-900 dark:text-white">{t('settings.backupData')}</h3>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mb-3">{t('settings.backupDesc')}</p>
+                  <div className="flex gap-2">
+                    <Button onClick={handleBackup} disabled={isExporting} variant="secondary" className="flex-1 justify-center">
+                      <HardDriveDownload className="w-4 h-4 mr-2" />
+                      {t('settings.createBackup')}
+                    </Button>
+                    <Button onClick={handleRestoreClick} disabled={isImporting} variant="secondary" className="flex-1 justify-center">
+                      <ArchiveRestore className="w-4 h-4 mr-2" />
+                      {t('settings.restoreBackup')}
+                    </Button>
+                  </div>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">{t('settings.restoreBackupNote')}</p>
+                </div>
+
+                <div className="w-full h-px bg-gray-200 dark:bg-gray-700" />
+
+                <AutoBackupSection />
+
+                <div className="w-full h-px bg-gray-200 dark:bg-gray-700" />
+
+                <TrustedDevicesSection refreshKey={devicesRefresh} />
+
+                <div className="w-full h-px bg-gray-200 dark:bg-gray-700" />
+
+                <div className="space-y-3">
+                  <h3 className="text-sm font-medium text-gray-900 dark:text-white flex items-center gap-2">
+                    <History className="w-4 h-4 text-gray-500" />
+                    {t('settings.historyRetention')}
+                  </h3>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    {t('settings.historyRetentionDesc')}
+                  </p>
+                  {retention && (
+                    <>
+                      <Select
+                        id="history-retention"
+                        options={retentionOptions}
+                        value={retention.retention_days?.toString() ?? ''}
+                        onChange={(e) =>
+                          updateRetention({
+                            ...retention,
+                            retention_days: e.target.value ? Number(e.target.value) : null,
+                          })
+                        }
+                      />
+                      <label className="flex items-start justify-between gap-4 cursor-pointer">
+                        <div className="flex-1">
+                          <div className="text-sm text-gray-900 dark:text-white">
+                            {t('settings.archiveHistory')}
+                          </div>
+                          <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+                            {t('settings.archiveHistoryDesc')}
+                          </p>
+                        </div>
+                        <button
+                          type="button"
+                          role="switch"
+                          aria-checked={retention.archive}
+                          onClick={() => updateRetention({ ...retention, archive: !retention.archive })}
+                          className={clsx(
+                            'relative inline-flex h-6 w-11 shrink-0 items-center rounded-full transition-colors focus:outline-none focus:ring-2 focus:ring-primary-500 focus:ring-offset-2 dark:focus:ring-offset-gray-800',
+                            retention.archive ? 'bg-primary-600' : 'bg-gray-300 dark:bg-gray-600'
+                          )}
+                        >
+                          <span
+                            className={clsx(
+                              'inline-block h-4 w-4 transform rounded-full bg-white transition-transform',
+                              retention.archive ? 'translate-x-6' : 'translate-x-1'
+                            )}
+                          />
+                        </button>
+                      </label>
+                      <Button
+                        onClick={handleCompactNow}
+                        disabled={isCompacting || retention.retention_days === null}
+                        variant="secondary"
+                        className="w-full justify-center"
+                      >
+                        {t('settings.compactNow')}
+                      </Button>
+                    </>
+                  )}
                 </div>
               </div>
             )}
@@ -298,23 +679,124 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
               onChange={(e) => setSourcePassword(e.target.value)}
               placeholder={t('header.importPasswordPlaceholder')}
               className="w-full px-3 py-2 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-primary-500"
-              onKeyDown={(e) => e.key === 'Enter' && sourcePassword && doImport(sourcePassword)}
+              onKeyDown={(e) => e.key === 'Enter' && sourcePassword && submitSourcePassword(sourcePassword)}
               autoFocus
             />
           </div>
           <div className="flex gap-2 justify-end">
             <button
-              onClick={() => doImport(null)}
+              onClick={() => submitSourcePassword(null)}
               className="px-3 py-2 text-sm text-gray-600 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700 rounded-lg transition-colors"
             >
               {t('header.importPasswordSkip')}
             </button>
-            <Button onClick={() => doImport(sourcePassword || null)} disabled={isImporting}>
+            <Button onClick={() => submitSourcePassword(sourcePassword || null)} disabled={isImporting}>
               {t('header.importPasswordConfirm')}
             </Button>
           </div>
         </div>
       </Modal>
+
+      {/* Portable export — passphrase chosen for export, or asked for import; KeePass database password */}
+      <Modal
+        isOpen={passphraseFor !== null}
+        onClose={() => setPassphraseFor(null)}
+        title={
+          passphraseFor === 'export'
+            ? t('settings.portableExport')
+            : passphraseFor === 'database'
+              ? t('foreignImport.databasePasswordTitle')
+              : t('settings.portableImportTitle')
+        }
+        size="sm"
+      >
+        <form
+          className="space-y-4"
+          onSubmit={(e) => {
+            e.preventDefault();
+            if (!passphraseTooShort && !passphraseMismatch) submitPassphrase();
+          }}
+        >
+          <p className="text-sm text-gray-600 dark:text-gray-300">
+            {passphraseFor === 'export'
+              ? t('settings.portableExportBody')
+              : passphraseFor === 'database'
+                ? t('foreignImport.databasePasswordBody')
+                : t('settings.portableImportBody')}
+          </p>
+          <Input
+            type="password"
+            label={passphraseFor === 'database' ? t('foreignImport.databasePassword') : t('settings.passphrase')}
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            helperText={
+              passphraseFor === 'export' ? t('settings.passphraseHelp', { min: MIN_PASSPHRASE_LENGTH }) : undefined
+            }
+            autoFocus
+          />
+          {passphraseFor === 'export' && (
+            <Input
+              type="password"
+              label={t('settings.passphraseConfirm')}
+              value={passphraseConfirm}
+              onChange={(e) => setPassphraseConfirm(e.target.value)}
+              error={passphraseConfirm && passphraseMismatch ? t('settings.passphraseMismatch') : undefined}
+            />
+          )}
+          <div className="flex gap-2 justify-end">
+            <Button type="button" variant="secondary" onClick={() => setPassphraseFor(null)}>
+              {t('common.cancel')}
+            </Button>
+            <Button
+              type="submit"
+              disabled={passphraseTooShort || passphraseMismatch}
+              isLoading={isExporting || isImporting}
+            >
+              {passphraseFor === 'export' ? t('settings.portableExportConfirm') : t('header.importPasswordConfirm')}
+            </Button>
+          </div>
+        </form>
+      </Modal>
+
+      {/* Import — file signed by an unknown device */}
+      <Modal isOpen={untrusted !== null} onClose={() => setUntrusted(null)} title={t('devices.untrustedTitle')} size="sm">
+        <div className="space-y-4">
+          <div className="flex items-start gap-3 p-3 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-700 rounded-lg">
+            <ShieldQuestion className="h-5 w-5 text-amber-600 dark:text-amber-400 shrink-0 mt-0.5" />
+            <p className="text-sm text-amber-800 dark:text-amber-300">{t('devices.untrustedBody')}</p>
+          </div>
+          <p className="text-center font-mono text-sm text-gray-900 dark:text-white">{untrusted?.fingerprint}</p>
+          <Input label={t('devices.name')} value={deviceName} onChange={(e) => setDeviceName(e.target.value)} autoFocus />
+          <div className="flex gap-2 justify-end">
+            <Button variant="secondary" onClick={() => setUntrusted(null)}>
+              {t('common.cancel')}
+            </Button>
+            <Button onClick={handleTrustAndRetry} isLoading={isImporting}>
+              {t('devices.trustAndContinue')}
+            </Button>
+          </div>
+        </div>
+      </Modal>
+
+      <ForeignImportModal
+        isOpen={showForeignImport}
+        onClose={() => setShowForeignImport(false)}
+        onSubmit={handleForeignImport}
+      />
+
+      <ClearExportModal
+        isOpen={showClearExport}
+        onClose={() => setShowClearExport(false)}
+        onSubmit={handleClearExport}
+      />
+
+      <ImportPreviewModal
+        report={importPreview}
+        onClose={handleCancelImport}
+        onConfirm={handleConfirmImport}
+        onStrategiesChange={handleStrategiesChange}
+        isImporting={isImporting}
+      />
     </>
   );
 };
@@ -1,30 +1,44 @@
-import React, { useMemo } from 'react';
-import { Folder, Tag, BarChart2, Hash } from 'lucide-react';
+import React, { useEffect, useState } from 'react';
+import { Folder, Tag, BarChart2, Hash, Settings2 } from 'lucide-react';
 import { clsx } from 'clsx';
 import { useLockboxStore } from '../store';
 import { useTranslation } from '../i18n';
-import { CATEGORIES, parseTags } from '../types';
+import { CategoryIcon } from './ui/CategoryIcon';
+import { TagManagerModal } from './TagManagerModal';
+import { CategoryManagerModal } from './CategoryManagerModal';
+import { FolderTree } from './FolderTree';
 
 interface SidebarProps {
   onStatsClick: () => void;
 }
 
 export const Sidebar: React.FC<SidebarProps> = ({ onStatsClick }) => {
-  const { selectedCategory, setSelectedCategory, selectedTag, setSelectedTag, lockboxes } = useLockboxStore();
-  const { t } = useTranslation();
+  const {
+    selectedCategory,
+    setSelectedCategory,
+    selectedTag,
+    setSelectedTag,
+    lockboxes,
+    tags,
+    fetchTags,
+    categories,
+    fetchCategories,
+  } = useLockboxStore();
+  const { t, categoryLabel } = useTranslation();
+  const [showTagManager, setShowTagManager] = useState(false);
+  const [showCategoryManager, setShowCategoryManager] = useState(false);
 
-  const allTags = useMemo(() => {
-    const counts: Record<string, number> = {};
-    for (const lb of lockboxes) {
-      for (const tag of parseTags(lb.tags)) {
-        counts[tag] = (counts[tag] ?? 0) + 1;
-      }
-    }
-    return Object.entries(counts).sort((a, b) => b[1] - a[1]);
-  }, [lockboxes]);
+  // Usage counts and categories created along with a lockbox come from the
+  // backend; refresh them whenever lockboxes change
+  useEffect(() => {
+    fetchTags();
+    fetchCategories();
+  }, [lockboxes, fetchTags, fetchCategories]);
 
-  const categoryCounts = CATEGORIES.reduce((acc, cat) => {
-    acc[cat] = lockboxes.filter((lb) => lb.category === cat).length;
+  const usedTags = tags.filter((tag) => tag.count > 0);
+
+  const categoryCounts = categories.reduce((acc, cat) => {
+    acc[cat.name] = lockboxes.filter((lb) => lb.category === cat.name).length;
     return acc;
   }, {} as Record<string, number>);
 
@@ -33,9 +47,18 @@ export const Sidebar: React.FC<SidebarProps> = ({ onStatsClick }) => {
   return (
     <aside className="w-64 bg-gray-50 dark:bg-gray-900 border-r border-gray-200 dark:border-gray-700 p-4 flex flex-col">
       <div className="flex-1">
-        <h2 className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider mb-3">
-          {t('sidebar.categories')}
-        </h2>
+        <div className="flex items-center justify-between mb-3">
+          <h2 className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider">
+            {t('sidebar.categories')}
+          </h2>
+          <button
+            onClick={() => setShowCategoryManager(true)}
+            className="p-1 rounded text-gray-400 hover:text-gray-600 dark:hover:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-800 transition-colors"
+            title={t('categories.manageTitle')}
+          >
+            <Settings2 className="h-3.5 w-3.5" />
+          </button>
+        </div>
         <nav className="space-y-1">
           {/* All */}
           <button
@@ -57,24 +80,24 @@ export const Sidebar: React.FC<SidebarProps> = ({ onStatsClick }) => {
           </button>
 
           {/* Categories */}
-          {CATEGORIES.map((category) => (
+          {categories.map((category) => (
             <button
-              key={category}
-              onClick={() => setSelectedCategory(category)}
+              key={category.id}
+              onClick={() => setSelectedCategory(category.name)}
               className={clsx(
                 'w-full flex items-center justify-between px-3 py-2 rounded-lg text-sm transition-colors',
-                selectedCategory === category
+                selectedCategory === category.name
                   ? 'bg-primary-100 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
                   : 'text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-800'
               )}
             >
-              <div className="flex items-center gap-2">
-                <Tag className="h-4 w-4" />
-                <span>{t(`category.${category}` as 'category.Passwords')}</span>
+              <div className="flex items-center gap-2 min-w-0">
+                <CategoryIcon icon={category.icon} color={category.color} className="h-4 w-4 shrink-0" />
+                <span className="truncate">{categoryLabel(category.name)}</span>
               </div>
-              {categoryCounts[category] > 0 && (
+              {categoryCounts[category.name] > 0 && (
                 <span className="text-xs bg-gray-200 dark:bg-gray-700 px-2 py-0.5 rounded-full">
-                  {categoryCounts[category]}
+                  {categoryCounts[category.name]}
                 </span>
               )}
             </button>
@@ -103,12 +126,23 @@ export const Sidebar: React.FC<SidebarProps> = ({ onStatsClick }) => {
         </nav>
       </div>
 
+      <FolderTree />
+
       {/* Tags section */}
-      {allTags.length > 0 && (
+      {tags.length > 0 && (
         <div className="mt-6">
-          <h2 className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider mb-3">
-            {t('tags.filterTitle')}
-          </h2>
+          <div className="flex items-center justify-between mb-3">
+            <h2 className="text-xs font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider">
+              {t('tags.filterTitle')}
+            </h2>
+            <button
+              onClick={() => setShowTagManager(true)}
+              className="p-1 rounded text-gray-400 hover:text-gray-600 dark:hover:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-800 transition-colors"
+              title={t('tags.manageTitle')}
+            >
+              <Settings2 className="h-3.5 w-3.5" />
+            </button>
+          </div>
           <nav className="space-y-1">
             <button
               onClick={() => setSelectedTag(null)}
@@ -124,7 +158,7 @@ export const Sidebar: React.FC<SidebarProps> = ({ onStatsClick }) => {
                 <span>{t('tags.all')}</span>
               </div>
             </button>
-            {allTags.map(([tag, count]) => (
+            {usedTags.map(({ name: tag, count }) => (
               <button
                 key={tag}
                 onClick={() => setSelectedTag(selectedTag === tag ? null : tag)}
@@ -179,6 +213,9 @@ export const Sidebar: React.FC<SidebarProps> = ({ onStatsClick }) => {
           {t('sidebar.viewStats')}
         </button>
       </div>
+
+      <TagManagerModal isOpen={showTagManager} onClose={() => setShowTagManager(false)} />
+      <CategoryManagerModal isOpen={showCategoryManager} onClose={() => setShowCategoryManager(false)} />
     </aside>
   );
 };
@@ -1,63 +1,15 @@
 import React, { useEffect, useState } from 'react';
-import { TrendingUp, Activity, XCircle, Zap, Clock, Shield } from 'lucide-react';
+import { TrendingUp, Activity, XCircle, Zap, Clock, Shield, AlertTriangle } from 'lucide-react';
 import { Modal } from './ui/Modal';
 import { useLockboxStore } from '../store';
 import { useTranslation } from '../i18n';
-import type { AccessLogEntry } from '../types';
+import type { AccessLogVerification, StatsReport } from '../types';
 
 interface StatsModalProps {
   isOpen: boolean;
   onClose: () => void;
 }
 
-interface Stats {
-  totalRequests: number;
-  totalCompleted: number;
-  totalCancelled: number;
-  totalPanic: number;
-  totalExtensions: number;
-  thisMonthRequests: number;
-  thisMonthCompleted: number;
-  thisMonthCancelled: number;
-}
-
-function computeStats(entries: AccessLogEntry[]): Stats {
-  const startOfMonth = new Date();
-  startOfMonth.setDate(1);
-  startOfMonth.setHours(0, 0, 0, 0);
-  const monthTs = startOfMonth.getTime();
-
-  const count = (type: string, fromTs = 0) =>
-    entries.filter((e) => e.event_type === type && e.timestamp >= fromTs).length;
-
-  return {
-    totalRequests: count('unlock_requested'),
-    totalCompleted: count('unlock_completed'),
-    totalCancelled: count('unlock_cancelled'),
-    totalPanic: count('panic_used'),
-    totalExtensions: count('extend_delay'),
-    thisMonthRequests: count('unlock_requested', monthTs),
-    thisMonthCompleted: count('unlock_completed', monthTs),
-    thisMonthCancelled: count('unlock_cancelled', monthTs),
-  };
-}
-
-function computeStreak(_lockboxes: unknown[], entries: AccessLogEntry[]): number | null {
-  const completedTimestamps = entries
-    .filter((e) => e.event_type === 'unlock_completed')
-    .map((e) => e.timestamp)
-    .sort((a, b) => b - a);
-
-  // null means never accessed → perfect streak (∞)
-  if (completedTimestamps.length === 0) return null;
-
-  const lastDate = new Date(completedTimestamps[0]);
-  lastDate.setHours(0, 0, 0, 0);
-  const today = new Date();
-  today.setHours(0, 0, 0, 0);
-  return Math.floor((today.getTime() - lastDate.getTime()) / (1000 * 60 * 60 * 24));
-}
-
 interface StatCardProps {
   icon: React.ReactNode;
   label: string;
@@ -66,6 +18,33 @@ interface StatCardProps {
   color?: string;
 }
 
+interface HeatmapRowProps {
+  label: string;
+  values: number[];
+  columnLabels: string[];
+}
+
+const HeatmapRow: React.FC<HeatmapRowProps> = ({ label, values, columnLabels }) => {
+  const max = Math.max(1, ...values);
+  return (
+    <div>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-2">{label}</p>
+      <div className="flex gap-0.5">
+        {values.map((value, i) => (
+          <div key={i} className="flex-1 min-w-0 text-center">
+            <div
+              className="h-6 rounded-sm bg-primary-500"
+              style={{ opacity: value === 0 ? 0.08 : 0.2 + (0.8 * value) / max }}
+              title={`${columnLabels[i]}: ${value}`}
+            />
+            <span className="block text-[10px] text-gray-400 mt-1 truncate">{columnLabels[i]}</span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+};
+
 const StatCard: React.FC<StatCardProps> = ({ icon, label, value, sub, color = 'text-primary-600 dark:text-primary-400' }) => (
   <div className="p-4 bg-gray-50 dark:bg-gray-700/50 rounded-xl">
     <div className="flex items-center gap-2 mb-2">
@@ -78,43 +57,64 @@ const StatCard: React.FC<StatCardProps> = ({ icon, label, value, sub, color = 't
 );
 
 export const StatsModal: React.FC<StatsModalProps> = ({ isOpen, onClose }) => {
-  const { t } = useTranslation();
+  const { t, formatDelay, locale } = useTranslation();
   const lockboxes = useLockboxStore((s) => s.lockboxes);
-  const getGlobalAccessLog = useLockboxStore((s) => s.getGlobalAccessLog);
-  const [entries, setEntries] = useState<AccessLogEntry[]>([]);
+  const getStatistics = useLockboxStore((s) => s.getStatistics);
+  const verifyAccessLog = useLockboxStore((s) => s.verifyAccessLog);
+  const [report, setReport] = useState<StatsReport | null>(null);
+  const [verification, setVerification] = useState<AccessLogVerification | null>(null);
   const [isLoading, setIsLoading] = useState(false);
 
   useEffect(() => {
     if (!isOpen) return;
     setIsLoading(true);
-    getGlobalAccessLog().then((data) => {
-      setEntries(data);
+    Promise.all([getStatistics(), verifyAccessLog()]).then(([stats, check]) => {
+      setReport(stats);
+      setVerification(check);
       setIsLoading(false);
     });
-  }, [isOpen, getGlobalAccessLog]);
+  }, [isOpen, getStatistics, verifyAccessLog]);
 
-  const stats = computeStats(entries);
-  const streakDays = computeStreak(lockboxes, entries);
+  // Per-lockbox entries exist as soon as anything was logged
+  const stats = report && report.lockboxes.length > 0 ? report.global : null;
+  const streakDays = stats?.current_streak_days ?? null;
 
   const successRate =
-    stats.totalRequests > 0
-      ? Math.round((stats.totalCompleted / stats.totalRequests) * 100)
+    stats && stats.all_time.requests > 0
+      ? Math.round((stats.all_time.completions / stats.all_time.requests) * 100)
       : 0;
 
-  const cancelRate =
-    stats.totalRequests > 0
-      ? Math.round((stats.totalCancelled / stats.totalRequests) * 100)
-      : 0;
+  const hourLabels = Array.from({ length: 24 }, (_, hour) => (hour % 6 === 0 ? String(hour) : ''));
+  // 2024-01-01 was a Monday, matching the backend's Monday-first order
+  const weekdayFormat = new Intl.DateTimeFormat(locale, { weekday: 'short' });
+  const weekdayLabels = Array.from({ length: 7 }, (_, day) => weekdayFormat.format(new Date(2024, 0, 1 + day)));
 
-  const hasData = entries.length > 0;
+  const cancelRate =
+    stats?.cancellation_ratio != null ? Math.round(stats.cancellation_ratio * 100) : 0;
 
   return (
     <Modal isOpen={isOpen} onClose={onClose} title={t('stats.title')} size="xl">
+      {!isLoading && verification && !verification.valid && (
+        <div className="flex gap-2 p-3 mb-6 rounded-xl bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-sm text-red-700 dark:text-red-300">
+          <AlertTriangle className="h-4 w-4 flex-shrink-0 mt-0.5" />
+          <div>
+            <p>{t('stats.logTampered')}</p>
+            {(verification.truncated_start || verification.truncated_end) && (
+              <p className="text-xs mt-1">{t('stats.logTruncated')}</p>
+            )}
+            {verification.first_broken_id !== null && (
+              <p className="text-xs mt-1">
+                {t('stats.logBrokenAt', { id: verification.first_broken_id })}
+              </p>
+            )}
+          </div>
+        </div>
+      )}
       {isLoading ? (
         <div className="flex items-center justify-center py-12">
           <div className="animate-spin rounded-full h-8 w-8 border-b-2 border-primary-500" />
         </div>
-      ) : !hasData ? (
+      ) : !stats ? (
         <div className="text-center py-12 text-gray-400 dark:text-gray-500">
           <Activity className="h-12 w-12 mx-auto mb-3 opacity-40" />
           <p>{t('stats.noData')}</p>
@@ -130,32 +130,32 @@ export const StatsModal: React.FC<StatsModalProps> = ({ isOpen, onClose }) => {
               <StatCard
                 icon={<TrendingUp className="h-4 w-4" />}
                 label={t('stats.totalUnlockRequests')}
-                value={stats.totalRequests}
+                value={stats.all_time.requests}
               />
               <StatCard
                 icon={<Shield className="h-4 w-4" />}
                 label={t('stats.totalUnlockCompleted')}
-                value={stats.totalCompleted}
+                value={stats.all_time.completions}
                 sub={`${successRate}% ${t('stats.successRate')}`}
                 color="text-green-600 dark:text-green-400"
               />
               <StatCard
                 icon={<XCircle className="h-4 w-4" />}
                 label={t('stats.totalCancellations')}
-                value={stats.totalCancelled}
+                value={stats.all_time.cancellations}
                 sub={`${cancelRate}% ${t('stats.cancelRate')}`}
                 color="text-red-500 dark:text-red-400"
               />
               <StatCard
                 icon={<Zap className="h-4 w-4" />}
                 label={t('stats.totalPanicUses')}
-                value={stats.totalPanic}
+                value={stats.all_time.panic_uses}
                 color="text-orange-500 dark:text-orange-400"
               />
               <StatCard
                 icon={<Clock className="h-4 w-4" />}
                 label={t('stats.totalExtensions')}
-                value={stats.totalExtensions}
+                value={stats.all_time.extensions}
                 color="text-blue-500 dark:text-blue-400"
               />
               <StatCard
@@ -175,18 +175,18 @@ export const StatsModal: React.FC<StatsModalProps> = ({ isOpen, onClose }) => {
               <StatCard
                 icon={<TrendingUp className="h-4 w-4" />}
                 label={t('stats.totalUnlockRequests')}
-                value={stats.thisMonthRequests}
+                value={stats.this_month.requests}
               />
               <StatCard
                 icon={<Shield className="h-4 w-4" />}
                 label={t('stats.totalUnlockCompleted')}
-                value={stats.thisMonthCompleted}
+                value={stats.this_month.completions}
                 color="text-green-600 dark:text-green-400"
               />
               <StatCard
                 icon={<XCircle className="h-4 w-4" />}
                 label={t('stats.totalCancellations')}
-                value={stats.thisMonthCancelled}
+                value={stats.this_month.cancellations}
                 color="text-red-500 dark:text-red-400"
               />
             </div>
@@ -204,6 +204,27 @@ export const StatsModal: React.FC<StatsModalProps> = ({ isOpen, onClose }) => {
                   ? t('stats.streakDay')
                   : t('stats.streakDays', { days: streakDays })}
             </p>
+            <p className="text-xs text-primary-700/70 dark:text-primary-300/70 mt-1">
+              {t('stats.longestStreak', { days: stats.longest_streak_days })}
+            </p>
+          </div>
+
+          {/* Patterns */}
+          <div>
+            <h3 className="text-sm font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wider mb-3">
+              {t('stats.patterns')}
+            </h3>
+            <div className="space-y-4 p-4 bg-gray-50 dark:bg-gray-700/50 rounded-xl">
+              <HeatmapRow label={t('stats.requestsByHour')} values={stats.requests_by_hour} columnLabels={hourLabels} />
+              <HeatmapRow label={t('stats.requestsByWeekday')} values={stats.requests_by_weekday} columnLabels={weekdayLabels} />
+              {stats.average_seconds_to_cancel !== null && (
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  {t('stats.averageTimeToCancel', {
+                    delay: formatDelay(Math.round(stats.average_seconds_to_cancel)),
+                  })}
+                </p>
+              )}
+            </div>
           </div>
         </div>
       )}
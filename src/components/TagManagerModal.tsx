@@ -0,0 +1,124 @@
+import React, { useEffect, useState } from 'react';
+import { toast } from 'react-toastify';
+import { Hash, Trash2 } from 'lucide-react';
+import { Modal } from './ui/Modal';
+import { Button } from './ui/Button';
+import { Input } from './ui/Input';
+import { useLockboxStore, describeError } from '../store';
+import { useTranslation } from '../i18n';
+import { isLockboxError, normalizeTag } from '../types';
+
+interface TagManagerModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+/** Pending action awaiting a second click, so nothing is merged or deleted by accident */
+type Pending =
+  | { kind: 'merge'; source: string; target: string }
+  | { kind: 'delete'; name: string };
+
+export const TagManagerModal: React.FC<TagManagerModalProps> = ({ isOpen, onClose }) => {
+  const { tags, fetchTags, renameTag, mergeTags, deleteTag } = useLockboxStore();
+  const { t } = useTranslation();
+  const [drafts, setDrafts] = useState<Record<string, string>>({});
+  const [pending, setPending] = useState<Pending | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      fetchTags();
+      setDrafts({});
+      setPending(null);
+    }
+  }, [isOpen, fetchTags]);
+
+  const handleRename = async (name: string) => {
+    const newName = normalizeTag(drafts[name] ?? name);
+    setDrafts((d) => ({ ...d, [name]: newName }));
+    if (!newName || newName === name) return;
+    try {
+      await renameTag(name, newName);
+    } catch (err) {
+      if (isLockboxError(err) && err.code === 'tag_exists') {
+        setPending({ kind: 'merge', source: name, target: newName });
+      } else {
+        toast.error(describeError(err));
+      }
+    }
+  };
+
+  const handleConfirm = async () => {
+    if (!pending) return;
+    try {
+      if (pending.kind === 'merge') {
+        await mergeTags([pending.source], pending.target);
+      } else {
+        await deleteTag(pending.name);
+      }
+      setPending(null);
+    } catch (err) {
+      toast.error(describeError(err));
+    }
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title={t('tags.manageTitle')} size="md">
+      <div className="space-y-4">
+        <p className="text-sm text-gray-600 dark:text-gray-400">{t('tags.manageDesc')}</p>
+
+        {tags.length === 0 ? (
+          <p className="text-sm text-gray-500 dark:text-gray-400">{t('tags.noTags')}</p>
+        ) : (
+          <ul className="space-y-2 max-h-80 overflow-y-auto">
+            {tags.map((tag) => (
+              <li key={tag.id} className="flex items-center gap-2">
+                <Hash className="h-4 w-4 text-gray-400 shrink-0" />
+                <Input
+                  value={drafts[tag.name] ?? tag.name}
+                  onChange={(e) => setDrafts((d) => ({ ...d, [tag.name]: e.target.value }))}
+                  onBlur={() => handleRename(tag.name)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter') e.currentTarget.blur();
+                  }}
+                  aria-label={t('tags.rename')}
+                />
+                <span className="text-xs bg-gray-200 dark:bg-gray-700 px-2 py-0.5 rounded-full whitespace-nowrap">
+                  {tag.count > 0 ? t('tags.usage', { count: tag.count }) : t('tags.unused')}
+                </span>
+                <button
+                  type="button"
+                  onClick={() => setPending({ kind: 'delete', name: tag.name })}
+                  className="p-1.5 rounded-lg text-gray-400 hover:text-red-600 hover:bg-red-50 dark:hover:bg-red-900/20 transition-colors"
+                  title={t('tags.delete')}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </button>
+              </li>
+            ))}
+          </ul>
+        )}
+
+        {pending && (
+          <div className="p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg space-y-3">
+            <p className="text-sm text-amber-800 dark:text-amber-300">
+              {pending.kind === 'merge'
+                ? t('tags.mergeConfirm', { source: pending.source, target: pending.target })
+                : t('tags.deleteConfirm', {
+                    name: pending.name,
+                    count: tags.find((tag) => tag.name === pending.name)?.count ?? 0,
+                  })}
+            </p>
+            <div className="flex justify-end gap-2">
+              <Button variant="secondary" size="sm" onClick={() => setPending(null)}>
+                {t('common.cancel')}
+              </Button>
+              <Button variant={pending.kind === 'delete' ? 'danger' : 'primary'} size="sm" onClick={handleConfirm}>
+                {pending.kind === 'merge' ? t('tags.merge') : t('tags.delete')}
+              </Button>
+            </div>
+          </div>
+        )}
+      </div>
+    </Modal>
+  );
+};
@@ -0,0 +1,149 @@
+import React, { useEffect, useState } from 'react';
+import { toast } from 'react-toastify';
+import { AlertTriangle, Copy, Fingerprint, KeyRound, Plus, Trash2 } from 'lucide-react';
+import { Button } from './ui/Button';
+import { Input } from './ui/Input';
+import { useLockboxStore, describeError } from '../store';
+import { useTranslation } from '../i18n';
+import { isLockboxError } from '../types';
+import type { DeviceIdentity, TrustedDevice } from '../types';
+
+interface TrustedDevicesSectionProps {
+  /** Bumped by the parent when a device was trusted elsewhere (import flow) */
+  refreshKey?: number;
+}
+
+/** This device's signing key, and the devices whose exports are accepted */
+export const TrustedDevicesSection: React.FC<TrustedDevicesSectionProps> = ({ refreshKey = 0 }) => {
+  const { getDeviceIdentity, resetDeviceKey, listTrustedDevices, trustDevice, removeTrustedDevice } = useLockboxStore();
+  const { t } = useTranslation();
+  const [identity, setIdentity] = useState<DeviceIdentity | null>(null);
+  const [devices, setDevices] = useState<TrustedDevice[]>([]);
+  const [name, setName] = useState('');
+  const [publicKey, setPublicKey] = useState('');
+  // The signing key no longer unseals and has to be replaced
+  const [unreadable, setUnreadable] = useState(false);
+
+  useEffect(() => {
+    getDeviceIdentity()
+      .then((identity) => {
+        setIdentity(identity);
+        setUnreadable(!identity.can_sign);
+      })
+      .catch((err) => {
+        if (isLockboxError(err) && err.code === 'device_key_unreadable') {
+          setUnreadable(true);
+        } else {
+          toast.error(describeError(err));
+        }
+      });
+    listTrustedDevices().then(setDevices).catch((err) => toast.error(describeError(err)));
+  }, [getDeviceIdentity, listTrustedDevices, refreshKey]);
+
+  const handleCopy = async () => {
+    if (!identity) return;
+    await navigator.clipboard.writeText(identity.public_key);
+    toast.success(t('devices.copied'));
+  };
+
+  const handleResetKey = async () => {
+    try {
+      setIdentity(await resetDeviceKey());
+      setUnreadable(false);
+      toast.success(t('devices.keyReplaced'));
+    } catch (err) {
+      toast.error(describeError(err));
+    }
+  };
+
+  const handleTrust = async () => {
+    try {
+      setDevices(await trustDevice(publicKey.trim(), name));
+      setName('');
+      setPublicKey('');
+    } catch (err) {
+      toast.error(describeError(err));
+    }
+  };
+
+  const handleRemove = async (device: TrustedDevice) => {
+    try {
+      setDevices(await removeTrustedDevice(device.public_key));
+    } catch (err) {
+      toast.error(describeError(err));
+    }
+  };
+
+  return (
+    <div className="space-y-3">
+      <h3 className="text-sm font-medium text-gray-900 dark:text-white flex items-center gap-2">
+        <Fingerprint className="w-4 h-4 text-gray-500" />
+        {t('devices.title')}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400">{t('devices.description')}</p>
+
+      {identity && (
+        <div className="flex items-center gap-2 p-3 bg-gray-50 dark:bg-gray-800 rounded-lg">
+          <div className="flex-1 min-w-0">
+            <p className="text-xs text-gray-500 dark:text-gray-400">{t('devices.thisDevice')}</p>
+            <p className="text-sm font-mono text-gray-900 dark:text-white">{identity.fingerprint}</p>
+          </div>
+          <Button variant="secondary" size="sm" onClick={handleCopy}>
+            <Copy className="w-4 h-4 mr-2" />
+            {t('devices.copyKey')}
+          </Button>
+        </div>
+      )}
+
+      {unreadable && (
+        <div className="space-y-2 p-3 bg-amber-50 dark:bg-amber-900/20 rounded-lg border border-amber-200 dark:border-amber-800">
+          <div className="flex items-start gap-2">
+            <AlertTriangle className="h-4 w-4 text-amber-600 dark:text-amber-400 shrink-0 mt-0.5" />
+            <p className="text-xs text-amber-700 dark:text-amber-300">{t('devices.keyUnreadable')}</p>
+          </div>
+          <Button variant="secondary" size="sm" onClick={handleResetKey}>
+            <KeyRound className="w-4 h-4 mr-2" />
+            {t('devices.replaceKey')}
+          </Button>
+        </div>
+      )}
+
+      {devices.length === 0 ? (
+        <p className="text-xs text-gray-500 dark:text-gray-400">{t('devices.empty')}</p>
+      ) : (
+        <ul className="divide-y divide-gray-100 dark:divide-gray-800">
+          {devices.map((device) => (
+            <li key={device.public_key} className="flex items-center gap-3 py-2">
+              <div className="flex-1 min-w-0">
+                <p className="text-sm text-gray-900 dark:text-white truncate">{device.name}</p>
+                <p className="text-xs font-mono text-gray-500 dark:text-gray-400">{device.fingerprint}</p>
+              </div>
+              <button
+                onClick={() => handleRemove(device)}
+                className="p-1 rounded text-gray-400 hover:text-red-600 dark:hover:text-red-400"
+                title={t('devices.remove')}
+              >
+                <Trash2 className="h-4 w-4" />
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div className="space-y-2">
+        <Input label={t('devices.name')} value={name} onChange={(e) => setName(e.target.value)} />
+        <div className="flex items-end gap-2">
+          <Input
+            label={t('devices.publicKey')}
+            value={publicKey}
+            onChange={(e) => setPublicKey(e.target.value)}
+            className="font-mono"
+          />
+          <Button variant="secondary" onClick={handleTrust} disabled={!publicKey.trim()}>
+            <Plus className="h-4 w-4" />
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+};
@@ -12,4 +12,14 @@ export { ExtendDelayModal } from './ExtendDelayModal';
 export { AboutModal } from './AboutModal';
 export { DocModal } from './DocModal';
 export { StatsModal } from './StatsModal';
+export { TagManagerModal } from './TagManagerModal';
+export { CategoryManagerModal } from './CategoryManagerModal';
+export { FolderTree } from './FolderTree';
+export { FolderModal } from './FolderModal';
+export { ImportPreviewModal } from './ImportPreviewModal';
+export { TrustedDevicesSection } from './TrustedDevicesSection';
+export { ExportSelectionSection } from './ExportSelectionSection';
+export { AutoBackupSection } from './AutoBackupSection';
+export { ForeignImportModal } from './ForeignImportModal';
+export { ClearExportModal } from './ClearExportModal';
 export { PasswordGeneratorModal } from './PasswordGeneratorModal';
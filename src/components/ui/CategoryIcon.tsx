@@ -0,0 +1,47 @@
+import React from 'react';
+import {
+  Book,
+  Briefcase,
+  Folder,
+  Gamepad2,
+  Heart,
+  Home,
+  Key,
+  Shield,
+  ShoppingCart,
+  Star,
+  Tag,
+  User,
+  Users,
+  Wallet,
+  type LucideIcon,
+} from 'lucide-react';
+
+/** Icons a category can use, by the identifier stored in the backend */
+export const CATEGORY_ICONS: Record<string, LucideIcon> = {
+  tag: Tag,
+  key: Key,
+  wallet: Wallet,
+  user: User,
+  briefcase: Briefcase,
+  users: Users,
+  gamepad: Gamepad2,
+  folder: Folder,
+  heart: Heart,
+  star: Star,
+  shield: Shield,
+  book: Book,
+  home: Home,
+  cart: ShoppingCart,
+};
+
+interface CategoryIconProps {
+  icon: string | null | undefined;
+  color?: string | null;
+  className?: string;
+}
+
+export const CategoryIcon: React.FC<CategoryIconProps> = ({ icon, color, className = 'h-4 w-4' }) => {
+  const Icon = (icon && CATEGORY_ICONS[icon]) || Tag;
+  return <Icon className={className} style={color ? { color } : undefined} />;
+};
@@ -4,12 +4,14 @@ import { clsx } from 'clsx';
 interface SelectProps extends React.SelectHTMLAttributes<HTMLSelectElement> {
   label?: string;
   error?: string;
+  helperText?: string;
   options: { value: string; label: string }[];
 }
 
 export const Select: React.FC<SelectProps> = ({
   label,
   error,
+  helperText,
   options,
   className,
   id,
@@ -52,6 +54,9 @@ export const Select: React.FC<SelectProps> = ({
         ))}
       </select>
       {error && <p className="mt-1 text-sm text-red-500">{error}</p>}
+      {helperText && !error && (
+        <p className="mt-1 text-sm text-gray-500 dark:text-gray-400">{helperText}</p>
+      )}
     </div>
   );
 };
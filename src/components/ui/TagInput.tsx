@@ -1,6 +1,7 @@
 import React, { useState, useRef, KeyboardEvent } from 'react';
 import { X, Tag } from 'lucide-react';
 import { clsx } from 'clsx';
+import { normalizeTag } from '../../types';
 
 interface TagInputProps {
   tags: string[];
@@ -23,9 +24,6 @@ export const TagInput: React.FC<TagInputProps> = ({
   const [isFocused, setIsFocused] = useState(false);
   const inputRef = useRef<HTMLInputElement>(null);
 
-  const normalizeTag = (raw: string) =>
-    raw.trim().toLowerCase().replace(/\s+/g, '-').replace(/[^a-z0-9-_]/g, '');
-
   const addTag = (raw: string) => {
     const tag = normalizeTag(raw);
     if (!tag || tags.includes(tag) || tags.length >= maxTags) return;
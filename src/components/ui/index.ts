@@ -5,3 +5,4 @@ export { Modal, ConfirmModal } from './Modal';
 export { Tooltip } from './Tooltip';
 export { TagInput } from './TagInput';
 export { DateTimePicker } from './DateTimePicker';
+export { CategoryIcon, CATEGORY_ICONS } from './CategoryIcon';
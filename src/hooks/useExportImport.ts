@@ -1,16 +1,64 @@
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { save, open } from '@tauri-apps/plugin-dialog';
-import { writeTextFile, readTextFile } from '@tauri-apps/plugin-fs';
+import { writeTextFile, readTextFile, readFile } from '@tauri-apps/plugin-fs';
 import { useLockboxStore } from '../store';
+import type {
+  ClearExport,
+  ClearFormat,
+  ForeignFormat,
+  ForeignImport,
+  ImportReport,
+  ImportStrategies,
+  LockboxFilter,
+  RestoreReport,
+} from '../types';
+
+/** File dialog filter for each format of another password manager */
+const FOREIGN_FILTERS: Record<ForeignFormat, { name: string; extensions: string[] }> = {
+  kdbx: { name: 'KeePass Database', extensions: ['kdbx'] },
+  keepass_xml: { name: 'KeePass XML', extensions: ['xml'] },
+  bitwarden: { name: 'Bitwarden JSON', extensions: ['json'] },
+  csv: { name: 'CSV', extensions: ['csv'] },
+};
+
+/** File dialog filter for each format of an export in clear */
+const CLEAR_FILTERS: Record<ClearFormat, { name: string; extension: string }> = {
+  keepass_xml: { name: 'KeePass XML', extension: 'xml' },
+  csv: { name: 'CSV', extension: 'csv' },
+};
+
+/** Base64 of a binary file, sent as text to the backend */
+const toBase64 = (bytes: Uint8Array): string => {
+  let binary = '';
+  for (let i = 0; i < bytes.length; i += 0x8000) {
+    binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+  }
+  return btoa(binary);
+};
 
 export function useExportImport() {
   const [isExporting, setIsExporting] = useState(false);
   const [isImporting, setIsImporting] = useState(false);
-  const [error, setError] = useState<string | null>(null);
+  const [error, setError] = useState<unknown>(null);
+  // File checked by the last preview, waiting for confirmation
+  const [pending, setPending] = useState<{
+    data: string;
+    sourcePassword: string | null;
+    passphrase: string | null;
+    strategies: ImportStrategies | null;
+    foreign: ForeignImport | null;
+  } | null>(null);
+  // File of the last preview that failed, kept for `retryPreview`
+  const [failed, setFailed] = useState<{
+    data: string;
+    sourcePassword: string | null;
+    foreign: ForeignImport | null;
+  } | null>(null);
   const fetchLockboxes = useLockboxStore((state) => state.fetchLockboxes);
 
-  const exportLockboxes = async (): Promise<boolean> => {
+  /** Exports the lockboxes matching `selection`, or every lockbox */
+  const exportLockboxes = async (selection: LockboxFilter | null = null): Promise<boolean> => {
     setIsExporting(true);
     setError(null);
 
@@ -27,21 +75,101 @@ export function useExportImport() {
         return false;
       }
 
-      const data = await invoke<string>('export_lockboxes');
+      const data = await invoke<string>('export_lockboxes', { selection });
       await writeTextFile(filePath, data);
 
       setIsExporting(false);
       return true;
     } catch (err) {
-      setError(String(err));
+      setError(err);
       setIsExporting(false);
       return false;
     }
   };
 
-  const importLockboxes = async (sourcePassword: string | null = null): Promise<string[]> => {
+  /**
+   * Writes a portable export: the unlocked lockboxes matching `selection`, re-encrypted under `passphrase`
+   * instead of the master password. Nothing is written when none is unlocked. Resolves to null when
+   * cancelled or failed.
+   */
+  const exportPortable = async (passphrase: string, selection: LockboxFilter | null = null): Promise<ClearExport | null> => {
+    setIsExporting(true);
+    setError(null);
+
+    try {
+      // Asked first: every exported lockbox is logged, so the file must be written
+      const filePath = await save({
+        defaultPath: `lockbox-portable-${Date.now()}.json`,
+        filters: [{ name: 'Lockbox Portable Export', extensions: ['json'] }],
+      });
+
+      if (!filePath) {
+        setIsExporting(false);
+        return null;
+      }
+
+      const result = await invoke<ClearExport>('export_portable', { passphrase, selection });
+      if (result.exported > 0) {
+        await writeTextFile(filePath, result.data);
+      }
+      // Exports count as views and may have relocked boxes
+      await fetchLockboxes();
+
+      setIsExporting(false);
+      return result;
+    } catch (err) {
+      setError(err);
+      setIsExporting(false);
+      return null;
+    }
+  };
+
+  /**
+   * Writes the unlocked lockboxes matching `selection` in clear, for another password manager.
+   * Nothing is written when none is unlocked. Resolves to null when cancelled or failed.
+   */
+  const exportClear = async (format: ClearFormat, selection: LockboxFilter | null = null): Promise<ClearExport | null> => {
+    setIsExporting(true);
+    setError(null);
+
+    try {
+      // Asked first: every exported lockbox is logged, so the file must be written
+      const { name, extension } = CLEAR_FILTERS[format];
+      const filePath = await save({
+        defaultPath: `lockbox-clear-${Date.now()}.${extension}`,
+        filters: [{ name, extensions: [extension] }],
+      });
+
+      if (!filePath) {
+        setIsExporting(false);
+        return null;
+      }
+
+      const result = await invoke<ClearExport>('export_clear', { format, selection });
+      if (result.exported > 0) {
+        await writeTextFile(filePath, result.data);
+      }
+      // Exports count as views and may have relocked boxes
+      await fetchLockboxes();
+
+      setIsExporting(false);
+      return result;
+    } catch (err) {
+      setError(err);
+      setIsExporting(false);
+      return null;
+    }
+  };
+
+  /**
+   * Asks for a file and checks it with `preview_import`; nothing is written.
+   * The file is kept for `confirmImport`. Resolves to null when cancelled or failed.
+   */
+  const previewImport = async (sourcePassword: string | null = null): Promise<ImportReport | null> => {
     setIsImporting(true);
     setError(null);
+    setPending(null);
+    setFailed(null);
 
     try {
       const filePath = await open({
@@ -53,29 +181,185 @@ export function useExportImport() {
 
       if (!filePath || typeof filePath !== 'string') {
         setIsImporting(false);
-        return [];
+        return null;
       }
 
       const data = await readTextFile(filePath);
-      const imported = await invoke<string[]>('import_lockboxes', {
+      return await runPreview(data, sourcePassword || null, null, null);
+    } catch (err) {
+      setError(err);
+      setIsImporting(false);
+      return null;
+    }
+  };
+
+  /**
+   * Asks for a file of another password manager and checks it like `previewImport`.
+   * A KDBX database fails with `passphrase_required` until `retryPreview` gets its master password.
+   */
+  const previewForeignImport = async (foreign: ForeignImport): Promise<ImportReport | null> => {
+    setIsImporting(true);
+    setError(null);
+    setPending(null);
+    setFailed(null);
+
+    try {
+      const filePath = await open({ filters: [FOREIGN_FILTERS[foreign.format]], multiple: false });
+
+      if (!filePath || typeof filePath !== 'string') {
+        setIsImporting(false);
+        return null;
+      }
+
+      const data = foreign.format === 'kdbx' ? toBase64(await readFile(filePath)) : await readTextFile(filePath);
+      return await runPreview(data, null, null, foreign);
+    } catch (err) {
+      setError(err);
+      setIsImporting(false);
+      return null;
+    }
+  };
+
+  const runPreview = async (
+    data: string,
+    sourcePassword: string | null,
+    passphrase: string | null,
+    foreign: ForeignImport | null
+  ): Promise<ImportReport | null> => {
+    setIsImporting(true);
+    setError(null);
+
+    try {
+      const report = await invoke<ImportReport>('preview_import', { data, sourcePassword, passphrase, foreign });
+      setPending({ data, sourcePassword, passphrase, strategies: null, foreign });
+      setFailed(null);
+      setIsImporting(false);
+      return report;
+    } catch (err) {
+      setFailed({ data, sourcePassword, foreign });
+      setError(err);
+      setIsImporting(false);
+      return null;
+    }
+  };
+
+  /**
+   * Checks the file of the last failed preview again, e.g. once its device
+   * is trusted, or with the passphrase of a portable file or KeePass database.
+   */
+  const retryPreview = async (passphrase: string | null = null): Promise<ImportReport | null> =>
+    failed ? runPreview(failed.data, failed.sourcePassword, passphrase, failed.foreign) : null;
+
+  /** Checks the pending file again with other conflict strategies */
+  const changeStrategies = async (strategies: ImportStrategies): Promise<ImportReport | null> => {
+    if (!pending) return null;
+    setError(null);
+
+    try {
+      const next = { ...pending, strategies };
+      const report = await invoke<ImportReport>('preview_import', next);
+      setPending(next);
+      return report;
+    } catch (err) {
+      setError(err);
+      return null;
+    }
+  };
+
+  /** Imports the previewed file, carrying out the action reported for each lockbox */
+  const confirmImport = async (): Promise<ImportReport | null> => {
+    if (!pending) return null;
+    setIsImporting(true);
+    setError(null);
+
+    try {
+      const report = await invoke<ImportReport>('import_lockboxes', pending);
+      await fetchLockboxes();
+      setPending(null);
+      setIsImporting(false);
+      return report;
+    } catch (err) {
+      setError(err);
+      setIsImporting(false);
+      return null;
+    }
+  };
+
+  /** Writes a full backup of the vault: state, schedules, history and settings */
+  const exportBackup = async (): Promise<boolean> => {
+    setIsExporting(true);
+    setError(null);
+
+    try {
+      const filePath = await save({
+        defaultPath: `lockbox-backup-${Date.now()}.json`,
+        filters: [{ name: 'Lockbox Backup', extensions: ['json'] }],
+      });
+
+      if (!filePath) {
+        setIsExporting(false);
+        return false;
+      }
+
+      const data = await invoke<string>('export_backup');
+      await writeTextFile(filePath, data);
+
+      setIsExporting(false);
+      return true;
+    } catch (err) {
+      setError(err);
+      setIsExporting(false);
+      return false;
+    }
+  };
+
+  /**
+   * Restores a full backup into this (empty) vault. Resolves to the restore
+   * report, or null when cancelled or failed.
+   */
+  const restoreBackup = async (sourcePassword: string | null = null): Promise<RestoreReport | null> => {
+    setIsImporting(true);
+    setError(null);
+
+    try {
+      const filePath = await open({
+        filters: [{ name: 'Lockbox Backup', extensions: ['json'] }],
+        multiple: false,
+      });
+
+      if (!filePath || typeof filePath !== 'string') {
+        setIsImporting(false);
+        return null;
+      }
+
+      const data = await readTextFile(filePath);
+      const report = await invoke<RestoreReport>('import_backup', {
         data,
         sourcePassword: sourcePassword || null,
       });
-
       await fetchLockboxes();
 
       setIsImporting(false);
-      return imported;
+      return report;
     } catch (err) {
-      setError(String(err));
+      setError(err);
       setIsImporting(false);
-      return [];
+      return null;
     }
   };
 
   return {
     exportLockboxes,
-    importLockboxes,
+    exportPortable,
+    exportClear,
+    exportBackup,
+    restoreBackup,
+    previewImport,
+    previewForeignImport,
+    retryPreview,
+    changeStrategies,
+    confirmImport,
+    cancelImport: () => setPending(null),
     isExporting,
     isImporting,
     error,
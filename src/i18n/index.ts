@@ -51,5 +51,12 @@ export function useTranslation() {
     [t]
   );
 
-  return { t, formatDelay, locale, setLocale };
+  /** Built-in category names are translated; user-defined ones are shown as is */
+  const categoryLabel = useCallback(
+    (name: string): string =>
+      getNested(translations[locale] as Record<string, unknown>, `category.${name}`) ?? name,
+    [locale]
+  );
+
+  return { t, formatDelay, categoryLabel, locale, setLocale };
 }
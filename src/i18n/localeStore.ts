@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
+import { invoke } from '@tauri-apps/api/core';
 import type { Locale } from './translations';
 
 interface LocaleState {
@@ -12,12 +13,27 @@ const getDefaultLocale = (): Locale => {
   return 'en';
 };
 
+/** Keeps the backend message catalog (errors, dialogs, notifications) in the UI language */
+const syncBackendLocale = (locale: Locale) => {
+  invoke('set_locale', { locale }).catch((err) =>
+    console.error('Failed to sync locale with backend:', err)
+  );
+};
+
 export const useLocaleStore = create<LocaleState>()(
   persist(
     (set) => ({
       locale: getDefaultLocale(),
-      setLocale: (locale) => set({ locale }),
+      setLocale: (locale) => {
+        set({ locale });
+        syncBackendLocale(locale);
+      },
     }),
-    { name: 'lockbox-locale' }
+    {
+      name: 'lockbox-locale',
+      onRehydrateStorage: () => (state) => {
+        if (state) syncBackendLocale(state.locale);
+      },
+    }
   )
 );
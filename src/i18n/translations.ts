@@ -234,6 +234,14 @@ export const translations = {
       penaltyDelay: "Penalty delay",
       penaltyDelayHint:
         "Time added to the unlock delay each time the countdown is cancelled",
+      // View limit
+      viewLimitSection: "View limit",
+      viewLimitSectionHint:
+        "Relock automatically after the content has been viewed a number of times",
+      viewLimitEnabled: "Limit views per unlock",
+      viewLimitCount: "Maximum views",
+      viewLimitCountHint:
+        "The lockbox relocks as soon as the content has been displayed this many times in one unlock window",
       // Panic code
       panicSection: "Emergency access code",
       panicSectionHint: "Single-use code to bypass the delay in an emergency",
@@ -306,6 +314,11 @@ export const translations = {
       scheduled_unlock_completed: "Scheduled unlock triggered",
       auto_relocked: "Auto-relocked",
       field_updated: "Settings updated",
+      content_viewed: "Content viewed",
+      content_copied: "Content copied",
+      content_exported: "Content exported in clear",
+      view_limit_relocked: "Relocked after view limit",
+      snapshot_restored: "Restored from an automatic backup",
     },
     stats: {
       title: "Self-control statistics",
@@ -324,6 +337,15 @@ export const translations = {
       streakDay: "1 day without access",
       streakDays: "{{days}} days without access",
       streakNever: "∞ — Never accessed",
+      longestStreak: "Longest streak: {{days}} days",
+      patterns: "Patterns",
+      requestsByHour: "Unlock requests by hour",
+      requestsByWeekday: "Unlock requests by weekday",
+      averageTimeToCancel: "On average, you cancel after {{delay}}",
+      logTampered:
+        "The access history has been altered outside of Lockbox. These statistics may be incomplete.",
+      logTruncated: "Entries are missing from the access history.",
+      logBrokenAt: "First altered entry: #{{id}}",
     },
     about: {
       title: "About",
@@ -386,6 +408,190 @@ export const translations = {
       filterTitle: "Tags",
       all: "All tags",
       noTags: "No tags yet",
+      manageTitle: "Manage tags",
+      manageDesc:
+        "Rename a tag on every lockbox at once. Renaming to an existing tag merges them.",
+      rename: "Tag name",
+      usage: "{{count}} lockbox(es)",
+      unused: "Unused",
+      merge: "Merge",
+      mergeConfirm: "\"{{target}}\" already exists. Merge \"{{source}}\" into it?",
+      delete: "Delete",
+      deleteConfirm: "Remove \"{{name}}\" from {{count}} lockbox(es) and delete it?",
+    },
+    categories: {
+      manageTitle: "Manage categories",
+      manageDesc:
+        "Categories can carry default policies for new lockboxes and a minimum unlock delay their lockboxes can never go below.",
+      new: "New category",
+      edit: "Edit",
+      delete: "Delete",
+      deleteConfirm: "Delete \"{{name}}\"? Its {{count}} lockbox(es) will become uncategorized.",
+      moveUp: "Move up",
+      moveDown: "Move down",
+      name: "Name",
+      icon: "Icon",
+      color: "Color",
+      defaults: "Default policies (minutes)",
+      defaultsHint: "Applied to new lockboxes in this category. Leave empty for no default.",
+      unlockDelay: "Unlock delay",
+      relockDelay: "Relock delay",
+      penalty: "Cancellation penalty",
+      minUnlockDelay: "Minimum unlock delay",
+      minUnlockDelayHint: "Existing lockboxes below it are raised to it.",
+      checklist: "Reflection checklist (one item per line)",
+      noDefault: "None",
+      save: "Save",
+      minimumApplies: "Unlock delay of at least {{delay}} in this category.",
+    },
+    importPreview: {
+      title: "Import preview",
+      description:
+        "Nothing has been imported yet. Check what this file contains and choose what happens to lockboxes that already exist.",
+      status: {
+        new: "New",
+        conflict: "Already exists",
+        bad_signature: "Bad signature",
+        wrong_password: "Wrong password",
+        invalid: "Invalid",
+        outdated: "Outdated",
+      },
+      conflictExisting: "A lockbox with this name already exists; it will be skipped.",
+      conflictFile: "Appears earlier in the file; it can only be renamed or skipped.",
+      strategyLabel: "When a lockbox already exists",
+      strategyHelp: "Replace is refused when it would loosen a policy; merge keeps the stricter value of each.",
+      strategy: {
+        skip: "Skip",
+        rename: "Import a copy",
+        replace: "Replace",
+        merge: "Merge",
+      },
+      renamedTo: "Will be imported as “{{name}}”.",
+      replaces: "Will replace the existing lockbox.",
+      merges: "Will be merged into the existing lockbox, keeping the stricter policies.",
+      replaceRefused: "Cannot replace: would loosen {{fields}}. It will be skipped.",
+      invalidFields: "Rejected fields: {{fields}}",
+      outdated: "Exported before this lockbox's delays were raised, or from a file that cannot prove otherwise; importing it would shorten them again.",
+      partial:
+        "This file holds a selection of lockboxes. Lockboxes it does not mention are left as they are.",
+      foreign:
+        "Read from {{format}}: each entry gets the delays chosen for the import, and its content is encrypted under your master password.",
+      unsigned:
+        "{{count}} lockbox(es) were not signed by a trusted device, so where they come from cannot be checked.",
+      unnamed: "(no name)",
+      confirm: "Import {{count}} lockbox(es)",
+    },
+    foreignImport: {
+      button: "From another app",
+      title: "Import from another password manager",
+      description:
+        "Entries become lockboxes, locked, with the delays chosen here. You can review them before anything is imported.",
+      format: "File",
+      formats: {
+        kdbx: "KeePass database (KDBX 4)",
+        keepass_xml: "KeePass XML export",
+        bitwarden: "Bitwarden JSON export (unencrypted)",
+        csv: "CSV (browser, 1Password, LastPass…)",
+      },
+      folders: "Folders become",
+      folderMapping: {
+        category: "Categories",
+        tags: "Tags",
+        ignore: "Nothing (ignored)",
+      },
+      clearFileWarning:
+        "Exports of other apps hold your passwords in clear. Delete the file once the import is done.",
+      chooseFile: "Choose file",
+      databasePasswordTitle: "KeePass database",
+      databasePasswordBody: "Enter the master password of this database. Databases that need a key file cannot be imported.",
+      databasePassword: "Master password",
+    },
+    clearExport: {
+      button: "For another app",
+      title: "Export for another password manager",
+      description:
+        "Writes the content of your unlocked lockboxes in clear, in a format other password managers import. Locked lockboxes are left out, so no delay is skipped.",
+      format: "Format",
+      formats: {
+        keepass_xml: "KeePass XML",
+        csv: "CSV",
+        portable: "Portable export",
+      },
+      warning:
+        "Anyone who reads this file sees your secrets. Each exported lockbox is logged and counts as a view; delete the file once it is imported.",
+      chooseFile: "Export",
+      exported: "{{count}} lockbox(es) exported in clear",
+      skipped: "{{count}} locked lockbox(es) left out",
+      nothingUnlocked: "No unlocked lockbox to export. Unlock the ones you want to export first.",
+    },
+    autoBackup: {
+      title: "Automatic Backups",
+      description:
+        "Encrypted copies of the database, taken in the background and rotated. They restore on this installation only; use a full backup to move to another machine.",
+      enabled: "Take automatic backups",
+      directory: "Folder",
+      defaultDirectory: "Default folder, next to the database",
+      interval: "Frequency",
+      every: {
+        6: "Every 6 hours",
+        12: "Every 12 hours",
+        24: "Every day",
+        72: "Every 3 days",
+        168: "Every week",
+      },
+      keep_daily: "Days kept",
+      keep_weekly: "Weeks kept",
+      keep_monthly: "Months kept",
+      backUpNow: "Back up now",
+      created: "Backup taken.",
+      empty: "No automatic backup yet.",
+      size: "{{size}} KB",
+      restore: "Restore",
+      restoreTitle: "Restore this backup?",
+      restoreBody:
+        "The vault goes back to its state of {{date}}; the current state is backed up first. Lockboxes keep their current lock state and their strictest delays: a restore never unlocks anything.",
+      restored: "Backup restored: {{count}} lockbox(es).",
+    },
+    exportSelection: {
+      label: "Lockboxes to export",
+      scope: {
+        all: "All lockboxes",
+        lockboxes: "Chosen lockboxes",
+        category: "One category",
+        tags: "By tags",
+        since: "Changed since a date",
+      },
+      chooseCategory: "Choose a category",
+      tagMatchAny: "With any of these tags",
+      tagMatchAll: "With all of these tags",
+      note: "A partial export is marked as such; importing it leaves other lockboxes alone.",
+    },
+    folders: {
+      title: "Folders",
+      label: "Folder",
+      root: "No folder",
+      empty: "No folders yet",
+      expand: "Expand",
+      collapse: "Collapse",
+      newTitle: "New folder",
+      editTitle: "Folder",
+      name: "Name",
+      parent: "Inside",
+      create: "Create",
+      save: "Save",
+      newSubfolder: "New subfolder",
+      bulkTitle: "Whole folder",
+      bulkDesc:
+        "Applies to the {{count}} lockbox(es) in this folder and its subfolders, under the usual rules.",
+      relockAll: "Relock unlocked ({{count}})",
+      relocked: "{{count}} lockbox(es) relocked",
+      extendAll: "Extend all delays",
+      extendDesc:
+        "Add the same time to the unlock delay of the {{count}} lockbox(es) in \"{{name}}\" and its subfolders. This is permanent.",
+      extended: "Delay extended on {{count}} lockbox(es)",
+      delete: "Delete folder",
+      deleteConfirm:
+        "Delete \"{{name}}\"? Its lockboxes and subfolders move up one level.",
     },
     extendDelay: {
       title: "Extend unlock delay",
@@ -397,6 +603,26 @@ export const translations = {
       warning:
         "This will permanently increase the delay and cannot be reversed.",
     },
+    devices: {
+      title: "Trusted devices",
+      description:
+        "Exports are signed with this device's key. Another machine accepts them once it trusts that key, without asking for this master password to check them.",
+      thisDevice: "This device",
+      copyKey: "Copy public key",
+      copied: "Public key copied. Add it on the machines that import your exports.",
+      empty: "No other device trusted yet.",
+      name: "Device name",
+      publicKey: "Public key",
+      remove: "Stop trusting",
+      untrustedTitle: "Unknown device",
+      untrustedBody:
+        "This file was signed by a device you do not trust yet. Compare this fingerprint with the one shown in that device's settings before trusting it.",
+      trustAndContinue: "Trust and continue",
+      keyUnreadable:
+        "This device's signing key can no longer be read, so exports cannot be signed. Replace it, then trust the new key again on your other machines.",
+      replaceKey: "Create a new device key",
+      keyReplaced: "New device key created. Share its public key with the machines that import your exports.",
+    },
     settings: {
       title: "Settings",
       general: "General",
@@ -409,20 +635,47 @@ export const translations = {
       relockedSound: "Sound on relock",
       relockedSoundDesc: "Play a sound when a lockbox is automatically relocked.",
       exportData: "Export Lockboxes",
-      exportDesc: "Export all your lockboxes to a secure, encrypted file.",
+      exportDesc: "Export your lockboxes, or a selection of them, to a secure, encrypted file.",
       importData: "Import Lockboxes",
       importDesc: "Import lockboxes from a previously exported file.",
-    },
-    notifications: {
-      unlockedTitle: "Lockbox Unlocked",
-      unlockedBody: '"{{name}}" is now unlocked and accessible.',
-      scheduledTitle: "Scheduled Unlock",
-      scheduledBody: '"{{name}}" is now unlocked.',
-      relockedTitle: "Lockbox Relocked",
-      relockedBody: '"{{name}}" has been automatically relocked.',
-      tamperTitle: "Clock Tamper Detected",
-      tamperBody:
-        "System clock manipulation detected. Active lockboxes have been relocked.",
+      backupData: "Full Backup",
+      backupDesc:
+        "A signed copy of the whole vault: lock states and running countdowns, schedules, emergency codes, categories, folders, settings and the access history.",
+      createBackup: "Create backup",
+      restoreBackup: "Restore backup",
+      restoreBackupNote:
+        "A backup can only be restored into an empty vault. Running countdowns resume with at least the time they had left.",
+      backupCreated: "Backup saved.",
+      backupRestored: "Backup restored: {{count}} lockbox(es).",
+      backupOutdated:
+        "Left out, as their delays were raised after this backup: {{names}}.",
+      portableExport: "Portable export",
+      portableExportNote:
+        "A portable export is protected by a passphrase of its own, so a new vault can import it without this master password.",
+      portableExportBody:
+        "Every unlocked lockbox is re-encrypted under this passphrase (Argon2id, AES-256-GCM); locked ones are left out, so no delay is skipped. Each exported lockbox counts as a view. Anyone with the file and the passphrase can read its contents: choose a long passphrase and keep it apart from the file.",
+      portableExportConfirm: "Export",
+      portableExported: "Portable export saved.",
+      portableImportTitle: "Passphrase-protected file",
+      portableImportBody: "This is a portable export. Enter the passphrase it was exported with.",
+      passphrase: "Passphrase",
+      passphraseConfirm: "Confirm passphrase",
+      passphraseHelp: "At least {{min}} characters.",
+      passphraseMismatch: "The passphrases do not match.",
+      historyRetention: "Access History Retention",
+      historyRetentionDesc:
+        "Older events are summarized by month so statistics are kept, then removed.",
+      retentionForever: "Keep everything",
+      retention3Months: "3 months",
+      retention6Months: "6 months",
+      retention1Year: "1 year",
+      retention2Years: "2 years",
+      archiveHistory: "Archive removed events",
+      archiveHistoryDesc:
+        "Save removed events to an encrypted file next to the database.",
+      compactNow: "Apply now",
+      compactedCount: "{{count}} events summarized and removed.",
+      compactedNothing: "No events older than the retention period.",
     },
   },
   fr: {
@@ -666,6 +919,14 @@ export const translations = {
       penaltyDelay: "Délai de pénalité",
       penaltyDelayHint:
         "Temps ajouté au délai de déverrouillage à chaque abandon du décompte",
+      // View limit
+      viewLimitSection: "Limite de consultations",
+      viewLimitSectionHint:
+        "Reverrouille automatiquement après un certain nombre d'affichages du contenu",
+      viewLimitEnabled: "Limiter les consultations par déverrouillage",
+      viewLimitCount: "Consultations maximum",
+      viewLimitCountHint:
+        "La lockbox se reverrouille dès que le contenu a été affiché ce nombre de fois pendant un même déverrouillage",
       // Panic code
       panicSection: "Code d'accès d'urgence",
       panicSectionHint:
@@ -741,6 +1002,11 @@ export const translations = {
       scheduled_unlock_completed: "Déverrouillage planifié déclenché",
       auto_relocked: "Reverrouillage automatique",
       field_updated: "Paramètres modifiés",
+      content_viewed: "Contenu consulté",
+      content_copied: "Contenu copié",
+      content_exported: "Contenu exporté en clair",
+      view_limit_relocked: "Reverrouillée après la limite de consultations",
+      snapshot_restored: "Restaurée depuis une sauvegarde automatique",
     },
     stats: {
       title: "Statistiques d'auto-contrôle",
@@ -760,6 +1026,15 @@ export const translations = {
       streakDay: "1 jour sans accès",
       streakDays: "{{days}} jours sans accès",
       streakNever: "∞ — Jamais accédé",
+      longestStreak: "Plus longue série : {{days}} jours",
+      patterns: "Tendances",
+      requestsByHour: "Demandes de déverrouillage par heure",
+      requestsByWeekday: "Demandes de déverrouillage par jour",
+      averageTimeToCancel: "En moyenne, vous annulez après {{delay}}",
+      logTampered:
+        "L'historique des accès a été modifié en dehors de Lockbox. Ces statistiques peuvent être incomplètes.",
+      logTruncated: "Des entrées manquent dans l'historique des accès.",
+      logBrokenAt: "Première entrée altérée : n°{{id}}",
     },
     about: {
       title: "À propos",
@@ -826,6 +1101,190 @@ export const translations = {
       filterTitle: "Tags",
       all: "Tous les tags",
       noTags: "Aucun tag pour l'instant",
+      manageTitle: "Gérer les tags",
+      manageDesc:
+        "Renommez un tag sur toutes les lockboxes à la fois. Renommer vers un tag existant les fusionne.",
+      rename: "Nom du tag",
+      usage: "{{count}} lockbox(es)",
+      unused: "Inutilisé",
+      merge: "Fusionner",
+      mergeConfirm: "« {{target}} » existe déjà. Y fusionner « {{source}} » ?",
+      delete: "Supprimer",
+      deleteConfirm: "Retirer « {{name}} » de {{count}} lockbox(es) et le supprimer ?",
+    },
+    categories: {
+      manageTitle: "Gérer les catégories",
+      manageDesc:
+        "Une catégorie peut définir des politiques par défaut pour les nouvelles lockboxes et un délai de déverrouillage minimum que ses lockboxes ne peuvent jamais descendre sous.",
+      new: "Nouvelle catégorie",
+      edit: "Modifier",
+      delete: "Supprimer",
+      deleteConfirm: "Supprimer « {{name}} » ? Ses {{count}} lockbox(es) n'auront plus de catégorie.",
+      moveUp: "Monter",
+      moveDown: "Descendre",
+      name: "Nom",
+      icon: "Icône",
+      color: "Couleur",
+      defaults: "Politiques par défaut (minutes)",
+      defaultsHint: "Appliquées aux nouvelles lockboxes de cette catégorie. Laissez vide pour aucune valeur par défaut.",
+      unlockDelay: "Délai de déverrouillage",
+      relockDelay: "Délai de reverrouillage",
+      penalty: "Pénalité d'annulation",
+      minUnlockDelay: "Délai de déverrouillage minimum",
+      minUnlockDelayHint: "Les lockboxes existantes en dessous y sont relevées.",
+      checklist: "Checklist de réflexion (un élément par ligne)",
+      noDefault: "Aucun",
+      save: "Enregistrer",
+      minimumApplies: "Délai de déverrouillage d'au moins {{delay}} dans cette catégorie.",
+    },
+    importPreview: {
+      title: "Aperçu de l'import",
+      description:
+        "Rien n'a encore été importé. Vérifiez le contenu de ce fichier et choisissez le sort des lockboxes qui existent déjà.",
+      status: {
+        new: "Nouvelle",
+        conflict: "Existe déjà",
+        bad_signature: "Signature invalide",
+        wrong_password: "Mauvais mot de passe",
+        invalid: "Invalide",
+        outdated: "Périmée",
+      },
+      conflictExisting: "Une lockbox porte déjà ce nom ; elle sera ignorée.",
+      conflictFile: "Apparaît plus haut dans le fichier ; elle peut seulement être renommée ou ignorée.",
+      strategyLabel: "Quand une lockbox existe déjà",
+      strategyHelp: "Le remplacement est refusé s'il assouplit une règle ; la fusion garde la valeur la plus stricte de chacune.",
+      strategy: {
+        skip: "Ignorer",
+        rename: "Importer une copie",
+        replace: "Remplacer",
+        merge: "Fusionner",
+      },
+      renamedTo: "Sera importée sous le nom « {{name}} ».",
+      replaces: "Remplacera la lockbox existante.",
+      merges: "Sera fusionnée avec la lockbox existante, en gardant les règles les plus strictes.",
+      replaceRefused: "Remplacement impossible : assouplirait {{fields}}. Elle sera ignorée.",
+      invalidFields: "Champs refusés : {{fields}}",
+      outdated: "Exportée avant l'allongement des délais de cette lockbox, ou d'un fichier qui ne peut prouver le contraire ; l'importer les raccourcirait à nouveau.",
+      partial:
+        "Ce fichier contient une sélection de lockboxes. Celles qu'il ne mentionne pas restent inchangées.",
+      foreign:
+        "Lu depuis {{format}} : chaque entrée reçoit les délais choisis pour l'import, et son contenu est chiffré avec votre mot de passe maître.",
+      unsigned:
+        "{{count}} lockbox(es) n'ont pas été signées par un appareil de confiance ; leur provenance ne peut donc pas être vérifiée.",
+      unnamed: "(sans nom)",
+      confirm: "Importer {{count}} lockbox(es)",
+    },
+    foreignImport: {
+      button: "Depuis une autre app",
+      title: "Importer depuis un autre gestionnaire de mots de passe",
+      description:
+        "Les entrées deviennent des lockboxes, verrouillées, avec les délais choisis ici. Vous pourrez les vérifier avant tout import.",
+      format: "Fichier",
+      formats: {
+        kdbx: "Base KeePass (KDBX 4)",
+        keepass_xml: "Export XML de KeePass",
+        bitwarden: "Export JSON de Bitwarden (non chiffré)",
+        csv: "CSV (navigateur, 1Password, LastPass…)",
+      },
+      folders: "Les dossiers deviennent",
+      folderMapping: {
+        category: "Des catégories",
+        tags: "Des tags",
+        ignore: "Rien (ignorés)",
+      },
+      clearFileWarning:
+        "Les exports des autres apps contiennent vos mots de passe en clair. Supprimez le fichier une fois l'import terminé.",
+      chooseFile: "Choisir le fichier",
+      databasePasswordTitle: "Base KeePass",
+      databasePasswordBody: "Saisissez le mot de passe maître de cette base. Les bases qui demandent un fichier clé ne peuvent pas être importées.",
+      databasePassword: "Mot de passe maître",
+    },
+    clearExport: {
+      button: "Pour une autre app",
+      title: "Exporter pour un autre gestionnaire de mots de passe",
+      description:
+        "Écrit le contenu de vos lockboxes déverrouillées en clair, dans un format que les autres gestionnaires importent. Les lockboxes verrouillées sont ignorées : aucun délai n'est contourné.",
+      format: "Format",
+      formats: {
+        keepass_xml: "XML KeePass",
+        csv: "CSV",
+        portable: "Export portable",
+      },
+      warning:
+        "Quiconque lit ce fichier voit vos secrets. Chaque lockbox exportée est journalisée et compte comme une consultation ; supprimez le fichier une fois importé.",
+      chooseFile: "Exporter",
+      exported: "{{count}} lockbox(es) exportée(s) en clair",
+      skipped: "{{count}} lockbox(es) verrouillée(s) ignorée(s)",
+      nothingUnlocked: "Aucune lockbox déverrouillée à exporter. Déverrouillez d'abord celles à exporter.",
+    },
+    autoBackup: {
+      title: "Sauvegardes automatiques",
+      description:
+        "Copies chiffrées de la base, prises en arrière-plan et renouvelées. Elles ne se restaurent que sur cette installation ; utilisez une sauvegarde complète pour changer de machine.",
+      enabled: "Faire des sauvegardes automatiques",
+      directory: "Dossier",
+      defaultDirectory: "Dossier par défaut, à côté de la base",
+      interval: "Fréquence",
+      every: {
+        6: "Toutes les 6 heures",
+        12: "Toutes les 12 heures",
+        24: "Tous les jours",
+        72: "Tous les 3 jours",
+        168: "Toutes les semaines",
+      },
+      keep_daily: "Jours conservés",
+      keep_weekly: "Semaines conservées",
+      keep_monthly: "Mois conservés",
+      backUpNow: "Sauvegarder maintenant",
+      created: "Sauvegarde effectuée.",
+      empty: "Aucune sauvegarde automatique pour l'instant.",
+      size: "{{size}} Ko",
+      restore: "Restaurer",
+      restoreTitle: "Restaurer cette sauvegarde ?",
+      restoreBody:
+        "Le coffre revient à son état du {{date}} ; l'état actuel est sauvegardé d'abord. Les lockboxes gardent leur état de verrouillage actuel et leurs délais les plus stricts : une restauration ne déverrouille rien.",
+      restored: "Sauvegarde restaurée : {{count}} lockbox(es).",
+    },
+    exportSelection: {
+      label: "Lockboxes à exporter",
+      scope: {
+        all: "Toutes les lockboxes",
+        lockboxes: "Lockboxes choisies",
+        category: "Une catégorie",
+        tags: "Par tags",
+        since: "Modifiées depuis une date",
+      },
+      chooseCategory: "Choisir une catégorie",
+      tagMatchAny: "Avec l'un de ces tags",
+      tagMatchAll: "Avec tous ces tags",
+      note: "Un export partiel est signalé comme tel ; l'importer laisse les autres lockboxes intactes.",
+    },
+    folders: {
+      title: "Dossiers",
+      label: "Dossier",
+      root: "Aucun dossier",
+      empty: "Aucun dossier pour l'instant",
+      expand: "Déplier",
+      collapse: "Replier",
+      newTitle: "Nouveau dossier",
+      editTitle: "Dossier",
+      name: "Nom",
+      parent: "Dans",
+      create: "Créer",
+      save: "Enregistrer",
+      newSubfolder: "Nouveau sous-dossier",
+      bulkTitle: "Tout le dossier",
+      bulkDesc:
+        "S'applique aux {{count}} lockbox(es) de ce dossier et de ses sous-dossiers, selon les règles habituelles.",
+      relockAll: "Reverrouiller les ouvertes ({{count}})",
+      relocked: "{{count}} lockbox(es) reverrouillée(s)",
+      extendAll: "Augmenter tous les délais",
+      extendDesc:
+        "Ajoute le même temps au délai de déverrouillage des {{count}} lockbox(es) de « {{name}} » et de ses sous-dossiers. C'est définitif.",
+      extended: "Délai augmenté sur {{count}} lockbox(es)",
+      delete: "Supprimer le dossier",
+      deleteConfirm:
+        "Supprimer « {{name}} » ? Ses lockboxes et sous-dossiers remontent d'un niveau.",
     },
     extendDelay: {
       title: "Augmenter le délai de déverrouillage",
@@ -837,6 +1296,26 @@ export const translations = {
       warning:
         "Cette action augmentera définitivement le délai et ne peut pas être annulée.",
     },
+    devices: {
+      title: "Appareils de confiance",
+      description:
+        "Les exports sont signés avec la clé de cet appareil. Une autre machine les accepte dès qu'elle fait confiance à cette clé, sans demander ce mot de passe maître pour les vérifier.",
+      thisDevice: "Cet appareil",
+      copyKey: "Copier la clé publique",
+      copied: "Clé publique copiée. Ajoutez-la sur les machines qui importent vos exports.",
+      empty: "Aucun autre appareil de confiance pour l'instant.",
+      name: "Nom de l'appareil",
+      publicKey: "Clé publique",
+      remove: "Ne plus faire confiance",
+      untrustedTitle: "Appareil inconnu",
+      untrustedBody:
+        "Ce fichier a été signé par un appareil auquel vous ne faites pas encore confiance. Comparez cette empreinte avec celle affichée dans les réglages de cet appareil avant de lui faire confiance.",
+      trustAndContinue: "Faire confiance et continuer",
+      keyUnreadable:
+        "La clé de signature de cet appareil ne peut plus être lue : les exports ne peuvent pas être signés. Remplacez-la, puis faites de nouveau confiance à la nouvelle clé sur vos autres machines.",
+      replaceKey: "Créer une nouvelle clé d'appareil",
+      keyReplaced: "Nouvelle clé d'appareil créée. Partagez sa clé publique avec les machines qui importent vos exports.",
+    },
     settings: {
       title: "Paramètres",
       general: "Général",
@@ -851,21 +1330,48 @@ export const translations = {
         "Jouer un son lorsqu'une lockbox est automatiquement reverrouillée.",
       exportData: "Exporter les lockboxes",
       exportDesc:
-        "Exportez toutes vos lockboxes dans un fichier sécurisé et chiffré.",
+        "Exportez vos lockboxes, ou une sélection d'entre elles, dans un fichier sécurisé et chiffré.",
       importData: "Importer des lockboxes",
       importDesc:
         "Importez des lockboxes depuis un fichier précédemment exporté.",
-    },
-    notifications: {
-      unlockedTitle: "Lockbox Déverrouillée",
-      unlockedBody: '"{{name}}" est maintenant déverrouillée et accessible.',
-      scheduledTitle: "Déverrouillage Planifié",
-      scheduledBody: '"{{name}}" est maintenant déverrouillée.',
-      relockedTitle: "Lockbox Reverrouillée",
-      relockedBody: '"{{name}}" a été automatiquement reverrouillée.',
-      tamperTitle: "Manipulation d'Horloge Détectée",
-      tamperBody:
-        "Une manipulation de l'horloge système a été détectée. Les lockboxes actives ont été reverrouillées.",
+      backupData: "Sauvegarde complète",
+      backupDesc:
+        "Une copie signée de tout le coffre : états de verrouillage et comptes à rebours en cours, planifications, codes d'urgence, catégories, dossiers, réglages et historique des accès.",
+      createBackup: "Créer une sauvegarde",
+      restoreBackup: "Restaurer une sauvegarde",
+      restoreBackupNote:
+        "Une sauvegarde ne peut être restaurée que dans un coffre vide. Les comptes à rebours en cours reprennent avec au moins le temps qu'il leur restait.",
+      backupCreated: "Sauvegarde enregistrée.",
+      backupRestored: "Sauvegarde restaurée : {{count}} lockbox(es).",
+      backupOutdated:
+        "Non restaurées, car leurs délais ont été allongés après cette sauvegarde : {{names}}.",
+      portableExport: "Export portable",
+      portableExportNote:
+        "Un export portable est protégé par sa propre phrase de passe : un nouveau coffre peut l'importer sans ce mot de passe maître.",
+      portableExportBody:
+        "Chaque lockbox déverrouillée est rechiffrée sous cette phrase de passe (Argon2id, AES-256-GCM) ; les lockboxes verrouillées sont ignorées, aucun délai n'est contourné. Chaque lockbox exportée compte comme une consultation. Quiconque possède le fichier et la phrase de passe peut lire ses contenus : choisissez une phrase longue et conservez-la à part du fichier.",
+      portableExportConfirm: "Exporter",
+      portableExported: "Export portable enregistré.",
+      portableImportTitle: "Fichier protégé par une phrase de passe",
+      portableImportBody: "Ceci est un export portable. Saisissez la phrase de passe choisie à l'export.",
+      passphrase: "Phrase de passe",
+      passphraseConfirm: "Confirmer la phrase de passe",
+      passphraseHelp: "Au moins {{min}} caractères.",
+      passphraseMismatch: "Les phrases de passe ne correspondent pas.",
+      historyRetention: "Conservation de l'historique des accès",
+      historyRetentionDesc:
+        "Les événements plus anciens sont résumés par mois pour conserver les statistiques, puis supprimés.",
+      retentionForever: "Tout conserver",
+      retention3Months: "3 mois",
+      retention6Months: "6 mois",
+      retention1Year: "1 an",
+      retention2Years: "2 ans",
+      archiveHistory: "Archiver les événements supprimés",
+      archiveHistoryDesc:
+        "Enregistrer les événements supprimés dans un fichier chiffré à côté de la base de données.",
+      compactNow: "Appliquer maintenant",
+      compactedCount: "{{count}} événements résumés et supprimés.",
+      compactedNothing: "Aucun événement plus ancien que la durée de conservation.",
     },
   },
 } as const;
@@ -1,5 +1,6 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
+import { describeError } from "./lockboxStore";
 
 interface AuthState {
   isAuthenticated: boolean;
@@ -27,7 +28,7 @@ export const useAuthStore = create<AuthState>((set) => ({
       const isSet = await invoke<boolean>("is_master_password_set");
       set({ isMasterPasswordSet: isSet, isLoading: false });
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
     }
   },
 
@@ -41,7 +42,7 @@ export const useAuthStore = create<AuthState>((set) => ({
         isLoading: false,
       });
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
       throw error;
     }
   },
@@ -59,7 +60,7 @@ export const useAuthStore = create<AuthState>((set) => ({
       }
       return isValid;
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
       return false;
     }
   },
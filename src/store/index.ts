@@ -1,4 +1,4 @@
-export { useLockboxStore, useFilteredLockboxes } from './lockboxStore';
+export { useLockboxStore, useFilteredLockboxes, describeError } from './lockboxStore';
 export { useAuthStore } from './authStore';
 export { useThemeStore } from './themeStore';
 export { useSettingsStore } from './settingsStore';
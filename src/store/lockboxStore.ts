@@ -6,40 +6,43 @@ import {
   sendNotification,
 } from "@tauri-apps/plugin-notification";
 import { useMemo } from "react";
-import type { Lockbox, CreateLockboxInput, AccessLogEntry } from "../types";
-import { parseTags } from "../types";
-import { useLocaleStore } from "../i18n/localeStore";
-import { translations } from "../i18n/translations";
+import type {
+  Lockbox,
+  LockboxDetail,
+  CreateLockboxInput,
+  AccessLogEntry,
+  AccessLogPage,
+  AccessLogQuery,
+  AccessLogVerification,
+  StatsReport,
+  RetentionPolicy,
+  CompactionReport,
+  BackupPolicy,
+  BackupInfo,
+  TagUsage,
+  Category,
+  CategoryInput,
+  Folder,
+  DeviceIdentity,
+  TrustedDevice,
+} from "../types";
+import { parseTags, isLockboxError } from "../types";
 import { useSettingsStore } from "./settingsStore";
 import { playUnlockedSound, playRelockedSound } from "../utils/notificationSound";
+import { folderSubtree } from "../utils/folders";
 
-function getTranslation(key: string, vars?: Record<string, string>): string {
-  const locale = useLocaleStore.getState().locale;
-  const keys = key.split(".");
-
-  let current: any = translations[locale];
-  for (const k of keys) {
-    if (current == null) break;
-    current = current[k];
-  }
-
-  let result = typeof current === "string" ? current : undefined;
-  if (!result) {
-    current = translations.en;
-    for (const k of keys) {
-      if (current == null) break;
-      current = (current as any)[k];
-    }
-    result = typeof current === "string" ? current : key;
-  }
-
-  if (vars && result) {
-    return result.replace(/\{\{(\w+)\}\}/g, (_, k) =>
-      String(vars[k] ?? `{{${k}}}`),
-    );
-  }
+/** Message of a backend `LockboxError`, already localized by the backend catalog */
+export function describeError(error: unknown): string {
+  return isLockboxError(error) ? error.message : String(error);
+}
 
-  return result || key;
+/** Localized notification text from the backend catalog */
+async function notify(kind: string, name?: string): Promise<void> {
+  const { title, body } = await invoke<{ title: string; body: string }>(
+    "localize_notification",
+    { kind, name: name ?? null },
+  );
+  sendNotification({ title, body });
 }
 
 interface LockboxState {
@@ -48,12 +51,19 @@ interface LockboxState {
   isLoading: boolean;
   error: string | null;
   searchQuery: string;
+  /** Ids ranked by the backend search for `searchQuery`; null until it answers */
+  searchResults: number[] | null;
   selectedCategory: string | null;
   selectedTag: string | null;
+  tags: TagUsage[];
+  categories: Category[];
+  folders: Folder[];
+  /** Folder whose subtree the list shows; null shows every folder */
+  selectedFolder: number | null;
 
   // Actions
   fetchLockboxes: () => Promise<void>;
-  fetchLockboxDecrypted: (id: number) => Promise<Lockbox | null>;
+  fetchLockboxDecrypted: (id: number) => Promise<LockboxDetail | null>;
   createLockbox: (input: CreateLockboxInput) => Promise<Lockbox>;
   updateLockbox: (
     id: number,
@@ -68,39 +78,97 @@ interface LockboxState {
   ) => Promise<Lockbox>;
   usePanicCode: (id: number, code: string) => Promise<Lockbox | null>;
   resetPanicCode: (id: number, newCode?: string) => Promise<Lockbox>;
+  recordContentCopy: (id: number) => Promise<void>;
   getAccessLog: (lockboxId: number) => Promise<AccessLogEntry[]>;
-  getGlobalAccessLog: () => Promise<AccessLogEntry[]>;
+  queryAccessLog: (query: AccessLogQuery) => Promise<AccessLogPage | null>;
+  verifyAccessLog: () => Promise<AccessLogVerification | null>;
+  getStatistics: () => Promise<StatsReport | null>;
+  fetchTags: () => Promise<void>;
+  renameTag: (name: string, newName: string) => Promise<void>;
+  mergeTags: (sources: string[], target: string) => Promise<void>;
+  deleteTag: (name: string) => Promise<void>;
+  fetchCategories: () => Promise<void>;
+  createCategory: (input: CategoryInput) => Promise<Category>;
+  updateCategory: (id: number, input: CategoryInput) => Promise<Category>;
+  reorderCategories: (ids: number[]) => Promise<void>;
+  deleteCategory: (id: number) => Promise<void>;
+  fetchFolders: () => Promise<void>;
+  createFolder: (name: string, parentId: number | null) => Promise<Folder>;
+  renameFolder: (id: number, name: string) => Promise<Folder>;
+  moveFolder: (id: number, parentId: number | null) => Promise<Folder>;
+  deleteFolder: (id: number) => Promise<void>;
+  moveLockboxes: (ids: number[], folderId: number | null) => Promise<void>;
+  relockFolder: (id: number) => Promise<Lockbox[]>;
+  extendFolderDelays: (id: number, additionalSeconds: number) => Promise<Lockbox[]>;
+  getRetentionPolicy: () => Promise<RetentionPolicy | null>;
+  setRetentionPolicy: (policy: RetentionPolicy) => Promise<void>;
+  compactAccessLog: () => Promise<CompactionReport>;
+  getBackupPolicy: () => Promise<BackupPolicy>;
+  setBackupPolicy: (policy: BackupPolicy) => Promise<void>;
+  listBackups: () => Promise<BackupInfo[]>;
+  runBackupNow: () => Promise<BackupInfo>;
+  restoreAutoBackup: (fileName: string) => Promise<number>;
+  getDeviceIdentity: () => Promise<DeviceIdentity>;
+  resetDeviceKey: () => Promise<DeviceIdentity>;
+  listTrustedDevices: () => Promise<TrustedDevice[]>;
+  trustDevice: (publicKey: string, name: string) => Promise<TrustedDevice[]>;
+  removeTrustedDevice: (publicKey: string) => Promise<TrustedDevice[]>;
   relockLockbox: (id: number) => Promise<Lockbox>;
   selectLockbox: (lockbox: Lockbox | null) => void;
   setSearchQuery: (query: string) => void;
+  runSearch: () => Promise<void>;
   setSelectedCategory: (category: string | null) => void;
   setSelectedTag: (tag: string | null) => void;
+  setSelectedFolder: (folder: number | null) => void;
   checkAndUpdateStates: () => Promise<void>;
   clearError: () => void;
 }
 
+/** State changes swapping in the given fresh copies of lockboxes */
+function replaceLockboxes(state: LockboxState, updated: Lockbox[]) {
+  const byId = new Map(updated.map((lb) => [lb.id, lb]));
+  return {
+    lockboxes: state.lockboxes.map((lb) => byId.get(lb.id) ?? lb),
+    selectedLockbox: state.selectedLockbox
+      ? (byId.get(state.selectedLockbox.id) ?? state.selectedLockbox)
+      : null,
+  };
+}
+
+const SEARCH_DEBOUNCE_MS = 150;
+let searchTimer: ReturnType<typeof setTimeout> | undefined;
+
 export const useLockboxStore = create<LockboxState>((set, get) => ({
   lockboxes: [],
   selectedLockbox: null,
   isLoading: false,
   error: null,
   searchQuery: "",
+  searchResults: null,
   selectedCategory: null,
   selectedTag: null,
+  tags: [],
+  categories: [],
+  folders: [],
+  selectedFolder: null,
 
   fetchLockboxes: async () => {
     set({ isLoading: true, error: null });
     try {
       const lockboxes = await invoke<Lockbox[]>("get_all_lockboxes");
       set({ lockboxes, isLoading: false });
+      // Metadata may have changed under the current search.
+      if (get().searchQuery) get().runSearch();
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
     }
   },
 
   fetchLockboxDecrypted: async (id: number) => {
     try {
-      const lockbox = await invoke<Lockbox | null>("get_lockbox", { id });
+      const lockbox = await invoke<LockboxDetail | null>("get_lockbox", {
+        id,
+      });
       return lockbox;
     } catch (error) {
       console.error("Failed to fetch decrypted lockbox:", error);
@@ -115,16 +183,17 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
         name: input.name,
         content: input.content,
         category: input.category || null,
-        unlockDelaySeconds: input.unlock_delay_seconds,
-        relockDelaySeconds: input.relock_delay_seconds,
-        reflectionEnabled: input.reflection_enabled ?? false,
+        unlockDelaySeconds: input.unlock_delay_seconds ?? null,
+        relockDelaySeconds: input.relock_delay_seconds ?? null,
+        reflectionEnabled: input.reflection_enabled ?? null,
         reflectionMessage: input.reflection_message ?? null,
         reflectionChecklist: input.reflection_checklist ?? null,
-        penaltyEnabled: input.penalty_enabled ?? false,
-        penaltySeconds: input.penalty_seconds ?? 0,
+        penaltyEnabled: input.penalty_enabled ?? null,
+        penaltySeconds: input.penalty_seconds ?? null,
         panicCode: input.panic_code ?? null,
         scheduledUnlockAt: input.scheduled_unlock_at ?? null,
         tags: input.tags ?? null,
+        maxViewsPerUnlock: input.max_views_per_unlock ?? null,
       });
       set((state) => ({
         lockboxes: [...state.lockboxes, lockbox].sort((a, b) =>
@@ -134,7 +203,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
       throw error;
     }
   },
@@ -146,6 +215,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       clear_tags?: boolean;
       clear_reflection_message?: boolean;
       clear_reflection_checklist?: boolean;
+      clear_max_views_per_unlock?: boolean;
     },
   ) => {
     set({ isLoading: true, error: null });
@@ -169,6 +239,8 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
         scheduledUnlockAt: updates.scheduled_unlock_at,
         tags: updates.tags,
         clearTags: updates.clear_tags ?? false,
+        maxViewsPerUnlock: updates.max_views_per_unlock,
+        clearMaxViewsPerUnlock: updates.clear_max_views_per_unlock ?? false,
       });
       set((state) => ({
         lockboxes: state.lockboxes.map((lb) => (lb.id === id ? lockbox : lb)),
@@ -178,7 +250,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
       throw error;
     }
   },
@@ -194,7 +266,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
         isLoading: false,
       }));
     } catch (error) {
-      set({ error: String(error), isLoading: false });
+      set({ error: describeError(error), isLoading: false });
       throw error;
     }
   },
@@ -210,7 +282,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: describeError(error) });
       throw error;
     }
   },
@@ -226,7 +298,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: describeError(error) });
       throw error;
     }
   },
@@ -245,7 +317,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: describeError(error) });
       throw error;
     }
   },
@@ -266,7 +338,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }
       return lockbox;
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: describeError(error) });
       throw error;
     }
   },
@@ -285,29 +357,228 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: describeError(error) });
       throw error;
     }
   },
 
+  recordContentCopy: async (id: number) => {
+    try {
+      await invoke("record_content_copy", { id });
+    } catch (error) {
+      console.error("Failed to record content copy:", error);
+    }
+  },
+
   getAccessLog: async (lockboxId: number) => {
+    const page = await get().queryAccessLog({ lockbox_ids: [lockboxId], limit: 10 });
+    return page?.entries ?? [];
+  },
+
+  queryAccessLog: async (query: AccessLogQuery) => {
+    try {
+      return await invoke<AccessLogPage>("query_access_log", { query });
+    } catch (error) {
+      console.error("Failed to query access log:", error);
+      return null;
+    }
+  },
+
+  verifyAccessLog: async () => {
     try {
-      return await invoke<AccessLogEntry[]>("get_access_log", { lockboxId });
+      return await invoke<AccessLogVerification>("verify_access_log");
     } catch (error) {
-      console.error("Failed to get access log:", error);
-      return [];
+      console.error("Failed to verify access log:", error);
+      return null;
+    }
+  },
+
+  getStatistics: async () => {
+    try {
+      const timezone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+      return await invoke<StatsReport>("get_statistics", { timezone });
+    } catch (error) {
+      console.error("Failed to get statistics:", error);
+      return null;
     }
   },
 
-  getGlobalAccessLog: async () => {
+  fetchTags: async () => {
     try {
-      return await invoke<AccessLogEntry[]>("get_global_access_log");
+      const tags = await invoke<TagUsage[]>("list_tags");
+      set({ tags });
     } catch (error) {
-      console.error("Failed to get global access log:", error);
-      return [];
+      console.error("Failed to list tags:", error);
     }
   },
 
+  renameTag: async (name: string, newName: string) => {
+    await invoke("rename_tag", { name, newName });
+    await Promise.all([get().fetchLockboxes(), get().fetchTags()]);
+    if (get().selectedTag === name) set({ selectedTag: newName });
+  },
+
+  mergeTags: async (sources: string[], target: string) => {
+    await invoke("merge_tags", { sources, target });
+    await Promise.all([get().fetchLockboxes(), get().fetchTags()]);
+    if (sources.includes(get().selectedTag ?? "")) set({ selectedTag: target });
+  },
+
+  deleteTag: async (name: string) => {
+    await invoke("delete_tag", { name });
+    await Promise.all([get().fetchLockboxes(), get().fetchTags()]);
+    if (get().selectedTag === name) set({ selectedTag: null });
+  },
+
+  fetchCategories: async () => {
+    try {
+      const categories = await invoke<Category[]>("list_categories");
+      set({ categories });
+    } catch (error) {
+      console.error("Failed to list categories:", error);
+    }
+  },
+
+  createCategory: async (input: CategoryInput) => {
+    const category = await invoke<Category>("create_category", { category: input });
+    await get().fetchCategories();
+    return category;
+  },
+
+  updateCategory: async (id: number, input: CategoryInput) => {
+    const previous = get().categories.find((c) => c.id === id);
+    const category = await invoke<Category>("update_category", { id, category: input });
+    // A rename or a raised floor changes lockboxes too
+    await Promise.all([get().fetchLockboxes(), get().fetchCategories()]);
+    if (previous && get().selectedCategory === previous.name) set({ selectedCategory: category.name });
+    return category;
+  },
+
+  reorderCategories: async (ids: number[]) => {
+    await invoke("reorder_categories", { ids });
+    await get().fetchCategories();
+  },
+
+  deleteCategory: async (id: number) => {
+    const deleted = get().categories.find((c) => c.id === id);
+    await invoke("delete_category", { id });
+    await Promise.all([get().fetchLockboxes(), get().fetchCategories()]);
+    if (deleted && get().selectedCategory === deleted.name) set({ selectedCategory: null });
+  },
+
+  fetchFolders: async () => {
+    try {
+      const folders = await invoke<Folder[]>("list_folders");
+      set({ folders });
+    } catch (error) {
+      console.error("Failed to fetch folders:", error);
+    }
+  },
+
+  createFolder: async (name: string, parentId: number | null) => {
+    const folder = await invoke<Folder>("create_folder", { name, parentId });
+    await get().fetchFolders();
+    return folder;
+  },
+
+  renameFolder: async (id: number, name: string) => {
+    const folder = await invoke<Folder>("rename_folder", { id, name });
+    await get().fetchFolders();
+    return folder;
+  },
+
+  moveFolder: async (id: number, parentId: number | null) => {
+    const folder = await invoke<Folder>("move_folder", { id, parentId });
+    await get().fetchFolders();
+    return folder;
+  },
+
+  deleteFolder: async (id: number) => {
+    const parent = get().folders.find((f) => f.id === id)?.parent_id ?? null;
+    await invoke("delete_folder", { id });
+    await Promise.all([get().fetchLockboxes(), get().fetchFolders()]);
+    if (get().selectedFolder === id) set({ selectedFolder: parent });
+  },
+
+  moveLockboxes: async (ids: number[], folderId: number | null) => {
+    await invoke("move_lockboxes", { ids, folderId });
+    await Promise.all([get().fetchLockboxes(), get().fetchFolders()]);
+  },
+
+  relockFolder: async (id: number) => {
+    const relocked = await invoke<Lockbox[]>("relock_folder", { id });
+    set((state) => replaceLockboxes(state, relocked));
+    return relocked;
+  },
+
+  extendFolderDelays: async (id: number, additionalSeconds: number) => {
+    const extended = await invoke<Lockbox[]>("extend_folder_delays", {
+      id,
+      additionalSeconds,
+    });
+    set((state) => replaceLockboxes(state, extended));
+    return extended;
+  },
+
+  getRetentionPolicy: async () => {
+    try {
+      return await invoke<RetentionPolicy>("get_retention_policy");
+    } catch (error) {
+      console.error("Failed to get retention policy:", error);
+      return null;
+    }
+  },
+
+  setRetentionPolicy: async (policy: RetentionPolicy) => {
+    await invoke("set_retention_policy", { policy });
+  },
+
+  compactAccessLog: async () => {
+    return await invoke<CompactionReport>("compact_access_log");
+  },
+
+  getBackupPolicy: async () => {
+    return await invoke<BackupPolicy>("get_backup_policy");
+  },
+
+  setBackupPolicy: async (policy: BackupPolicy) => {
+    await invoke("set_backup_policy", { policy });
+  },
+
+  listBackups: async () => {
+    return await invoke<BackupInfo[]>("list_backups");
+  },
+
+  runBackupNow: async () => {
+    return await invoke<BackupInfo>("run_backup_now");
+  },
+
+  restoreAutoBackup: async (fileName: string) => {
+    const restored = await invoke<Lockbox[]>("restore_backup", { fileName });
+    await Promise.all([get().fetchLockboxes(), get().fetchTags(), get().fetchCategories(), get().fetchFolders()]);
+    return restored.length;
+  },
+
+  getDeviceIdentity: async () => {
+    return await invoke<DeviceIdentity>("get_device_identity");
+  },
+
+  resetDeviceKey: async () => {
+    return await invoke<DeviceIdentity>("reset_device_key");
+  },
+
+  listTrustedDevices: async () => {
+    return await invoke<TrustedDevice[]>("list_trusted_devices");
+  },
+
+  trustDevice: async (publicKey: string, name: string) => {
+    return await invoke<TrustedDevice[]>("trust_device", { publicKey, name });
+  },
+
+  removeTrustedDevice: async (publicKey: string) => {
+    return await invoke<TrustedDevice[]>("remove_trusted_device", { publicKey });
+  },
+
   relockLockbox: async (id: number) => {
     set({ error: null });
     try {
@@ -319,7 +590,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
       }));
       return lockbox;
     } catch (error) {
-      set({ error: String(error) });
+      set({ error: describeError(error) });
       throw error;
     }
   },
@@ -329,7 +600,25 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
   },
 
   setSearchQuery: (query: string) => {
-    set({ searchQuery: query });
+    set({ searchQuery: query, searchResults: null });
+    clearTimeout(searchTimer);
+    if (query.trim()) {
+      searchTimer = setTimeout(() => get().runSearch(), SEARCH_DEBOUNCE_MS);
+    }
+  },
+
+  runSearch: async () => {
+    const query = get().searchQuery;
+    if (!query.trim()) return;
+    try {
+      const results = await invoke<Lockbox[]>("search_lockboxes", { query });
+      // Drop answers to a query the user has since changed.
+      if (get().searchQuery === query) {
+        set({ searchResults: results.map((lb) => lb.id) });
+      }
+    } catch (error) {
+      console.error("Failed to search lockboxes:", error);
+    }
   },
 
   setSelectedCategory: (category: string | null) => {
@@ -340,6 +629,10 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
     set({ selectedTag: tag });
   },
 
+  setSelectedFolder: (folder: number | null) => {
+    set({ selectedFolder: folder });
+  },
+
   checkAndUpdateStates: async () => {
     try {
       const newLockboxes = await invoke<Lockbox[]>(
@@ -367,12 +660,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
               oldLb.unlock_timestamp &&
               !newLb.unlock_timestamp
             ) {
-              sendNotification({
-                title: getTranslation("notifications.unlockedTitle"),
-                body: getTranslation("notifications.unlockedBody", {
-                  name: newLb.name,
-                }),
-              });
+              await notify("unlocked", newLb.name);
               if (unlockedSoundEnabled) playUnlockedSound();
             }
             // 2. Scheduled Unlocked
@@ -382,12 +670,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
               oldLb.scheduled_unlock_at &&
               !newLb.scheduled_unlock_at
             ) {
-              sendNotification({
-                title: getTranslation("notifications.scheduledTitle"),
-                body: getTranslation("notifications.scheduledBody", {
-                  name: newLb.name,
-                }),
-              });
+              await notify("scheduled", newLb.name);
               if (unlockedSoundEnabled) playUnlockedSound();
             }
             // 3. Auto-relocked
@@ -397,12 +680,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
               oldLb.relock_timestamp &&
               !newLb.relock_timestamp
             ) {
-              sendNotification({
-                title: getTranslation("notifications.relockedTitle"),
-                body: getTranslation("notifications.relockedBody", {
-                  name: newLb.name,
-                }),
-              });
+              await notify("relocked", newLb.name);
               if (relockedSoundEnabled) playRelockedSound();
             }
             // 4. Tamper detected (was unlocking/scheduled/unlocked, now locked with no timestamps)
@@ -421,10 +699,7 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
           }
 
           if (tamperDetected) {
-            sendNotification({
-              title: getTranslation("notifications.tamperTitle"),
-              body: getTranslation("notifications.tamperBody"),
-            });
+            await notify("tamper");
           }
         }
       }
@@ -447,20 +722,37 @@ export const useLockboxStore = create<LockboxState>((set, get) => ({
 }));
 
 export const useFilteredLockboxes = () => {
-  const { lockboxes, searchQuery, selectedCategory, selectedTag } =
-    useLockboxStore(
-      useShallow((state) => ({
-        lockboxes: state.lockboxes,
-        searchQuery: state.searchQuery,
-        selectedCategory: state.selectedCategory,
-        selectedTag: state.selectedTag,
-      })),
-    );
+  const {
+    lockboxes,
+    searchQuery,
+    searchResults,
+    selectedCategory,
+    selectedTag,
+    folders,
+    selectedFolder,
+  } = useLockboxStore(
+    useShallow((state) => ({
+      lockboxes: state.lockboxes,
+      searchQuery: state.searchQuery,
+      searchResults: state.searchResults,
+      selectedCategory: state.selectedCategory,
+      selectedTag: state.selectedTag,
+      folders: state.folders,
+      selectedFolder: state.selectedFolder,
+    })),
+  );
 
   return useMemo(() => {
     let filtered = lockboxes;
 
-    if (searchQuery) {
+    if (searchQuery && searchResults) {
+      // Ranked backend results, best match first.
+      const byId = new Map(lockboxes.map((lb) => [lb.id, lb]));
+      filtered = searchResults
+        .map((id) => byId.get(id))
+        .filter((lb): lb is Lockbox => lb !== undefined);
+    } else if (searchQuery) {
+      // Until the backend answers, narrow the list locally.
       const query = searchQuery.toLowerCase();
       filtered = filtered.filter(
         (lb) =>
@@ -482,6 +774,21 @@ export const useFilteredLockboxes = () => {
       );
     }
 
+    if (selectedFolder !== null) {
+      const subtree = folderSubtree(folders, selectedFolder);
+      filtered = filtered.filter(
+        (lb) => lb.folder_id !== null && subtree.has(lb.folder_id),
+      );
+    }
+
     return filtered;
-  }, [lockboxes, searchQuery, selectedCategory, selectedTag]);
+  }, [
+    lockboxes,
+    searchQuery,
+    searchResults,
+    selectedCategory,
+    selectedTag,
+    folders,
+    selectedFolder,
+  ]);
 };
@@ -0,0 +1,15 @@
+/** Structured error returned by every backend command (see src-tauri/src/error.rs) */
+export interface LockboxError {
+  code: string;
+  params: Record<string, string | number>;
+  message: string;
+}
+
+export function isLockboxError(error: unknown): error is LockboxError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as LockboxError).code === 'string' &&
+    typeof (error as LockboxError).message === 'string'
+  );
+}
@@ -1 +1,3 @@
 export * from './lockbox';
+export * from './errors';
+export * from './transfer';
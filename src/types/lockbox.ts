@@ -1,7 +1,7 @@
+/** Lockbox metadata as returned by the backend; never carries content or hashes */
 export interface Lockbox {
   id: number;
   name: string;
-  content: string;
   category: string | null;
   is_locked: boolean;
   unlock_delay_seconds: number;
@@ -18,20 +18,31 @@ export interface Lockbox {
   penalty_enabled: boolean;
   penalty_seconds: number;
   // Panic code
-  panic_code_hash: string | null;
+  has_panic_code: boolean;
   panic_code_used: boolean;
   // Scheduled unlock
   scheduled_unlock_at: number | null;
   // Free tags
   tags: string | null; // JSON array e.g. '["urgent","work"]'
+  // View limit
+  max_views_per_unlock: number | null;
+  views_this_unlock: number;
+  // Folder holding the box; null at the root
+  folder_id: number | null;
+}
+
+/** Returned by `get_lockbox`: content is decrypted only while unlocked */
+export interface LockboxDetail extends Lockbox {
+  content: string | null;
 }
 
+/** Policies left out are taken from the category's defaults by the backend */
 export interface CreateLockboxInput {
   name: string;
   content: string;
   category?: string;
-  unlock_delay_seconds: number;
-  relock_delay_seconds: number;
+  unlock_delay_seconds?: number;
+  relock_delay_seconds?: number;
   reflection_enabled?: boolean;
   reflection_message?: string;
   reflection_checklist?: string; // JSON array as string
@@ -40,6 +51,7 @@ export interface CreateLockboxInput {
   panic_code?: string; // raw code, hashed backend-side
   scheduled_unlock_at?: number;
   tags?: string; // JSON array
+  max_views_per_unlock?: number;
 }
 
 export interface UpdateLockboxInput {
@@ -59,11 +71,191 @@ export interface UpdateLockboxInput {
   tags?: string;
 }
 
-export interface AccessLogEntry {
+/** Structured payloads of access log events, keyed by event type (version 1) */
+export interface AccessEventDetailsMap {
+  unlock_requested: { unlock_delay_seconds: number; unlock_at: number };
+  unlock_cancelled: {
+    penalty_seconds: number;
+    unlock_delay_seconds: number;
+    waited_seconds: number | null;
+  };
+  extend_delay: { additional_seconds: number; unlock_delay_seconds: number };
+  unlock_completed: { relock_delay_seconds: number };
+  scheduled_unlock_completed: { scheduled_at: number; relock_delay_seconds: number };
+  panic_used: { relock_delay_seconds: number };
+  relock_manual: Record<string, never>;
+  auto_relocked: Record<string, never>;
+  view_limit_relocked: { views: number };
+  content_viewed: { views_this_unlock: number; max_views_per_unlock: number | null };
+  content_copied: Record<string, never>;
+  content_exported: {
+    format: 'keepass_xml' | 'csv' | 'portable';
+    views_this_unlock: number;
+    max_views_per_unlock: number | null;
+  };
+  field_updated: { fields: string[] };
+  snapshot_restored: { created_at: number };
+  clock_tamper_detected: {
+    was_unlocked: boolean;
+    cancelled_unlock: boolean;
+    cancelled_schedule: boolean;
+  };
+}
+
+export type AccessEventType = keyof AccessEventDetailsMap;
+
+/** Versioned `details` payload; `null` for entries logged before details existed */
+export type AccessEventDetails<T extends AccessEventType> = (AccessEventDetailsMap[T] & { v: 1 }) | null;
+
+export type AccessLogEntry = {
+  [T in AccessEventType]: {
+    id: number;
+    lockbox_id: number;
+    event_type: T;
+    timestamp: number;
+    details: AccessEventDetails<T>;
+  };
+}[AccessEventType];
+
+export interface AccessLogQuery {
+  lockbox_ids?: number[];
+  event_types?: string[];
+  /** Inclusive lower bound, in milliseconds. */
+  from?: number;
+  /** Exclusive upper bound, in milliseconds. */
+  to?: number;
+  /** `next_cursor` of the previous page. */
+  cursor?: string;
+  limit?: number;
+  /** Also count the matching entries, which costs a pass over all of them. */
+  with_counts?: boolean;
+}
+
+export interface AccessLogPage {
+  entries: AccessLogEntry[];
+  next_cursor: string | null;
+  /** Entries matching the filters across all pages, when `with_counts` was asked. */
+  total: number | null;
+  counts_by_event: Record<string, number> | null;
+}
+
+export interface StatsEventCounts {
+  requests: number;
+  completions: number;
+  cancellations: number;
+  panic_uses: number;
+  extensions: number;
+}
+
+/** Self-control metrics computed by the backend in the requested timezone */
+export interface StatsMetrics {
+  all_time: StatsEventCounts;
+  this_month: StatsEventCounts;
+  cancellation_ratio: number | null;
+  /** Whole days since the last access; `null` if never accessed */
+  current_streak_days: number | null;
+  longest_streak_days: number;
+  last_access_at: number | null;
+  /** Unlock requests per local hour (0-23) */
+  requests_by_hour: number[];
+  /** Unlock requests per local weekday, Monday first */
+  requests_by_weekday: number[];
+  average_seconds_to_cancel: number | null;
+}
+
+export interface StatsReport {
+  timezone: string;
+  generated_at: number;
+  global: StatsMetrics;
+  lockboxes: (StatsMetrics & { lockbox_id: number })[];
+}
+
+/** Result of `verify_access_log` over the access log hash chain */
+export interface AccessLogVerification {
+  valid: boolean;
+  entries_checked: number;
+  anchor_valid: boolean;
+  first_broken_id: number | null;
+  truncated_start: boolean;
+  truncated_end: boolean;
+}
+
+/** A tag with the number of lockboxes carrying it */
+export interface TagUsage {
+  id: number;
+  name: string;
+  count: number;
+}
+
+/** Defaults applied to lockboxes created in a category; null means none */
+export interface CategoryPolicies {
+  unlock_delay_seconds: number | null;
+  relock_delay_seconds: number | null;
+  penalty_seconds: number | null;
+  reflection_checklist: string | null; // JSON array
+  /** Unlock delay floor for every lockbox in the category */
+  min_unlock_delay_seconds: number | null;
+}
+
+export interface Category extends CategoryPolicies {
   id: number;
-  lockbox_id: number;
-  event_type: 'unlock_requested' | 'unlock_completed' | 'unlock_cancelled' | 'panic_used' | 'extend_delay' | string;
-  timestamp: number;
+  name: string;
+  icon: string | null;
+  color: string | null;
+  position: number;
+  lockbox_count: number;
+}
+
+export type CategoryInput = Pick<Category, 'name' | 'icon' | 'color'> & CategoryPolicies;
+
+/** A node of the folder tree; `parent_id` is null at the root */
+export interface Folder {
+  id: number;
+  name: string;
+  parent_id: number | null;
+  /** Lockboxes directly in this folder, not in its subfolders */
+  lockbox_count: number;
+}
+
+/** Filter for `get_all_lockboxes` and selective exports; every criterion set must hold */
+export interface LockboxFilter {
+  ids?: number[];
+  category?: string;
+  /** Lockboxes with any or all of the tags */
+  tags?: string[];
+  tag_match?: 'any' | 'all';
+  /** Only lockboxes changed at or after this time, in milliseconds */
+  updated_since?: number;
+}
+
+/** Access log retention; `retention_days: null` keeps every raw event */
+export interface RetentionPolicy {
+  retention_days: number | null;
+  archive: boolean;
+}
+
+/** Automatic backups; `directory: null` uses the default one next to the database */
+export interface BackupPolicy {
+  enabled: boolean;
+  directory: string | null;
+  interval_hours: number;
+  keep_daily: number;
+  keep_weekly: number;
+  keep_monthly: number;
+}
+
+/** An automatic backup file, newest first in `list_backups` */
+export interface BackupInfo {
+  file_name: string;
+  created_at: number;
+  size_bytes: number;
+}
+
+export interface CompactionReport {
+  cutoff: number | null;
+  removed_entries: number;
+  aggregates_updated: number;
+  archive_file: string | null;
 }
 
 export interface ExportData {
@@ -114,17 +306,23 @@ export function parseTags(tags: string | null | undefined): string[] {
   }
 }
 
+/** Normalize a tag as typed: lowercase, spaces to dashes, other symbols dropped */
+export function normalizeTag(raw: string): string {
+  return raw.trim().toLowerCase().replace(/\s+/g, '-').replace(/[^a-z0-9-_]/g, '');
+}
+
 /** Serialize a string array into a JSON tags string for the DB */
 export function serializeTags(tags: string[]): string | undefined {
   return tags.length > 0 ? JSON.stringify(tags) : undefined;
 }
 
-export const CATEGORIES = [
-  'Passwords',
-  'Financial',
-  'Personal',
-  'Work',
-  'Social',
-  'Gaming',
-  'Other',
+export const CATEGORY_COLORS = [
+  '#6366f1',
+  '#3b82f6',
+  '#10b981',
+  '#f59e0b',
+  '#ef4444',
+  '#ec4899',
+  '#8b5cf6',
+  '#6b7280',
 ] as const;
@@ -0,0 +1,103 @@
+import type { Lockbox } from './lockbox';
+
+/** What importing one lockbox of a file does, or would do (see src-tauri/src/transfer.rs) */
+export type ImportStatus = 'new' | 'conflict' | 'bad_signature' | 'wrong_password' | 'invalid' | 'outdated';
+
+/** What to do with an imported lockbox whose name is already taken */
+export type ConflictStrategy = 'skip' | 'rename' | 'replace' | 'merge';
+
+/** Write an import performs for one lockbox */
+export type ImportAction = 'create' | 'rename' | 'replace' | 'merge' | 'skip';
+
+/** Strategy for every conflict, with overrides keyed by position in the file */
+export interface ImportStrategies {
+  default: ConflictStrategy;
+  overrides: Record<number, ConflictStrategy>;
+}
+
+/** A field rejected by backend validation */
+export interface FieldError {
+  field: string;
+  kind: string;
+  limit: number | null;
+}
+
+export interface ImportItem {
+  /** Position in the file */
+  index: number;
+  name: string;
+  category: string | null;
+  unlock_delay_seconds: number;
+  status: ImportStatus;
+  /** Set when a trusted device signed the file */
+  signed: boolean;
+  existing_id: number | null;
+  errors: FieldError[];
+  /** Strategy applied to a conflict */
+  strategy: ConflictStrategy | null;
+  action: ImportAction;
+  renamed_to: string | null;
+  /** Fields a replace would loosen; the replace is refused if any */
+  looser_fields: string[];
+  /** Set once `action` was carried out */
+  imported: boolean;
+}
+
+/** Per-lockbox report of `preview_import` and `import_lockboxes` */
+export interface ImportReport {
+  version: string;
+  exported_at: number;
+  /** The file holds a selection of lockboxes, not a whole vault */
+  partial: boolean;
+  /** Set when the file was read from another password manager's format */
+  source: ForeignFormat | null;
+  items: ImportItem[];
+}
+
+/** Outcome of `import_backup` */
+export interface RestoreReport {
+  lockboxes: Lockbox[];
+  /** Lockboxes left out because their delays were raised since the backup */
+  outdated: string[];
+}
+
+/** Files of other password managers an import can read (see src-tauri/src/foreign.rs) */
+export type ForeignFormat = 'keepass_xml' | 'kdbx' | 'bitwarden' | 'csv';
+
+/** What the folders of the source become */
+export type FolderMapping = 'category' | 'tags' | 'ignore';
+
+/** How the entries of a foreign file become lockboxes */
+export interface ForeignImport {
+  format: ForeignFormat;
+  unlock_delay_seconds: number;
+  relock_delay_seconds: number;
+  folders: FolderMapping;
+}
+
+/** Formats an export in clear can write */
+export type ClearFormat = 'keepass_xml' | 'csv';
+
+/** Result of `export_clear`: the file, and how many lockboxes it holds or left out as locked */
+export interface ClearExport {
+  data: string;
+  exported: number;
+  skipped_locked: number;
+}
+
+/** This machine's export signing identity */
+export interface DeviceIdentity {
+  public_key: string;
+  /** Short form for comparing keys by eye */
+  fingerprint: string;
+  /** False when the signing key can no longer be read and must be replaced */
+  can_sign: boolean;
+}
+
+/** Another machine whose exports are accepted */
+export interface TrustedDevice {
+  public_key: string;
+  name: string;
+  fingerprint: string;
+  added_at: number;
+}
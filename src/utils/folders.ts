@@ -0,0 +1,31 @@
+import type { Folder } from '../types';
+
+/** Ids of a folder and of every folder below it */
+export function folderSubtree(folders: Folder[], id: number): Set<number> {
+  const ids = new Set([id]);
+  let grew = true;
+  while (grew) {
+    grew = false;
+    for (const folder of folders) {
+      if (folder.parent_id !== null && ids.has(folder.parent_id) && !ids.has(folder.id)) {
+        ids.add(folder.id);
+        grew = true;
+      }
+    }
+  }
+  return ids;
+}
+
+/** Folders in tree order, each with its depth and its full "A / B / C" path */
+export function flattenFolders(folders: Folder[]): { folder: Folder; depth: number; path: string }[] {
+  const result: { folder: Folder; depth: number; path: string }[] = [];
+  const visit = (parentId: number | null, depth: number, prefix: string) => {
+    for (const folder of folders.filter((f) => f.parent_id === parentId)) {
+      const path = prefix ? `${prefix} / ${folder.name}` : folder.name;
+      result.push({ folder, depth, path });
+      visit(folder.id, depth + 1, path);
+    }
+  };
+  visit(null, 0, '');
+  return result;
+}